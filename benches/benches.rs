@@ -7,7 +7,6 @@ use morph_rs::{
 };
 use pprof::criterion::{Output, PProfProfiler};
 use pyo3::PyResult;
-use std::io::Read;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -17,10 +16,9 @@ fn benchmark_init(c: &mut Criterion) {
     let dict_path = "dict.opcorpora.xml";
 
     // Задаем Throughput в виде самого словаря для подсчета данных/секунду.
-    let bytes = std::fs::File::open(dict_path)
+    let bytes = std::fs::metadata(dict_path)
         .expect("Open dictionary file")
-        .bytes()
-        .count() as u64;
+        .len();
 
     let mut group = c.benchmark_group("mops init");
     group.sample_size(10);
@@ -47,10 +45,9 @@ fn benchmark(c: &mut Criterion) {
     let words = binding.lines();
 
     // Задаем Throughput для подсчета данных/секунду.
-    let bytes = std::fs::File::open("benches/data/words.txt")
+    let bytes = std::fs::metadata("benches/data/words.txt")
         .expect("Open text file")
-        .bytes()
-        .count() as u64;
+        .len();
 
     let mut group = c.benchmark_group("Rust 0.2.0. War&Peace words. Dictionary + Prefix Vanga");
     group.throughput(criterion::Throughput::Bytes(bytes));
@@ -80,6 +77,28 @@ fn benchmark(c: &mut Criterion) {
             })
         },
     );
+
+    group.bench_with_input(
+        BenchmarkId::new("parse_iter", 2),
+        &(&mops, words.clone()),
+        |b, (mops, words)| {
+            b.iter(|| {
+                for word in words.clone() {
+                    if let Ok(parses) = mops.parse_iter(word) {
+                        parses.for_each(|parse| {
+                            black_box(parse.ok());
+                        });
+                    }
+                }
+            })
+        },
+    );
+
+    group.bench_with_input(
+        BenchmarkId::new("parse_batch", 3),
+        &(&mops, words.clone()),
+        |b, (mops, words)| b.iter(|| black_box(mops.parse_batch(words.clone()))),
+    );
 }
 
 /// Функция, которая запускает бенчмарки по поиску всех форм слова.
@@ -91,10 +110,9 @@ fn benchmark_declension(c: &mut Criterion) {
     let words = binding.lines().take(100);
 
     // Задаем Throughput для подсчета данных/секунду.
-    let bytes = std::fs::File::open("benches/data/words.txt")
+    let bytes = std::fs::metadata("benches/data/words.txt")
         .expect("Open text file")
-        .bytes()
-        .count() as u64;
+        .len();
 
     let mut group = c.benchmark_group("Rust 0.2.0. Declension 1000 dictionary words");
     group.throughput(criterion::Throughput::Bytes(bytes));
@@ -124,10 +142,9 @@ fn benchmark_inflection(c: &mut Criterion) {
     let words = binding.lines();
 
     // Задаем Throughput для подсчета данных/секунду.
-    let bytes = std::fs::File::open("benches/data/inflect.txt")
+    let bytes = std::fs::metadata("benches/data/inflect.txt")
         .expect("Open text file")
-        .bytes()
-        .count() as u64;
+        .len();
 
     let mut group = c.benchmark_group("Rust 0.2.0. Inflect Words");
     group.throughput(criterion::Throughput::Bytes(bytes));
@@ -0,0 +1,21 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    capi_header();
+}
+
+/// Генерация C-заголовка для `src/capi.rs`.
+#[cfg(feature = "capi")]
+fn capi_header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{crate_dir}/src/capi.rs"))
+        .generate()
+        .expect("Unable to generate C header")
+        .write_to_file(format!("{crate_dir}/include/morph_rs.h"));
+}
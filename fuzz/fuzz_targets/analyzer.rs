@@ -0,0 +1,60 @@
+//! Фаззинг публичных методов `MorphAnalyzer` произвольным текстом.
+//!
+//! Запуск: `cargo +nightly fuzz run analyzer` из корня репозитория.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use morph_rs::{
+    morph::grammemes::{Case, Gender, Grammem, Number},
+    Language, MorphAnalyzer,
+};
+use std::{collections::HashMap, path::PathBuf, sync::OnceLock};
+use tempfile::TempDir;
+
+/// Тестовый словарь собирается один раз на весь прогон.
+fn analyzer() -> &'static MorphAnalyzer {
+    static ANALYZER: OnceLock<(MorphAnalyzer, TempDir)> = OnceLock::new();
+    &ANALYZER
+        .get_or_init(|| {
+            let dict_path: PathBuf =
+                concat!(env!("CARGO_MANIFEST_DIR"), "/../data/test/template_dict.xml").into();
+            let tmp_dir = tempfile::tempdir().expect("Temp dir");
+            let out_dir = tmp_dir.path().to_path_buf();
+
+            let dict = MorphAnalyzer::create(dict_path, out_dir.clone(), Language::Russian)
+                .expect("Dictionary");
+            let anal = MorphAnalyzer::init(dict, out_dir).expect("Analyzer");
+            (anal, tmp_dir)
+        })
+        .0
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let anal = analyzer();
+    let plural = vec![Grammem::Number(Number::Plural)];
+
+    if let Ok(parses) = anal.parse(text) {
+        for parse in parses.0 {
+            let _ = anal.inflect_parsed(parse.clone(), plural.clone());
+            let _ = anal.declension_parsed(&parse);
+        }
+    }
+    let _ = anal.normalize(text);
+    let _ = anal.inflect_inizio(text);
+    let _ = anal.inflect_forms(text, plural);
+    let _ = anal.declension(text);
+
+    if let Ok(fio) = anal.parse_fio(text) {
+        let _ = anal.inflect_fio_parsed(&fio, Case::Dativus);
+    }
+    let _ = anal.patronymic(text, Gender::Masculine, Case::Genetivus);
+    let _ = anal.parse_numeral(text);
+    let _ = anal.spell_number_str(text, vec![]);
+    let _ = anal.render(text, &HashMap::new());
+
+    anal.analyze_text(text).for_each(drop);
+    let _ = anal.lemmatize_batch(text.split_whitespace());
+});
@@ -0,0 +1,128 @@
+/* morph-rs C API. Generated by cbindgen from src/capi.rs, do not edit. */
+
+#ifndef MORPH_RS_H
+#define MORPH_RS_H
+
+#include <stddef.h>
+
+// Коды возврата, повторяющие варианты `MopsErr` и `ParseErr`.
+typedef enum MopsStatus {
+  MOPS_STATUS_OK = 0,
+  // Передан нулевой указатель.
+  MOPS_STATUS_NULL_POINTER = 1,
+  // Строка не в UTF-8.
+  MOPS_STATUS_INVALID_UTF8 = 2,
+  // Паника внутри анализатора.
+  MOPS_STATUS_PANIC = 3,
+  MOPS_STATUS_FILE = 10,
+  MOPS_STATUS_IO = 11,
+  MOPS_STATUS_XML = 12,
+  MOPS_STATUS_SERDE = 13,
+  MOPS_STATUS_DICTIONARY = 14,
+  MOPS_STATUS_FST = 15,
+  MOPS_STATUS_USER_DICTIONARY = 16,
+  MOPS_STATUS_OUT_OF_BOUND = 20,
+  MOPS_STATUS_LOST_NORMAL_FORM = 21,
+  MOPS_STATUS_LOST_LEMMA_ID = 22,
+  MOPS_STATUS_LOST_LEMMAS_ROW = 23,
+  MOPS_STATUS_LOST_PARSE = 24,
+  MOPS_STATUS_UNKNOWN_WORD = 25,
+  MOPS_STATUS_NO_POS = 26,
+  MOPS_STATUS_DECLENSION = 27,
+  MOPS_STATUS_BINARY_TAG = 28,
+  MOPS_STATUS_FIO = 29,
+  MOPS_STATUS_NUMERAL = 30,
+  MOPS_STATUS_UNKNOWN_GRAMMEM = 31,
+  MOPS_STATUS_TEMPLATE = 32,
+} MopsStatus;
+
+// Открытый анализатор.
+typedef struct MopsAnalyzer MopsAnalyzer;
+
+// Результат разбора, нормализации или изменения формы.
+typedef struct MopsWords MopsWords;
+
+// Одно слово результата. Строки принадлежат `MopsWords` и живут до `mops_words_free`.
+typedef struct MopsWord {
+  // Слово: разобранное, нормальная форма или измененная форма.
+  const char *word;
+  // Нормальная форма слова.
+  const char *normal_form;
+  // Граммемы `OpenCorpora` через запятую: "NOUN,inan,femn,sing,gent".
+  const char *tag;
+  // Способ разбора: "Dictionary", "KnowPrefix(...)", "UnknowPrefix(...)", "Postfix".
+  const char *method;
+} MopsWord;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Открытие собранного словаря из каталога `path`.
+//
+// # Safety
+// `path` - C-строка, `out` - валидный указатель. Анализатор освобождается `mops_free`.
+enum MopsStatus mops_open(const char *path,
+                          struct MopsAnalyzer **out);
+
+// Освобождение анализатора.
+//
+// # Safety
+// `analyzer` - нулевой указатель или результат `mops_open`, освобождаемый один раз.
+void mops_free(struct MopsAnalyzer *analyzer);
+
+// Все разборы слова. Для несловарного слова без вангования результат пуст.
+//
+// # Safety
+// `analyzer` - результат `mops_open`, `word` - C-строка, `out` - валидный указатель.
+// Результат освобождается `mops_words_free`.
+enum MopsStatus mops_parse(const struct MopsAnalyzer *analyzer,
+                           const char *word,
+                           struct MopsWords **out);
+
+// Нормальные формы слова.
+//
+// # Safety
+// То же, что у `mops_parse`.
+enum MopsStatus mops_normalize(const struct MopsAnalyzer *analyzer,
+                               const char *word,
+                               struct MopsWords **out);
+
+// Формы слова с граммемами `grammemes` через запятую ("plur,ablt").
+// При нулевом `grammemes` слово приводится к начальной форме.
+//
+// # Safety
+// То же, что у `mops_parse`, `grammemes` - нулевой указатель или C-строка.
+enum MopsStatus mops_inflect(const struct MopsAnalyzer *analyzer,
+                             const char *word,
+                             const char *grammemes,
+                             struct MopsWords **out);
+
+// Количество слов в результате.
+//
+// # Safety
+// `words` - нулевой указатель или результат запроса.
+size_t mops_words_len(const struct MopsWords *words);
+
+// Слово результата по индексу, нулевой указатель за границей результата.
+//
+// # Safety
+// `words` - нулевой указатель или результат запроса.
+const struct MopsWord *mops_words_get(const struct MopsWords *words,
+                                      size_t index);
+
+// Освобождение результата.
+//
+// # Safety
+// `words` - нулевой указатель или результат запроса, освобождаемый один раз.
+void mops_words_free(struct MopsWords *words);
+
+// Текст последней ошибки в текущем потоке или нулевой указатель.
+// Строка действительна до следующего вызова функций `mops_*` в этом потоке.
+const char *mops_last_error(void);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* MORPH_RS_H */
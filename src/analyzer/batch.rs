@@ -0,0 +1,89 @@
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::{
+    errors::{MopsErr, MopsResult, ParseErr},
+    MorphAnalyzer, Normalized,
+};
+
+impl MorphAnalyzer {
+    /// Обработка слов пачкой: повторы обрабатываются один раз, уникальные слова - параллельно.
+    ///
+    /// Результаты выдаются в порядке входных слов, ошибка одного слова не прерывает пачку.
+    pub(crate) fn batch<I, T, F>(&self, words: I, f: F) -> Vec<MopsResult<T>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+        T: Clone + Send,
+        F: Fn(&Self, &str) -> Result<T, ParseErr> + Sync,
+    {
+        let words = words.into_iter().collect_vec();
+        let unique = words.iter().map(AsRef::as_ref).unique().collect_vec();
+
+        let results: HashMap<&str, Result<T, ParseErr>> = unique
+            .into_par_iter()
+            .map(|word| (word, f(self, word)))
+            .collect();
+
+        words
+            .iter()
+            .map(|word| results[word.as_ref()].clone().map_err(MopsErr::Parse))
+            .collect()
+    }
+
+    /// Лемма слова: нормальная форма первого разбора.
+    ///
+    /// Слово приводится к нижнему регистру, слово без разбора остается как есть.
+    pub(crate) fn word_lemma(&self, word: &str) -> Result<Normalized, ParseErr> {
+        let word = word.to_lowercase();
+        Ok(self
+            .parse_cached(&word)?
+            .0
+            .first()
+            .map(|parse| parse.normal_form())
+            .unwrap_or(word))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{analyzer::CacheConfig, test_infrastructure::infrastructure::analyzer};
+
+    #[test]
+    fn test_batch() {
+        let (_tmp_dir, anal) = analyzer("data/test/template_dict.xml");
+
+        let words = ["файлы", "изменений", "файлы", "Пользователю", "файлы"];
+        let lowercase = words.map(str::to_lowercase);
+
+        let lemmas = anal
+            .lemmatize_batch(words)
+            .into_iter()
+            .collect::<MopsResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            lemmas,
+            vec!["файл", "изменение", "файл", "пользователь", "файл"]
+        );
+
+        let parses = anal.parse_batch(&lowercase);
+        assert_eq!(parses.len(), words.len());
+        for (word, parse) in lowercase.iter().zip(parses) {
+            assert_eq!(parse.unwrap(), anal.parse(word).unwrap());
+        }
+
+        let normalized = anal.normalize_batch(lowercase.iter().map(String::as_str));
+        for (word, normal) in lowercase.iter().zip(normalized) {
+            assert_eq!(normal.unwrap(), anal.normalize(word).unwrap());
+        }
+
+        // Леммы берутся из кэша разборов.
+        let anal = anal.with_cache(CacheConfig::new(10));
+        anal.lemmatize_batch(words);
+        anal.lemmatize_batch(words);
+        let stats = anal.cache_stats().unwrap().parse;
+        assert_eq!((stats.hits, stats.misses), (3, 3));
+    }
+}
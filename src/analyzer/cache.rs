@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    hash::{BuildHasher, Hash, RandomState},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    analyzer::{InflectWords, NormalizedWords, ParsedWords},
+    errors::ParseErr,
+    morph::grammemes::Grammem,
+    MorphAnalyzer,
+};
+
+/// Отсутствующий узел в списке LRU.
+const NIL: usize = usize::MAX;
+/// Наибольшее число шардов одного кэша.
+const SHARDS: usize = 16;
+/// Наименьшая емкость шарда: маленькие кэши не делятся и вытесняют строго по LRU.
+const SHARD_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Размеры кэшей результатов анализатора в словах. Нулевой размер отключает кэш.
+pub struct CacheConfig {
+    pub parse: usize,
+    pub normalize: usize,
+    pub inflect: usize,
+}
+
+impl CacheConfig {
+    /// Одинаковый размер для всех кэшей.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            parse: capacity,
+            normalize: capacity,
+            inflect: capacity,
+        }
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self::new(10_000)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Статистика одного кэша.
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Сколько результатов хранится сейчас.
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// Доля попаданий от всех обращений.
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Статистика кэшей анализатора.
+pub struct CacheReport {
+    pub parse: CacheStats,
+    pub normalize: CacheStats,
+    pub inflect: CacheStats,
+}
+
+#[derive(Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+#[derive(Debug)]
+/// Вытеснение давно не использованных: хеш-таблица на индексы двусвязного списка в векторе.
+struct Lru<K, V> {
+    map: HashMap<K, usize>,
+    nodes: Vec<Node<K, V>>,
+    /// Последний использованный.
+    head: usize,
+    /// Кандидат на вытеснение.
+    tail: usize,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Lru<K, V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            map: HashMap::with_capacity(capacity),
+            nodes: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            capacity,
+        }
+    }
+
+    fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = *self.map.get(key)?;
+        self.detach(idx);
+        self.attach(idx);
+        Some(self.nodes[idx].value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(&idx) = self.map.get(&key) {
+            self.nodes[idx].value = value;
+            self.detach(idx);
+            self.attach(idx);
+            return;
+        }
+
+        let idx = if self.nodes.len() < self.capacity {
+            self.nodes.push(Node {
+                key: key.clone(),
+                value,
+                prev: NIL,
+                next: NIL,
+            });
+            self.nodes.len() - 1
+        } else {
+            let idx = self.tail;
+            self.detach(idx);
+            let node = &mut self.nodes[idx];
+            self.map.remove(&node.key);
+            node.key = key.clone();
+            node.value = value;
+            idx
+        };
+
+        self.map.insert(key, idx);
+        self.attach(idx);
+    }
+
+    fn detach(&mut self, idx: usize) {
+        let (prev, next) = (self.nodes[idx].prev, self.nodes[idx].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    fn attach(&mut self, idx: usize) {
+        self.nodes[idx].prev = NIL;
+        self.nodes[idx].next = self.head;
+        match self.head {
+            NIL => self.tail = idx,
+            head => self.nodes[head].prev = idx,
+        }
+        self.head = idx;
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.nodes.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+}
+
+#[derive(Debug)]
+/// Потокобезопасный кэш со счетчиками попаданий.
+///
+/// Результаты хранятся за `Arc`: под блокировкой клонируется только указатель,
+/// копия результата для вызывающего делается уже после ее снятия.
+/// Большой кэш делится на шарды по хешу ключа со своим LRU и мьютексом в каждом,
+/// чтобы потоки `parse_batch` и `normalize_batch` не ждали одну блокировку.
+struct Cache<K, V> {
+    shards: Vec<Mutex<Lru<K, Arc<V>>>>,
+    hasher: RandomState,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Cache<K, V> {
+    fn new(capacity: usize) -> Self {
+        let count = (capacity / SHARD_CAPACITY).clamp(1, SHARDS);
+        // Емкость делится между шардами без остатка в сумме.
+        let shards = (0..count)
+            .map(|i| {
+                let extra = usize::from(i < capacity % count);
+                Mutex::new(Lru::new(capacity / count + extra))
+            })
+            .collect();
+
+        Self {
+            shards,
+            hasher: RandomState::new(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Результат из кэша или вычисленный и сохраненный. Ошибки не кэшируются.
+    ///
+    /// Вычисление идет без блокировки: одно слово может посчитаться дважды в разных потоках.
+    fn get_or_try_insert<Q>(
+        &self,
+        key: &Q,
+        f: impl FnOnce() -> Result<V, ParseErr>,
+    ) -> Result<V, ParseErr>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = K> + ?Sized,
+    {
+        let shard = &self.shards[self.hasher.hash_one(key) as usize % self.shards.len()];
+
+        let cached = Self::lock(shard).get(key);
+        if let Some(value) = cached {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(V::clone(&value));
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let value = Arc::new(f()?);
+        Self::lock(shard).insert(key.to_owned(), Arc::clone(&value));
+        Ok(Arc::unwrap_or_clone(value))
+    }
+
+    fn lock(shard: &Mutex<Lru<K, Arc<V>>>) -> std::sync::MutexGuard<'_, Lru<K, Arc<V>>> {
+        // Отравленный мьютекс означает панику в другом потоке посреди вставки - шард сбрасывается.
+        shard.lock().unwrap_or_else(|poisoned| {
+            let mut lru = poisoned.into_inner();
+            lru.clear();
+            lru
+        })
+    }
+
+    fn stats(&self) -> CacheStats {
+        let (len, capacity) = self.shards.iter().fold((0, 0), |(len, capacity), shard| {
+            let lru = Self::lock(shard);
+            (len + lru.map.len(), capacity + lru.capacity)
+        });
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            len,
+            capacity,
+        }
+    }
+}
+
+/// Ключ кэша изменения форм: слово и запрошенные граммемы.
+type InflectKey = (String, Option<Vec<Grammem>>);
+
+#[derive(Debug)]
+/// Кэши результатов анализатора.
+pub(crate) struct AnalyzerCache {
+    parse: Cache<String, ParsedWords>,
+    normalize: Cache<String, NormalizedWords>,
+    inflect: Cache<InflectKey, Option<InflectWords>>,
+}
+
+impl AnalyzerCache {
+    pub(crate) fn new(config: CacheConfig) -> Self {
+        Self {
+            parse: Cache::new(config.parse),
+            normalize: Cache::new(config.normalize),
+            inflect: Cache::new(config.inflect),
+        }
+    }
+
+    pub(crate) fn config(&self) -> CacheConfig {
+        let report = self.report();
+        CacheConfig {
+            parse: report.parse.capacity,
+            normalize: report.normalize.capacity,
+            inflect: report.inflect.capacity,
+        }
+    }
+
+    pub(crate) fn report(&self) -> CacheReport {
+        CacheReport {
+            parse: self.parse.stats(),
+            normalize: self.normalize.stats(),
+            inflect: self.inflect.stats(),
+        }
+    }
+}
+
+impl MorphAnalyzer {
+    /// Разбор слова через кэш, если он включен.
+    pub(crate) fn parse_cached(&self, word: &str) -> Result<ParsedWords, ParseErr> {
+        match &self.cache {
+            Some(cache) => cache
+                .parse
+                .get_or_try_insert(word, || self.parse_word(word)),
+            None => self.parse_word(word),
+        }
+    }
+
+    /// Нормализация слова через кэш, если он включен.
+    pub(crate) fn normalize_cached(&self, word: &str) -> Result<NormalizedWords, ParseErr> {
+        match &self.cache {
+            Some(cache) => cache
+                .normalize
+                .get_or_try_insert(word, || self.normalized_word(word)),
+            None => self.normalized_word(word),
+        }
+    }
+
+    /// Изменение формы слова через кэш, если он включен.
+    pub(crate) fn inflect_cached(
+        &self,
+        word: &str,
+        grammemes: Option<Vec<Grammem>>,
+    ) -> Result<Option<InflectWords>, ParseErr> {
+        match &self.cache {
+            Some(cache) => {
+                let key = (word.to_string(), grammemes);
+                cache
+                    .inflect
+                    .get_or_try_insert(&key, || self.inflect_word(word, key.1.to_owned()))
+            }
+            None => self.inflect_word(word, grammemes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::{analyzer, shared_analyzer};
+    use test_case::test_case;
+
+    #[test]
+    fn test_lru() {
+        let mut lru = Lru::new(2);
+        lru.insert("a".to_string(), 1);
+        lru.insert("b".to_string(), 2);
+        assert_eq!(lru.get("a"), Some(1));
+
+        // "b" дольше всех не использовался.
+        lru.insert("c".to_string(), 3);
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.get("a"), Some(1));
+        assert_eq!(lru.get("c"), Some(3));
+
+        lru.insert("c".to_string(), 4);
+        lru.insert("d".to_string(), 5);
+        assert_eq!(lru.get("a"), None);
+        assert_eq!(lru.get("c"), Some(4));
+        assert_eq!(lru.map.len(), 2);
+
+        let mut disabled = Lru::new(0);
+        disabled.insert("a".to_string(), 1);
+        assert_eq!(disabled.get("a"), None);
+    }
+
+    #[test_case(0 => 1)]
+    #[test_case(2 => 1)]
+    #[test_case(10_000 => 9)]
+    #[test_case(1_000_000 => SHARDS)]
+    fn test_shards(capacity: usize) -> usize {
+        let cache = Cache::<String, usize>::new(capacity);
+        for _ in 0..2 {
+            for i in 0..100 {
+                let key = i.to_string();
+                assert_eq!(cache.get_or_try_insert(&key, || Ok(i)).unwrap(), i);
+            }
+        }
+
+        let stats = cache.stats();
+        assert_eq!(stats.capacity, capacity);
+        if capacity >= 100 {
+            assert_eq!((stats.hits, stats.misses, stats.len), (100, 100, 100));
+        }
+        cache.shards.len()
+    }
+
+    #[test]
+    fn test_analyzer_cache() {
+        let plain = shared_analyzer("data/test/template_dict.xml");
+        assert_eq!(plain.cache_stats(), None);
+
+        let (_tmp_dir, anal) = analyzer("data/test/template_dict.xml");
+        let anal = anal.with_cache(CacheConfig::new(2));
+
+        for word in [
+            "файлы",
+            "файлы",
+            "изменений",
+            "файлы",
+            "пользователи",
+            "изменений",
+        ] {
+            assert_eq!(anal.parse(word).unwrap(), plain.parse(word).unwrap());
+            assert_eq!(
+                anal.normalize(word).unwrap(),
+                plain.normalize(word).unwrap()
+            );
+        }
+        for _ in 0..2 {
+            assert_eq!(
+                anal.inflect_inizio("файлы")
+                    .unwrap()
+                    .map(|forms| forms.0.len()),
+                plain
+                    .inflect_inizio("файлы")
+                    .unwrap()
+                    .map(|forms| forms.0.len())
+            );
+        }
+
+        let stats = anal.cache_stats().unwrap();
+        // "изменений" в конце вытеснен "пользователи": емкость кэша - два слова.
+        let expected = CacheStats {
+            hits: 2,
+            misses: 4,
+            len: 2,
+            capacity: 2,
+        };
+        assert_eq!(stats.parse, expected);
+        assert_eq!(stats.normalize, expected);
+        assert_eq!((stats.inflect.hits, stats.inflect.misses), (1, 1));
+        assert!((stats.parse.hit_rate() - 1.0 / 3.0).abs() < f64::EPSILON);
+
+        let mut anal = anal;
+        anal.clear_cache();
+        let cleared = anal.cache_stats().unwrap();
+        assert_eq!(
+            cleared.parse,
+            CacheStats {
+                capacity: 2,
+                ..Default::default()
+            }
+        );
+    }
+}
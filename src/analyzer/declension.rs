@@ -1,4 +1,4 @@
-use super::Tag;
+use super::{PrefixAlternations, Tag};
 use crate::{
     errors::{Bound, DeclensionErr},
     morph::grammemes::{Grammem, ParteSpeech},
@@ -10,268 +10,17 @@ const ALPHABET: [char; 32] = [
     'у', 'ф', 'х', 'ц', 'ч', 'ш', 'щ', 'ъ', 'ы', 'ь', 'э', 'ю', 'я',
 ];
 
-/// Для некоторых нормализованных форм характерно чередование букв в префиксе.
-/// Для того, чтобы найти все слова для спряжений/склонений, нам необходимо
-/// проверить также префиксы с чередованием.
-///
-/// ## WARN!
-/// Собирается в функции `test_infrastructure::test_form_first_chars`.
-/// Важно собирать эти значения, если меняются связи между леммами.
-const DIFF: [(&str, &str); 252] = [
-    ("брать", "бе"),
-    ("вить", "вь"),
-    ("вить", "ве"),
-    ("сбиться", "со"),
-    ("петь", "по"),
-    ("вбить", "во"),
-    ("рот", "рт"),
-    ("ты", "те"),
-    ("ты", "то"),
-    ("тьма", "те"),
-    ("айка", "ае"),
-    ("что-то", "че"),
-    ("так", "т"),
-    ("питься", "пь"),
-    ("питься", "пе"),
-    ("содраться", "сд"),
-    ("лед", "ль"),
-    ("сие", "се"),
-    ("честься", "чт"),
-    ("опереть", "об"),
-    ("искать", "ищ"),
-    ("исшить", "из"),
-    ("день", "дн"),
-    ("яйцо", "яи"),
-    ("рыться", "ро"),
-    ("удить", "уж"),
-    ("сжечь", "со"),
-    ("око", "оч"),
-    ("человек", "лю"),
-    ("переть", "пр"),
-    ("счесть", "со"),
-    ("они", "их"),
-    ("они", "ни"),
-    ("они", "им"),
-    ("что-либо", "че"),
-    ("пить", "пь"),
-    ("пить", "пе"),
-    ("молоть", "ме"),
-    ("слать", "шл"),
-    ("зло", "зо"),
-    ("демейк", "ди"),
-    ("соваться", "су"),
-    ("чтоб", "шт"),
-    ("чтоб", "шо"),
-    ("пойти", "пш"),
-    ("переться", "пр"),
-    ("раздать", "ро"),
-    ("уняться", "уй"),
-    ("ребенок", "де"),
-    ("себя", "со"),
-    ("ухо", "уш"),
-    ("опереться", "об"),
-    ("расти", "ро"),
-    ("класть", "ло"),
-    ("сесть", "ся"),
-    ("взять", "во"),
-    ("жевать", "жу"),
-    ("истереть", "из"),
-    ("мьютить", "мю"),
-    ("то", "т"),
-    ("гнать", "го"),
-    ("год", "ле"),
-    ("год", "гг"),
-    ("слить", "со"),
-    ("нквд", "эн"),
-    ("жечь", "жг"),
-    ("жечь", "жж"),
-    ("щи", "ще"),
-    ("щи", "ща"),
-    ("мять", "мн"),
-    ("кто", "ко"),
-    ("кто", "ке"),
-    ("мыться", "мо"),
-    ("широта", "ш"),
-    ("заря", "зо"),
-    ("искаться", "ищ"),
-    ("ехать", "ед"),
-    ("ехать", "ез"),
-    ("биться", "бь"),
-    ("биться", "бе"),
-    ("подонок", "па"),
-    ("выть", "во"),
-    ("петься", "по"),
-    ("мстить", "мщ"),
-    ("смять", "со"),
-    ("литься", "ль"),
-    ("литься", "ле"),
-    ("испить", "из"),
-    ("быть", "ес"),
-    ("быть", "су"),
-    ("быть", "бу"),
-    ("быть", "е"),
-    ("стереться", "со"),
-    ("унять", "уй"),
-    ("уйти", "уш"),
-    ("счесться", "со"),
-    ("сжать", "со"),
-    ("мяться", "мн"),
-    ("рыть", "ро"),
-    ("изойти", "ис"),
-    ("мыть", "мо"),
-    ("стереть", "со"),
-    ("ия", "ии"),
-    ("ия", "ию"),
-    ("ия", "ие"),
-    ("ия", "ий"),
-    ("она", "ее"),
-    ("она", "не"),
-    ("она", "ей"),
-    ("она", "ею"),
-    ("она", "ея"),
-    ("оно", "ег"),
-    ("оно", "не"),
-    ("оно", "ем"),
-    ("оно", "им"),
-    ("оно", "ни"),
-    ("что", "шт"),
-    ("что", "че"),
-    ("что", "ча"),
-    ("что", "чо"),
-    ("что", "шо"),
-    ("я", "ме"),
-    ("я", "мн"),
-    ("кто-нибудь", "ко"),
-    ("кто-нибудь", "ке"),
-    ("впить", "во"),
-    ("пень", "пн"),
-    ("звать", "зо"),
-    ("сегвей", "си"),
-    ("ввить", "во"),
-    ("что-нибудь", "че"),
-    ("лить", "ль"),
-    ("лить", "ле"),
-    ("влить", "во"),
-    ("смяться", "со"),
-    ("вобраться", "вб"),
-    ("тереться", "тр"),
-    ("вшить", "во"),
-    ("пес", "пс"),
-    ("впереться", "во"),
-    ("шиться", "шь"),
-    ("шиться", "ше"),
-    ("драться", "де"),
-    ("согнать", "сг"),
-    ("влиться", "во"),
-    ("ая", "аи"),
-    ("ая", "ае"),
-    ("ая", "аю"),
-    ("ая", "ай"),
-    ("спереться", "со"),
-    ("вмять", "во"),
-    ("узить", "уж"),
-    ("истереться", "из"),
-    ("драть", "де"),
-    ("вжать", "во"),
-    ("спереть", "со"),
-    ("впиться", "во"),
-    ("услать", "уш"),
-    ("впереть", "во"),
-    ("честь", "чт"),
-    ("честь", "чл"),
-    ("ретейлер", "ри"),
-    ("совать", "су"),
-    ("свиться", "со"),
-    ("век", "вв"),
-    ("лен", "ль"),
-    ("идти", "ше"),
-    ("идти", "шл"),
-    ("бить", "бь"),
-    ("бить", "бе"),
-    ("слаться", "шл"),
-    ("рожь", "рж"),
-    ("лезть", "по"),
-    ("лоб", "лб"),
-    ("содрать", "сд"),
-    ("гнаться", "го"),
-    ("жать", "жн"),
-    ("жать", "жм"),
-    ("вогнать", "вг"),
-    ("вы", "ва"),
-    ("есться", "ем"),
-    ("есться", "ед"),
-    ("есться", "еш"),
-    ("есться", "ел"),
-    ("есться", "ев"),
-    ("кто-то", "ко"),
-    ("кто-то", "ке"),
-    ("сон", "сн"),
-    ("сжаться", "со"),
-    ("ныть", "но"),
-    ("свить", "со"),
-    ("спиться", "со"),
-    ("ковать", "ку"),
-    ("кто-либо", "ко"),
-    ("кто-либо", "ке"),
-    ("дно", "до"),
-    ("браться", "бе"),
-    ("сшить", "со"),
-    ("жечься", "жг"),
-    ("жечься", "жж"),
-    ("опить", "об"),
-    ("мох", "мх"),
-    ("вобрать", "вб"),
-    ("он", "ег"),
-    ("он", "не"),
-    ("он", "ем"),
-    ("он", "им"),
-    ("он", "ни"),
-    ("удиться", "уж"),
-    ("коваться", "ку"),
-    ("вошь", "вш"),
-    ("ребеночек", "де"),
-    ("ров", "рв"),
-    ("спить", "со"),
-    ("шов", "шв"),
-    ("узиться", "уж"),
-    ("жеваться", "жу"),
-    ("лгать", "лж"),
-    ("александрович", "са"),
-    ("ложь", "лж"),
-    ("жаться", "жм"),
-    ("втереться", "во"),
-    ("молоться", "ме"),
-    ("опиться", "об"),
-    ("мы", "на"),
-    ("мереть", "мр"),
-    ("втереть", "во"),
-    ("вжаться", "во"),
-    ("лев", "ль"),
-    ("слиться", "со"),
-    ("тереть", "тр"),
-    ("сбить", "со"),
-    ("виться", "вь"),
-    ("виться", "ве"),
-    ("мейл", "мэ"),
-    ("зваться", "зо"),
-    ("изостлать", "ис"),
-    ("лечь", "ля"),
-    ("шить", "шь"),
-    ("шить", "ше"),
-    ("как", "к"),
-    ("вмяться", "во"),
-    ("идтись", "шл"),
-    ("есть", "ем"),
-    ("есть", "ед"),
-    ("есть", "еш"),
-    ("есть", "ел"),
-    ("есть", "ев"),
-    ("взяться", "во"),
-    ("ютиться", "юч"),
-    ("хентай", "хэ"),
-    ("сжечься", "со"),
+/// Части речи со сравнительной степенью через приставки "по-" и "наи-".
+pub(crate) const PO_NAI: [ParteSpeech; 4] = [
+    ParteSpeech::AdjectiveFull,
+    ParteSpeech::AdjectiveShort,
+    ParteSpeech::Comparative,
+    ParteSpeech::Adverb,
 ];
 
+/// Сколько первых букв слова ограничивают префиксный поиск.
+pub(crate) const INIZIO_CHARS: usize = 2;
+
 /// Поиск алфавитных границ префиксов для ограничения прохода по Fst::Stream.
 ///
 /// Для наречий и прилагательных проверяются также их приставки
@@ -284,27 +33,41 @@ pub fn alphabet_stream(
     word: &str,
     normal_form: &str,
     tag: Tag,
+    alternations: &PrefixAlternations,
 ) -> Result<Vec<(String, Option<String>)>, DeclensionErr> {
+    // Сама нормальная форма тоже может начинаться иначе, чем слово: "нами" -> "мы".
+    let alternations = std::iter::once(normal_form)
+        .chain(
+            alternations
+                .get(normal_form)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        )
+        .collect::<Vec<_>>();
+
     // todo после нормализации
-    if [
-        ParteSpeech::AdjectiveFull,
-        ParteSpeech::AdjectiveShort,
-        ParteSpeech::Comparative,
-        ParteSpeech::Adverb,
-    ]
-    .contains(&Grammem::pos_in_tag(&tag).unwrap_or_else(|| panic!("No ParteSpeech in {tag:?}")))
-    {
-        alphabet_vicino_po_nai(word, normal_form)
+    if Grammem::pos_in_tag(&tag).is_some_and(|pos| PO_NAI.contains(&pos)) {
+        alphabet_vicino_po_nai(word, &alternations)
     } else {
-        alphabet_vicino(word, normal_form)
+        alphabet_vicino(word, &alternations)
     }
 }
 
 /// Взятие следующей буквы алфавита для конечной границы префиксного поиска.
+///
+/// Для символов не из алфавита ('ё', латиница, цифры) берется следующий символ Unicode:
+/// порядок байтов UTF-8 в fst совпадает с порядком кодов символов.
 fn next_char(char: &char) -> Result<Option<String>, DeclensionErr> {
-    let char_id = ALPHABET
-        .binary_search(char)
-        .map_err(|_| DeclensionErr::BinaryChar(*char))?;
+    let Ok(char_id) = ALPHABET.binary_search(char) else {
+        let next = match *char {
+            char::MAX => None,
+            '\u{D7FF}' => Some('\u{E000}'),
+            char => char::from_u32(char as u32 + 1),
+        };
+        return Ok(next.map(String::from));
+    };
+
     if char_id == ALPHABET.len() - 1 {
         Ok(None)
     } else {
@@ -321,23 +84,25 @@ fn next_char(char: &char) -> Result<Option<String>, DeclensionErr> {
 
 /// Взятие префиксных пар для прохода по `fst::Stream`.
 ///
-/// Если у нормализованной формы и других вариаций слова есть чередования в префиксе,
-/// будут взяты все инварианты.
+/// Если у нормализованной формы и других вариаций слова есть чередования в префиксе
+/// (нормальная форма и `alternations` из словаря), будут взяты все инварианты.
 ///
 /// Если нет возможности найти "следующую по алфавиту" (например, для 'я'), то
 /// возвращается None, сигнализируя `fst::Stream`, что необходимо брать `less-or-equal` от начальных букв.
 pub(crate) fn alphabet_vicino(
     word: &str,
-    normal_form: &str,
+    alternations: &[&str],
 ) -> Result<Vec<(String, Option<String>)>, DeclensionErr> {
     let mut result = Vec::new();
 
     let (inizio, next_chars) = take_chars(word)?;
     result.push((inizio, next_chars));
 
-    for (_, diff) in DIFF.iter().filter(|pair| pair.0 == normal_form) {
-        let (diff_inizio, diff_next_chars) = take_chars(diff)?;
-        result.push((diff_inizio, diff_next_chars))
+    for diff in alternations {
+        let pair = take_chars(diff)?;
+        if !result.contains(&pair) {
+            result.push(pair)
+        }
     }
 
     Ok(result)
@@ -350,18 +115,16 @@ pub(crate) fn alphabet_vicino(
 /// включая вариации с "по-" и "наи-".
 pub(crate) fn alphabet_vicino_po_nai(
     word: &str,
-    normal_form: &str,
+    alternations: &[&str],
 ) -> Result<Vec<(String, Option<String>)>, DeclensionErr> {
     let mut result = Vec::new();
 
     // todo слова с по/наи как часть корня
-    let word = if let Some(word) = word.strip_prefix("по") {
-        word
-    } else if let Some(word) = word.strip_prefix("наи") {
-        word
-    } else {
-        word
-    };
+    // Слово из одной приставки ("по") остается как есть.
+    let word = ["по", "наи"]
+        .into_iter()
+        .find_map(|prefix| word.strip_prefix(prefix).filter(|stem| !stem.is_empty()))
+        .unwrap_or(word);
 
     let (inizio, next_chars) = take_chars(word)?;
     let (nai, nai_next) = (
@@ -377,9 +140,11 @@ pub(crate) fn alphabet_vicino_po_nai(
     result.push((nai, nai_next));
     result.push((po, po_next));
 
-    for (_, diff) in DIFF.iter().filter(|pair| pair.0 == normal_form) {
-        let (diff_inizio, diff_next_chars) = take_chars(diff)?;
-        result.push((diff_inizio, diff_next_chars))
+    for diff in alternations {
+        let pair = take_chars(diff)?;
+        if !result.contains(&pair) {
+            result.push(pair)
+        }
     }
 
     Ok(result)
@@ -407,7 +172,8 @@ fn take_chars(word: &str) -> Result<(String, Option<String>), DeclensionErr> {
                 next_chars.push_str(&char);
                 Some(next_chars)
             }
-            None => None,
+            // Для второй буквы 'я' граница сдвигается по первой букве: "пя" -> "р".
+            None => next_char(&first)?,
         }
     } else {
         next_char(&first)?
@@ -421,14 +187,15 @@ mod test {
     use itertools::Itertools;
     use test_case::test_case;
 
-    #[test_case("больше", "больше", &[("бо", "бп"), ("наибо", "наибп"), ("побо", "побп")])]
-    #[test_case("получше", "хороший", &[("лу", "лф"), ("наилу", "наилф"), ("полу", "полф")])]
-    #[test_case("опаснее", "опасно", &[("оп", "ор"), ("наиоп", "наиор"), ("пооп", "поор")])]
-    #[test_case("худшее", "худший", &[("ху", "хф"), ("наиху", "наихф"), ("поху", "похф")])]
-    #[test_case("похуже", "плохо", &[("ху", "хф"), ("наиху", "наихф"), ("поху", "похф")])]
-    fn test_po_nai(word: &str, normal_form: &str, result: &[(&str, &str)]) {
+    #[test_case("больше", &[], &[("бо", "бп"), ("наибо", "наибп"), ("побо", "побп")])]
+    #[test_case("получше", &[], &[("лу", "лф"), ("наилу", "наилф"), ("полу", "полф")])]
+    #[test_case("опаснее", &[], &[("оп", "ор"), ("наиоп", "наиор"), ("пооп", "поор")])]
+    #[test_case("худшее", &[], &[("ху", "хф"), ("наиху", "наихф"), ("поху", "похф")])]
+    #[test_case("похуже", &[], &[("ху", "хф"), ("наиху", "наихф"), ("поху", "похф")])]
+    #[test_case("по", &[], &[("по", "пп"), ("наипо", "наипп"), ("попо", "попп")])]
+    fn test_po_nai(word: &str, alternations: &[&str], result: &[(&str, &str)]) {
         assert_eq!(
-            alphabet_vicino_po_nai(word, normal_form)
+            alphabet_vicino_po_nai(word, alternations)
                 .unwrap()
                 .iter()
                 .map(|(k, l)| (k.as_str(), l.as_ref().unwrap().as_str()))
@@ -436,4 +203,20 @@ mod test {
             result
         )
     }
+
+    #[test_case("стали", &[] => vec![("ст".to_string(), Some("су".to_string()))])]
+    #[test_case("пятьсот", &[] => vec![("пя".to_string(), Some("р".to_string()))])]
+    #[test_case("яя", &[] => vec![("яя".to_string(), None)])]
+    #[test_case("ёж", &[] => vec![("ёж".to_string(), Some("ёз".to_string()))])]
+    #[test_case("её", &[] => vec![("её".to_string(), Some("еђ".to_string()))])]
+    #[test_case("iphone", &[] => vec![("ip".to_string(), Some("iq".to_string()))])]
+    #[test_case("1-й", &[] => vec![("1-".to_string(), Some("1.".to_string()))])]
+    #[test_case("\u{10FFFF}", &[] => vec![("\u{10FFFF}".to_string(), None)])]
+    #[test_case("нас", &["мы"] => vec![
+        ("на".to_string(), Some("нб".to_string())),
+        ("мы".to_string(), Some("мь".to_string()))
+    ])]
+    fn test_vicino(word: &str, alternations: &[&str]) -> Vec<(String, Option<String>)> {
+        alphabet_vicino(word, alternations).unwrap()
+    }
 }
@@ -20,9 +20,12 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use super::{LemmasRows, OpCLid};
+use super::{
+    declension::{INIZIO_CHARS, PO_NAI},
+    LemmaForms, LemmasRows, OpCLid, PrefixAlternations,
+};
 
-#[derive(Debug, Default, Serialize, Deserialize, Allocative)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, Allocative)]
 /// Мета-информация словаря.
 pub struct Meta {
     version: String,
@@ -30,6 +33,23 @@ pub struct Meta {
     language: Language,
 }
 
+impl Meta {
+    /// Версия словаря `OpenCorpora`.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Ревизия словаря `OpenCorpora`.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Язык словаря.
+    pub fn language(&self) -> &Language {
+        &self.language
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, Allocative)]
 /// Словарь, полученный из постобработки словаря Opencorpora.
 pub struct Dictionary {
@@ -41,6 +61,12 @@ pub struct Dictionary {
     pub lemmas: Lemmas,
     pub paradigms: Vec<Vanga>,
     pub lemmas_rows: LemmasRows,
+    /// Пуст у словарей, собранных до появления индекса.
+    #[serde(default)]
+    pub lemma_forms: LemmaForms,
+    /// Чередования в началах форм для префиксного поиска, пусты у старых словарей.
+    #[serde(default)]
+    pub alternations: PrefixAlternations,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Serialize, Deserialize)]
@@ -100,6 +126,24 @@ impl Dictionary {
         outdir: P,
         language: Language,
     ) -> Result<Self, DictionaryErr> {
+        let writer = File::create(&outdir).map_err(|error| DictionaryErr::Outdir {
+            outdir: outdir.as_ref().into(),
+            error,
+        })?;
+        let wtr = std::io::BufWriter::new(writer);
+
+        let fst = MapBuilder::new(wtr).map_err(DictionaryErr::FstBuild)?;
+        let (dictionary, _) = Self::build(dict, fst, language)?;
+        Ok(dictionary)
+    }
+
+    /// Сборка словаря с записью fst в `fst`: в файл или в память (`MapBuilder::memory`).
+    /// Возвращается словарь и writer с записанным fst.
+    pub(crate) fn build<W: Write>(
+        dict: DictionaryOpenCorpora,
+        mut fst: MapBuilder<W>,
+        language: Language,
+    ) -> Result<(Self, W), DictionaryErr> {
         let DictionaryOpenCorpora {
             version,
             revision,
@@ -109,14 +153,6 @@ impl Dictionary {
 
         let link_connotation: HashMap<u64, Vec<u64>> = links.collect_lemmas();
 
-        let writer = File::create(&outdir).map_err(|error| DictionaryErr::Outdir {
-            outdir: outdir.as_ref().into(),
-            error,
-        })?;
-        let wtr = std::io::BufWriter::new(writer);
-
-        let mut fst = MapBuilder::new(wtr).map_err(DictionaryErr::FstBuild)?;
-
         // Предварительный сбор тегов, чтобы найти только уникальные.
         let mut tags: HashSet<Tag> = HashSet::new();
 
@@ -235,6 +271,8 @@ impl Dictionary {
 
         lemmas_rows.sort();
 
+        let alternations = Self::prefix_alternations(&word_map);
+
         // Финальные наборы парсингов для слов.
         let mut vec_parse: Vec<Vec<Parse>> = Vec::new();
 
@@ -274,20 +312,30 @@ impl Dictionary {
 
         vec_parse.sort();
 
+        // Обратный индекс от леммы к формам, чтобы склонение не проходило весь словарь.
+        let mut lemma_forms = LemmaForms::new();
+
         for (word, tags) in word_parses.into_iter() {
             let id = vec_parse
                 .binary_search(&tags)
-                .map_err(|_| DictionaryErr::BinaryParse(tags))?;
+                .map_err(|_| DictionaryErr::BinaryParse(tags.clone()))?;
+
+            for lemma_id in tags.iter().filter_map(|parse| parse.form.id()).unique() {
+                lemma_forms
+                    .entry(lemma_id as OpCLid)
+                    .or_default()
+                    .push((word.clone(), id as u64));
+            }
             fst.insert(word, id as u64)
                 .map_err(DictionaryErr::FstBuild)?;
         }
 
-        fst.finish().map_err(DictionaryErr::FstBuild)?;
+        let fst = fst.into_inner().map_err(DictionaryErr::FstBuild)?;
         // Образование fst закончено
 
         let paradigms = Vanga::parse_vangas(paradigms, &tags)?;
 
-        Ok(Self {
+        let dictionary = Self {
             meta: Meta {
                 version,
                 revision,
@@ -298,7 +346,51 @@ impl Dictionary {
             lemmas,
             paradigms,
             lemmas_rows,
-        })
+            lemma_forms,
+            alternations,
+        };
+        Ok((dictionary, fst))
+    }
+
+    /// Сбор чередований в началах форм: формы, которые не начинаются с первых букв
+    /// своей нормальной формы, не попадут в префиксный поиск без отдельного префикса.
+    ///
+    /// Например, "человек" -> "люди" дает пару "человек" - "лю".
+    /// Прилагательные и наречия пропускаются: их приставки "по-" и "наи-" ищутся отдельно.
+    fn prefix_alternations(
+        word_map: &BTreeMap<String, Vec<ParseIntermediate>>,
+    ) -> PrefixAlternations {
+        let mut alternations = PrefixAlternations::new();
+
+        for (word, parses) in word_map {
+            let word = word.replace('ё', "е");
+            let inizio = word.chars().take(INIZIO_CHARS).collect::<String>();
+
+            for parse in parses {
+                if Grammem::pos_in_tag(&parse.tag).is_some_and(|pos| PO_NAI.contains(&pos)) {
+                    continue;
+                }
+
+                let normal_inizio = parse
+                    .normal_form
+                    .replace('ё', "е")
+                    .chars()
+                    .take(INIZIO_CHARS)
+                    .collect::<String>();
+                if word.starts_with(&normal_inizio) {
+                    continue;
+                }
+
+                let diffs = alternations
+                    .entry(parse.normal_form.to_string())
+                    .or_default();
+                if !diffs.contains(&inizio) {
+                    diffs.push(inizio.clone());
+                }
+            }
+        }
+
+        alternations
     }
 }
 
@@ -533,6 +625,16 @@ pub(crate) mod test {
         assert_eq!(lemma.first_tags().unwrap(), tag);
     }
 
+    #[test]
+    fn test_prefix_alternations() {
+        let tmp_dir = tempdir().unwrap();
+        let dict = make_dict("data/test/tagger_dict.xml", tmp_dir.path().join("dict.fst"));
+
+        let mut alternations = PrefixAlternations::new();
+        alternations.insert("мы".to_string(), vec!["на".to_string()]);
+        assert_eq!(dict.alternations, alternations);
+    }
+
     #[test]
     fn test_normalization_small() {
         let dict = DictionaryOpenCorpora::init_from_path("data/test/test_lemma.xml").unwrap();
@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fst::{IntoStreamer, Streamer};
+use itertools::Itertools;
+use serde::Serialize;
+
+use super::{Meta, OpCLid, ParsedWords};
+use crate::{errors::MopsResult, json::tag_string, MorphAnalyzer, NormalizedWords};
+
+/// Сколько слов разбирается за раз при сравнении: в памяти держатся только различия.
+const DIFF_CHUNK: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Различия двух собранных словарей: старого и нового.
+pub struct DictionaryDiff {
+    pub old: Meta,
+    pub new: Meta,
+    /// Леммы `OpenCorpora` по обратному индексу форм, пусто для словарей без индекса.
+    pub lemmas: SetDiff<Lemma>,
+    /// Теги в обозначениях `OpenCorpora`.
+    pub tags: SetDiff<String>,
+    /// Связи между леммами: строки лемм, объединяющие несколько лемм `OpenCorpora`.
+    pub links: SetDiff<Vec<Lemma>>,
+    /// Слова, у которых изменился вывод `parse` или `normalize`.
+    pub words: Vec<WordDiff>,
+}
+
+impl DictionaryDiff {
+    /// Словари не различаются.
+    pub fn is_empty(&self) -> bool {
+        self.lemmas.is_empty()
+            && self.tags.is_empty()
+            && self.links.is_empty()
+            && self.words.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Добавленные и удаленные элементы, по возрастанию.
+pub struct SetDiff<T> {
+    pub added: Vec<T>,
+    pub removed: Vec<T>,
+}
+
+impl<T: Ord + Clone> SetDiff<T> {
+    fn new(old: &BTreeSet<T>, new: &BTreeSet<T>) -> Self {
+        Self {
+            added: new.difference(old).cloned().collect(),
+            removed: old.difference(new).cloned().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+/// Лемма `OpenCorpora`: id и ее нормальная форма.
+pub struct Lemma {
+    pub id: OpCLid,
+    pub word: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Изменившийся вывод для слова. Каждый разбор - "нормальная_форма тег метод".
+pub struct WordDiff {
+    pub word: String,
+    pub parse: Option<Change>,
+    pub normalize: Option<Change>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub old: Vec<String>,
+    pub new: Vec<String>,
+}
+
+impl Change {
+    fn new(old: Vec<String>, new: Vec<String>) -> Option<Self> {
+        (old != new).then_some(Self { old, new })
+    }
+}
+
+fn parses(parsed: MopsResult<ParsedWords>) -> Vec<String> {
+    match parsed {
+        Ok(parsed) => parsed
+            .0
+            .iter()
+            .map(|parse| {
+                format!(
+                    "{} {} {}",
+                    parse.normal_form,
+                    tag_string(&parse.tags),
+                    parse.method
+                )
+            })
+            .collect(),
+        Err(err) => vec![format!("error: {err}")],
+    }
+}
+
+fn normal_forms(normalized: MopsResult<NormalizedWords>) -> Vec<String> {
+    match normalized {
+        Ok(normalized) => normalized
+            .0
+            .iter()
+            .map(|normal| {
+                format!(
+                    "{} {} {}",
+                    normal.normal_word,
+                    tag_string(&normal.tags),
+                    normal.method
+                )
+            })
+            .collect(),
+        Err(err) => vec![format!("error: {err}")],
+    }
+}
+
+impl MorphAnalyzer {
+    /// Нормальные формы лемм `OpenCorpora` по обратному индексу: для каждой леммы - первая
+    /// по алфавиту начальная или нормальная форма, а если их нет - первая любая.
+    ///
+    /// Пусто для словарей без обратного индекса.
+    fn lemma_words(&self) -> BTreeMap<OpCLid, String> {
+        self.lemma_forms
+            .iter()
+            .filter_map(|(id, forms)| {
+                let main = |parse_id: u64| {
+                    self.get_parse(parse_id).into_iter().flatten().any(|parse| {
+                        parse.form.id() == Some(*id as u64)
+                            && (parse.form.is_normal() || parse.form.is_inizio())
+                    })
+                };
+                let (word, _) = forms
+                    .iter()
+                    .find(|(_, parse_id)| main(*parse_id))
+                    .or_else(|| forms.first())?;
+                Some((*id, word.clone()))
+            })
+            .collect()
+    }
+
+    fn lemma(words: &BTreeMap<OpCLid, String>, id: OpCLid) -> Lemma {
+        let word = words.get(&id).cloned().unwrap_or_default();
+        Lemma { id, word }
+    }
+
+    fn lemma_set(&self, words: &BTreeMap<OpCLid, String>) -> BTreeSet<Lemma> {
+        self.lemma_forms
+            .keys()
+            .map(|id| Self::lemma(words, *id))
+            .collect()
+    }
+
+    fn tag_set(&self) -> BTreeSet<String> {
+        self.tags.iter().map(tag_string).collect()
+    }
+
+    fn link_set(&self, words: &BTreeMap<OpCLid, String>) -> BTreeSet<Vec<Lemma>> {
+        self.lemmas_rows
+            .iter()
+            .map(|row| row.iter().copied().sorted().dedup().collect_vec())
+            .filter(|row| row.len() > 1)
+            .map(|row| row.into_iter().map(|id| Self::lemma(words, id)).collect())
+            .collect()
+    }
+
+    /// Сравнение словаря `self` (старого) с `new`.
+    ///
+    /// Вывод `parse` и `normalize` сравнивается для `words`, а без списка - для всех словоформ
+    /// обоих словарей. Слова обрабатываются пачками по [`DIFF_CHUNK`].
+    pub(crate) fn diff_dictionary(&self, new: &Self, words: Option<&[String]>) -> DictionaryDiff {
+        let words = match words {
+            Some(words) => {
+                let words = words.iter().cloned().unique().collect_vec();
+                words
+                    .chunks(DIFF_CHUNK)
+                    .flat_map(|chunk| self.word_diffs(new, chunk))
+                    .collect()
+            }
+            None => {
+                // Словоформы обоих словарей по возрастанию, без повторов.
+                let mut wordforms = self.fst.op().add(&new.fst).union().into_stream();
+                let mut chunk = Vec::with_capacity(DIFF_CHUNK);
+                let mut diffs = Vec::new();
+                while let Some((word, _)) = wordforms.next() {
+                    // Ключи fst собираются из строк и всегда в UTF-8.
+                    chunk.push(String::from_utf8_lossy(word).into_owned());
+                    if chunk.len() == DIFF_CHUNK {
+                        diffs.extend(self.word_diffs(new, &chunk));
+                        chunk.clear();
+                    }
+                }
+                diffs.extend(self.word_diffs(new, &chunk));
+                diffs
+            }
+        };
+
+        let (old_lemmas, new_lemmas) = (self.lemma_words(), new.lemma_words());
+        DictionaryDiff {
+            old: self.meta.clone(),
+            new: new.meta.clone(),
+            lemmas: SetDiff::new(&self.lemma_set(&old_lemmas), &new.lemma_set(&new_lemmas)),
+            tags: SetDiff::new(&self.tag_set(), &new.tag_set()),
+            links: SetDiff::new(&self.link_set(&old_lemmas), &new.link_set(&new_lemmas)),
+            words,
+        }
+    }
+
+    /// Различия вывода `parse` и `normalize` для пачки слов. Слова разбираются параллельно.
+    fn word_diffs(&self, new: &Self, words: &[String]) -> Vec<WordDiff> {
+        let old_parses = self.parse_batch(words);
+        let new_parses = new.parse_batch(words);
+        let old_normalized = self.normalize_batch(words);
+        let new_normalized = new.normalize_batch(words);
+
+        words
+            .iter()
+            .zip(old_parses.into_iter().zip(new_parses))
+            .zip(old_normalized.into_iter().zip(new_normalized))
+            .filter_map(
+                |((word, (old_parse, new_parse)), (old_normal, new_normal))| {
+                    let parse = Change::new(parses(old_parse), parses(new_parse));
+                    let normalize = Change::new(normal_forms(old_normal), normal_forms(new_normal));
+                    (parse.is_some() || normalize.is_some()).then(|| WordDiff {
+                        word: word.clone(),
+                        parse,
+                        normalize,
+                    })
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::xml_analyzer;
+
+    /// Новая ревизия тестового словаря: без предлога "из", со связью "сталь" - "стал".
+    fn revision() -> (String, String) {
+        let old = std::fs::read_to_string("data/test/tagger_dict.xml").unwrap();
+        let preposition = old
+            .lines()
+            .find(|line| line.contains(r#"<lemma id="5""#))
+            .unwrap();
+        let new = old
+            .replace(r#"revision="417257""#, r#"revision="417258""#)
+            .replace(&format!("{preposition}\n"), "")
+            .replace(
+                r#"<link id="1" from="3" to="3" type="1"/>"#,
+                "<link id=\"1\" from=\"3\" to=\"3\" type=\"1\"/>\n    <link id=\"2\" from=\"1\" to=\"2\" type=\"1\"/>",
+            );
+        (old, new)
+    }
+
+    #[test]
+    fn test_diff() {
+        let (old, new) = revision();
+        let (_old_dir, old) = xml_analyzer(&old);
+        let (_new_dir, new) = xml_analyzer(&new);
+
+        let diff = old.diff_dictionary(&new, None);
+        assert_eq!((diff.old.revision(), diff.new.revision()), (417257, 417258));
+        assert_eq!(diff.lemmas.added, vec![]);
+        assert_eq!(
+            diff.lemmas.removed,
+            vec![Lemma {
+                id: 5,
+                word: "из".to_string()
+            }]
+        );
+        assert_eq!(diff.tags.removed, vec!["PREP".to_string()]);
+        assert_eq!(
+            diff.links.added,
+            vec![vec![
+                Lemma {
+                    id: 1,
+                    word: "сталь".to_string()
+                },
+                Lemma {
+                    id: 2,
+                    word: "стал".to_string()
+                }
+            ]]
+        );
+        assert_eq!(
+            diff.words
+                .iter()
+                .map(|word| word.word.as_str())
+                .collect_vec(),
+            vec!["из", "стал", "стала", "стали", "стало"]
+        );
+
+        assert!(old.diff_dictionary(&old, None).is_empty());
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let (old, new) = revision();
+        let (_old_dir, old) = xml_analyzer(&old);
+        let (_new_dir, new) = xml_analyzer(&new);
+
+        let words = ["ножи", "из", "ножи"].map(String::from);
+        let diff = old.diff_dictionary(&new, Some(&words));
+        let word = diff.words.first().unwrap();
+
+        assert_eq!(diff.words.len(), 1);
+        assert_eq!(word.word, "из");
+        assert_eq!(
+            word.parse,
+            Some(Change {
+                old: vec!["из PREP Dictionary".to_string()],
+                new: vec![],
+            })
+        );
+    }
+}
@@ -0,0 +1,652 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    errors::{FioErr, ParseErr},
+    morph::grammemes::{Case, Gender, Grammem, Number, Other},
+    Method, MorphAnalyzer, ParsedWord,
+};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    derive_more::Display,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+/// Часть ФИО.
+pub enum FioKind {
+    Surname,
+    Name,
+    Patronymic,
+}
+
+impl FioKind {
+    /// Граммема OpenCorpora, которой часть ФИО помечена в словаре.
+    pub fn grammem(&self) -> Grammem {
+        match self {
+            FioKind::Surname => Grammem::Other(Other::Surname),
+            FioKind::Name => Grammem::Other(Other::Name),
+            FioKind::Patronymic => Grammem::Other(Other::Patronymic),
+        }
+    }
+}
+
+/// Возможные порядки следования частей ФИО.
+///
+/// При одинаковой уверенности выбирается тот порядок, который стоит раньше.
+const PATTERNS: [&[FioKind]; 8] = [
+    &[FioKind::Surname, FioKind::Name, FioKind::Patronymic],
+    &[FioKind::Name, FioKind::Patronymic, FioKind::Surname],
+    &[FioKind::Name, FioKind::Patronymic],
+    &[FioKind::Surname, FioKind::Name],
+    &[FioKind::Name, FioKind::Surname],
+    &[FioKind::Name],
+    &[FioKind::Surname],
+    &[FioKind::Patronymic],
+];
+
+/// Окончание, количество отрезаемых букв и окончания
+/// родительного, дательного, винительного, творительного и предложного падежей.
+///
+/// Пустые окончания без отрезания букв означают несклоняемое слово.
+type Endings = (&'static str, usize, [&'static str; 5]);
+
+const INDECLINABLE: [&str; 5] = ["", "", "", "", ""];
+
+/// Окончания мужских фамилий. Порядок важен: более длинные окончания проверяются раньше.
+const SURNAME_MASC: [Endings; 25] = [
+    ("енко", 0, INDECLINABLE),
+    ("ко", 0, INDECLINABLE),
+    ("их", 0, INDECLINABLE),
+    ("ых", 0, INDECLINABLE),
+    ("аго", 0, INDECLINABLE),
+    ("яго", 0, INDECLINABLE),
+    ("ский", 2, ["ого", "ому", "ого", "им", "ом"]),
+    ("цкий", 2, ["ого", "ому", "ого", "им", "ом"]),
+    ("ской", 2, ["ого", "ому", "ого", "им", "ом"]),
+    ("ый", 2, ["ого", "ому", "ого", "ым", "ом"]),
+    ("ов", 0, ["а", "у", "а", "ым", "е"]),
+    ("ев", 0, ["а", "у", "а", "ым", "е"]),
+    ("ёв", 0, ["а", "у", "а", "ым", "е"]),
+    ("ин", 0, ["а", "у", "а", "ым", "е"]),
+    ("ын", 0, ["а", "у", "а", "ым", "е"]),
+    ("а", 1, ["ы", "е", "у", "ой", "е"]),
+    ("я", 1, ["и", "е", "ю", "ей", "е"]),
+    ("й", 1, ["я", "ю", "я", "ем", "е"]),
+    ("ь", 1, ["я", "ю", "я", "ем", "е"]),
+    ("о", 0, INDECLINABLE),
+    ("е", 0, INDECLINABLE),
+    ("и", 0, INDECLINABLE),
+    ("у", 0, INDECLINABLE),
+    ("ю", 0, INDECLINABLE),
+    ("", 0, ["а", "у", "а", "ом", "е"]),
+];
+
+/// Окончания женских фамилий.
+const SURNAME_FEMN: [Endings; 13] = [
+    ("ская", 2, ["ой", "ой", "ую", "ой", "ой"]),
+    ("цкая", 2, ["ой", "ой", "ую", "ой", "ой"]),
+    ("ая", 2, ["ой", "ой", "ую", "ой", "ой"]),
+    ("яя", 2, ["ей", "ей", "юю", "ей", "ей"]),
+    ("ова", 1, ["ой", "ой", "у", "ой", "ой"]),
+    ("ева", 1, ["ой", "ой", "у", "ой", "ой"]),
+    ("ёва", 1, ["ой", "ой", "у", "ой", "ой"]),
+    ("ина", 1, ["ой", "ой", "у", "ой", "ой"]),
+    ("ына", 1, ["ой", "ой", "у", "ой", "ой"]),
+    ("ия", 1, ["и", "и", "ю", "ей", "и"]),
+    ("а", 1, ["ы", "е", "у", "ой", "е"]),
+    ("я", 1, ["и", "е", "ю", "ей", "е"]),
+    ("", 0, INDECLINABLE),
+];
+
+/// Окончания мужских отчеств.
+const PATRONYMIC_MASC: [Endings; 4] = [
+    ("вич", 0, ["а", "у", "а", "ем", "е"]),
+    ("тич", 0, ["а", "у", "а", "ем", "е"]),
+    ("ич", 0, ["а", "у", "а", "ом", "е"]),
+    ("", 0, INDECLINABLE),
+];
+
+/// Окончания женских отчеств.
+const PATRONYMIC_FEMN: [Endings; 2] =
+    [("на", 1, ["ы", "е", "у", "ой", "е"]), ("", 0, INDECLINABLE)];
+
+/// Окончания мужских имен.
+const NAME_MASC: [Endings; 11] = [
+    ("ий", 2, ["ия", "ию", "ия", "ием", "ии"]),
+    ("а", 1, ["ы", "е", "у", "ой", "е"]),
+    ("я", 1, ["и", "е", "ю", "ей", "е"]),
+    ("й", 1, ["я", "ю", "я", "ем", "е"]),
+    ("ь", 1, ["я", "ю", "я", "ем", "е"]),
+    ("о", 0, INDECLINABLE),
+    ("е", 0, INDECLINABLE),
+    ("и", 0, INDECLINABLE),
+    ("у", 0, INDECLINABLE),
+    ("ю", 0, INDECLINABLE),
+    ("", 0, ["а", "у", "а", "ом", "е"]),
+];
+
+/// Окончания женских имен.
+const NAME_FEMN: [Endings; 5] = [
+    ("ия", 1, ["и", "и", "ю", "ей", "и"]),
+    ("а", 1, ["ы", "е", "у", "ой", "е"]),
+    ("я", 1, ["и", "е", "ю", "ей", "е"]),
+    ("ь", 1, ["и", "и", "ь", "ью", "и"]),
+    ("", 0, INDECLINABLE),
+];
+
+/// Окончания, по которым слово похоже на фамилию, даже если его нет в словаре.
+const SURNAME_SUFFIXES: [&str; 18] = [
+    "ов", "ев", "ёв", "ин", "ын", "ова", "ева", "ёва", "ина", "ына", "ский", "цкий", "ская",
+    "цкая", "ской", "ко", "их", "ых",
+];
+
+/// Окончания женских фамилий для определения пола.
+const SURNAME_FEMN_SUFFIXES: [&str; 8] = ["ова", "ева", "ёва", "ина", "ына", "ская", "цкая", "ая"];
+
+/// Окончания мужских фамилий для определения пола.
+const SURNAME_MASC_SUFFIXES: [&str; 9] =
+    ["ов", "ев", "ёв", "ин", "ын", "ский", "цкий", "ской", "ый"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Часть ФИО: слово в исходном написании, его роль и словарный разбор, если он найден.
+pub struct FioPart {
+    word: String,
+    kind: FioKind,
+    parse: Option<ParsedWord>,
+}
+
+impl FioPart {
+    /// Получение слова в исходном написании.
+    pub fn word(&self) -> String {
+        self.word.to_owned()
+    }
+
+    /// Роль слова в ФИО.
+    pub fn kind(&self) -> FioKind {
+        self.kind
+    }
+
+    /// Словарный разбор слова.
+    /// `None`, если слова нет в словаре и оно склоняется по продуктивным окончаниям.
+    pub fn parse(&self) -> Option<ParsedWord> {
+        self.parse.to_owned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Разобранное ФИО.
+pub struct Fio {
+    parts: Vec<FioPart>,
+    gender: Option<Gender>,
+}
+
+impl Fio {
+    /// Части ФИО в порядке следования в исходной строке.
+    pub fn parts(&self) -> &[FioPart] {
+        &self.parts
+    }
+
+    /// Пол, определенный по отчеству, имени или фамилии.
+    pub fn gender(&self) -> Option<Gender> {
+        self.gender
+    }
+
+    /// Взятие нужной части ФИО.
+    pub fn get(&self, kind: FioKind) -> Option<&FioPart> {
+        self.parts.iter().find(|part| part.kind == kind)
+    }
+}
+
+impl MorphAnalyzer {
+    /// Разбор ФИО: определение фамилии, имени, отчества и пола.
+    ///
+    /// Для каждого слова ищутся словарные разборы с граммемами `Surn`, `Name`, `Patr`,
+    /// после чего выбирается наиболее вероятный порядок частей ФИО.
+    pub(crate) fn fio_parse(&self, fio: &str) -> Result<Fio, ParseErr> {
+        let words = fio.split_whitespace().collect_vec();
+
+        match words.len() {
+            0 => return Err(ParseErr::Fio(FioErr::EmptyFio)),
+            1..=3 => {}
+            _ => return Err(ParseErr::Fio(FioErr::TooManyParts(fio.to_string()))),
+        }
+
+        let lowercase = words.iter().map(|w| w.to_lowercase()).collect_vec();
+
+        let mut candidates = Vec::with_capacity(words.len());
+        for word in lowercase.iter() {
+            candidates.push(self.fio_candidates(word)?);
+        }
+
+        let mut best: Option<(&[FioKind], u32)> = None;
+        for pattern in PATTERNS.iter().filter(|p| p.len() == words.len()) {
+            if let Some(score) = pattern_score(pattern, &lowercase, &candidates) {
+                if best.is_none_or(|(_, best_score)| score > best_score) {
+                    best = Some((pattern, score));
+                }
+            }
+        }
+
+        let (pattern, _) =
+            best.ok_or_else(|| ParseErr::Fio(FioErr::UnknownStructure(fio.to_string())))?;
+        debug!("ФИО '{fio}' разобрано как {pattern:?}");
+
+        let gender = fio_gender(pattern, &lowercase, &candidates);
+
+        let parts = pattern
+            .iter()
+            .zip(words)
+            .zip(candidates)
+            .map(|((kind, word), candidates)| FioPart {
+                word: word.to_string(),
+                kind: *kind,
+                parse: pick_parse(candidates, *kind, gender),
+            })
+            .collect_vec();
+
+        Ok(Fio { parts, gender })
+    }
+
+    /// Склонение разобранного ФИО в нужный падеж.
+    ///
+    /// Словарные части склоняются по словарю, остальные - по продуктивным окончаниям
+    /// с учетом определенного пола.
+    pub(crate) fn fio_inflect(&self, fio: &Fio, case: Case) -> Result<Fio, ParseErr> {
+        let case = fio_case(case)?;
+        let mut parts = Vec::with_capacity(fio.parts.len());
+
+        for part in fio.parts.iter() {
+            let inflected = match &part.parse {
+                Some(parse) => self.fio_inflect_parse(parse, case)?,
+                None => None,
+            };
+
+            let part = match inflected {
+                Some(parse) => FioPart {
+                    word: restore_register(&part.word, &parse.word),
+                    kind: part.kind,
+                    parse: Some(parse),
+                },
+                None => {
+                    let word = rule_inflect(&part.word.to_lowercase(), part.kind, fio.gender, case);
+                    FioPart {
+                        word: restore_register(&part.word, &word),
+                        kind: part.kind,
+                        parse: None,
+                    }
+                }
+            };
+
+            parts.push(part);
+        }
+
+        Ok(Fio {
+            parts,
+            gender: fio.gender,
+        })
+    }
+
+    /// Словарные разборы слова, относящиеся к ФИО.
+    pub(crate) fn fio_candidates(&self, word: &str) -> Result<Vec<ParsedWord>, ParseErr> {
+        Ok(self
+            .parse_word(word)?
+            .0
+            .into_iter()
+            .filter(|parse| parse.method == Method::Dictionary)
+            .filter(|parse| {
+                [FioKind::Surname, FioKind::Name, FioKind::Patronymic]
+                    .iter()
+                    .any(|kind| parse.tags.contains(&kind.grammem()))
+            })
+            .collect())
+    }
+
+    /// Склонение словарной части ФИО в нужный падеж единственного числа.
+    pub(crate) fn fio_inflect_parse(
+        &self,
+        parse: &ParsedWord,
+        case: Case,
+    ) -> Result<Option<ParsedWord>, ParseErr> {
+        let grammemes = vec![Grammem::Case(case), Grammem::Number(Number::Singular)];
+
+        let inflected = match self.inflect_parsed_words(parse.to_owned(), Some(grammemes.clone())) {
+            Ok(inflected) => inflected,
+            // Не все буквы поддерживаются префиксным поиском по словарю (например, 'ё').
+            // В таком случае слово склоняется по продуктивным окончаниям.
+            // Предсказанные разборы не склоняются по словарю.
+            Err(err @ (ParseErr::Declension(_) | ParseErr::UnknownWord(_))) => {
+                debug!("{} склоняется по окончаниям: {err}", parse.word);
+                None
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(inflected
+            .and_then(|words| words.find(grammemes))
+            .map(|word| ParsedWord {
+                word: word.inflect_form,
+                tags: word.tags,
+                normal_form: word.normal_form,
+                method: word.method,
+            }))
+    }
+}
+
+/// Уверенность в том, что слова соответствуют порядку частей ФИО.
+///
+/// Словарный разбор с нужной граммемой дает больше уверенности, чем продуктивное окончание.
+/// Отчество без словарного разбора и характерного окончания невозможно.
+fn pattern_score(
+    pattern: &[FioKind],
+    words: &[String],
+    candidates: &[Vec<ParsedWord>],
+) -> Option<u32> {
+    let mut score = 0;
+
+    for ((kind, word), candidates) in pattern.iter().zip(words).zip(candidates) {
+        if candidates
+            .iter()
+            .any(|parse| parse.tags.contains(&kind.grammem()))
+        {
+            score += 2;
+        } else if is_rule_kind(word, *kind) {
+            score += 1;
+        } else if *kind == FioKind::Patronymic {
+            return None;
+        }
+    }
+
+    Some(score)
+}
+
+/// Похоже ли слово на часть ФИО по продуктивным окончаниям.
+fn is_rule_kind(word: &str, kind: FioKind) -> bool {
+    match kind {
+        FioKind::Surname => SURNAME_SUFFIXES.iter().any(|suffix| word.ends_with(suffix)),
+        FioKind::Patronymic => patronymic_gender(word).is_some(),
+        FioKind::Name => false,
+    }
+}
+
+/// Пол по окончанию отчества.
+fn patronymic_gender(word: &str) -> Option<Gender> {
+    if word.ends_with("ич") {
+        Some(Gender::Masculine)
+    } else if word.ends_with("вна") || word.ends_with("чна") {
+        Some(Gender::Feminine)
+    } else {
+        None
+    }
+}
+
+/// Пол по окончанию фамилии.
+fn surname_gender(word: &str) -> Option<Gender> {
+    if SURNAME_FEMN_SUFFIXES
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        Some(Gender::Feminine)
+    } else if SURNAME_MASC_SUFFIXES
+        .iter()
+        .any(|suffix| word.ends_with(suffix))
+    {
+        Some(Gender::Masculine)
+    } else {
+        None
+    }
+}
+
+/// Пол по словарным разборам части ФИО.
+///
+/// Предпочтение отдается разборам в именительном падеже:
+/// "иванова" - это и женская фамилия, и мужская в родительном падеже.
+fn dictionary_gender(candidates: &[ParsedWord], kind: FioKind) -> Option<Gender> {
+    let parses = candidates
+        .iter()
+        .filter(|parse| parse.tags.contains(&kind.grammem()))
+        .collect_vec();
+
+    let nominativus = parses
+        .iter()
+        .filter(|parse| parse.tags.contains(&Grammem::Case(Case::Nominativus)))
+        .collect_vec();
+    let parses = if nominativus.is_empty() {
+        parses
+    } else {
+        nominativus.into_iter().copied().collect_vec()
+    };
+
+    let genders = parses
+        .iter()
+        .filter_map(|parse| {
+            parse.tags.iter().find_map(|grammem| match grammem {
+                Grammem::Gender(gender @ (Gender::Masculine | Gender::Feminine)) => Some(*gender),
+                _ => None,
+            })
+        })
+        .unique()
+        .collect_vec();
+
+    match genders.as_slice() {
+        [gender] => Some(*gender),
+        _ => None,
+    }
+}
+
+/// Определение пола по отчеству, затем по имени, затем по фамилии.
+fn fio_gender(
+    pattern: &[FioKind],
+    words: &[String],
+    candidates: &[Vec<ParsedWord>],
+) -> Option<Gender> {
+    for kind in [FioKind::Patronymic, FioKind::Name, FioKind::Surname] {
+        let Some(i) = pattern.iter().position(|k| *k == kind) else {
+            continue;
+        };
+
+        let gender = dictionary_gender(&candidates[i], kind).or_else(|| match kind {
+            FioKind::Patronymic => patronymic_gender(&words[i]),
+            FioKind::Surname => surname_gender(&words[i]),
+            FioKind::Name => None,
+        });
+
+        if gender.is_some() {
+            return gender;
+        }
+    }
+
+    None
+}
+
+/// Выбор словарного разбора части ФИО с учетом пола.
+/// Предпочтение отдается разбору в именительном падеже.
+fn pick_parse(
+    candidates: Vec<ParsedWord>,
+    kind: FioKind,
+    gender: Option<Gender>,
+) -> Option<ParsedWord> {
+    let mut parses = candidates
+        .into_iter()
+        .filter(|parse| parse.tags.contains(&kind.grammem()))
+        .filter(|parse| match gender {
+            Some(gender) => {
+                parse.tags.contains(&Grammem::Gender(gender))
+                    || parse.tags.contains(&Grammem::Gender(Gender::Common))
+            }
+            None => true,
+        })
+        .collect_vec();
+
+    match parses
+        .iter()
+        .position(|parse| parse.tags.contains(&Grammem::Case(Case::Nominativus)))
+    {
+        Some(i) => Some(parses.swap_remove(i)),
+        None => parses.into_iter().next(),
+    }
+}
+
+/// Приведение падежа к одному из шести основных.
+pub(crate) fn fio_case(case: Case) -> Result<Case, ParseErr> {
+    match case {
+        Case::Fixed => Err(ParseErr::Fio(FioErr::UnsupportedCase(case))),
+        Case::Vocativus => Ok(Case::Nominativus),
+        Case::Gen2 => Ok(Case::Genetivus),
+        Case::Acc2 => Ok(Case::Accusativus),
+        Case::Loc2 => Ok(Case::Locativus),
+        case => Ok(case),
+    }
+}
+
+/// Склонение части ФИО по продуктивным окончаниям.
+///
+/// Слово ожидается в именительном падеже и нижнем регистре.
+pub(crate) fn rule_inflect(
+    word: &str,
+    kind: FioKind,
+    gender: Option<Gender>,
+    case: Case,
+) -> String {
+    let idx = match case {
+        Case::Genetivus => 0,
+        Case::Dativus => 1,
+        Case::Accusativus => 2,
+        Case::Ablativus => 3,
+        Case::Locativus => 4,
+        _ => return word.to_string(),
+    };
+
+    let feminine = gender == Some(Gender::Feminine);
+    let endings: &[Endings] = match (kind, feminine) {
+        (FioKind::Surname, false) => &SURNAME_MASC,
+        (FioKind::Surname, true) => &SURNAME_FEMN,
+        (FioKind::Name, false) => &NAME_MASC,
+        (FioKind::Name, true) => &NAME_FEMN,
+        (FioKind::Patronymic, false) => &PATRONYMIC_MASC,
+        (FioKind::Patronymic, true) => &PATRONYMIC_FEMN,
+    };
+
+    match endings.iter().find(|(suffix, ..)| word.ends_with(suffix)) {
+        Some((_, cut, forms)) => {
+            let len = word.chars().count();
+            let stem = word
+                .chars()
+                .take(len.saturating_sub(*cut))
+                .collect::<String>();
+            join_ending(&stem, forms[idx])
+        }
+        None => word.to_string(),
+    }
+}
+
+/// Присоединение окончания к основе с учетом правил правописания:
+/// "и" вместо "ы" после г, к, х, ж, ш, щ, ч и безударное "ей" вместо "ой" после ж, ш, щ, ч, ц.
+pub(crate) fn join_ending(stem: &str, ending: &str) -> String {
+    match (stem.chars().last(), ending) {
+        (Some('г' | 'к' | 'х' | 'ж' | 'ш' | 'щ' | 'ч'), "ы") => format!("{stem}и"),
+        (Some('ж' | 'ш' | 'щ' | 'ч' | 'ц'), "ой") => format!("{stem}ей"),
+        _ => format!("{stem}{ending}"),
+    }
+}
+
+/// Восстановление регистра исходного слова: "Иванов" -> "Иванову", "ИВАНОВ" -> "ИВАНОВУ".
+pub(crate) fn restore_register(original: &str, word: &str) -> String {
+    let mut chars = original.chars().filter(|c| c.is_alphabetic());
+
+    match chars.next() {
+        Some(first) if first.is_uppercase() => {
+            if chars.clone().next().is_some() && chars.all(|c| c.is_uppercase()) {
+                word.to_uppercase()
+            } else {
+                let mut word_chars = word.chars();
+                match word_chars.next() {
+                    Some(first) => first.to_uppercase().chain(word_chars).collect(),
+                    None => String::new(),
+                }
+            }
+        }
+        _ => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test_case("Иванов Иван Иванович", Case::Dativus => "Иванову Ивану Ивановичу"; "full_dativus")]
+    #[test_case("Иванов Иван Иванович", Case::Ablativus => "Ивановым Иваном Ивановичем"; "full_ablativus")]
+    #[test_case("Иван Иванович Иванов", Case::Genetivus => "Ивана Ивановича Иванова"; "name_first_genetivus")]
+    #[test_case("Иванова Мария Ивановна", Case::Genetivus => "Ивановой Марии Ивановны"; "femn_genetivus")]
+    #[test_case("Иванова Мария Ивановна", Case::Accusativus => "Иванову Марию Ивановну"; "femn_accusativus")]
+    #[test_case("Сидоров Сергей Петрович", Case::Dativus => "Сидорову Сергею Петровичу"; "unknown_surname_dativus")]
+    #[test_case("Петровская Мария", Case::Ablativus => "Петровской Марией"; "adjective_surname_ablativus")]
+    #[test_case("Шевченко Илья Никитич", Case::Locativus => "Шевченко Илье Никитиче"; "indeclinable_surname_locativus")]
+    #[test_case("ИВАНОВ ИВАН", Case::Dativus => "ИВАНОВУ ИВАНУ"; "uppercase_dativus")]
+    fn test_inflect_fio(fio: &str, case: Case) -> String {
+        let anal = shared_analyzer("data/test/fio_dict.xml");
+        anal.inflect_fio(fio, case).unwrap().to_string()
+    }
+
+    #[test_case("Иванов Иван Иванович" => Some(Gender::Masculine); "by_patronymic_masc")]
+    #[test_case("Мария Ивановна" => Some(Gender::Feminine); "by_patronymic_femn")]
+    #[test_case("Иванова" => Some(Gender::Feminine); "by_surname")]
+    #[test_case("Сидорова Анна" => Some(Gender::Feminine); "by_name")]
+    #[test_case("Никита" => Some(Gender::Masculine); "by_name_in_a")]
+    fn test_fio_gender(fio: &str) -> Option<Gender> {
+        let anal = shared_analyzer("data/test/fio_dict.xml");
+        anal.parse_fio(fio).unwrap().gender()
+    }
+
+    #[test]
+    fn test_fio_parts() {
+        let anal = shared_analyzer("data/test/fio_dict.xml");
+        let fio = anal.parse_fio("Иван Иванович Сидоров").unwrap();
+
+        assert_eq!(
+            fio.parts().iter().map(|p| p.kind()).collect_vec(),
+            vec![FioKind::Name, FioKind::Patronymic, FioKind::Surname]
+        );
+        assert!(fio.get(FioKind::Name).unwrap().parse().is_some());
+        assert!(fio.get(FioKind::Surname).unwrap().parse().is_none());
+    }
+
+    #[test]
+    fn test_fio_errors() {
+        let anal = shared_analyzer("data/test/fio_dict.xml");
+
+        assert!(anal.parse_fio("   ").is_err());
+        assert!(anal.parse_fio("Иванов Иван Иванович Второй").is_err());
+        assert!(anal.inflect_fio("Иванов Иван", Case::Fixed).is_err());
+    }
+
+    #[test_case("кузьмин", FioKind::Surname, Gender::Masculine, Case::Ablativus => "кузьминым")]
+    #[test_case("петровский", FioKind::Surname, Gender::Masculine, Case::Dativus => "петровскому")]
+    #[test_case("смирнова", FioKind::Surname, Gender::Feminine, Case::Accusativus => "смирнову")]
+    #[test_case("черных", FioKind::Surname, Gender::Feminine, Case::Genetivus => "черных")]
+    #[test_case("глинка", FioKind::Surname, Gender::Masculine, Case::Genetivus => "глинки")]
+    #[test_case("шмидт", FioKind::Surname, Gender::Feminine, Case::Dativus => "шмидт")]
+    #[test_case("ильич", FioKind::Patronymic, Gender::Masculine, Case::Ablativus => "ильичом")]
+    #[test_case("любовь", FioKind::Name, Gender::Feminine, Case::Ablativus => "любовью")]
+    #[test_case("василий", FioKind::Name, Gender::Masculine, Case::Locativus => "василии")]
+    #[test_case("маша", FioKind::Name, Gender::Feminine, Case::Genetivus => "маши")]
+    #[test_case("маша", FioKind::Name, Gender::Feminine, Case::Ablativus => "машей")]
+    #[test_case("ольга", FioKind::Name, Gender::Feminine, Case::Genetivus => "ольги")]
+    #[test_case("ольга", FioKind::Name, Gender::Feminine, Case::Ablativus => "ольгой")]
+    #[test_case("саша", FioKind::Name, Gender::Masculine, Case::Genetivus => "саши")]
+    #[test_case("саша", FioKind::Name, Gender::Masculine, Case::Ablativus => "сашей")]
+    #[test_case("лука", FioKind::Name, Gender::Masculine, Case::Genetivus => "луки")]
+    fn test_rule_inflect(word: &str, kind: FioKind, gender: Gender, case: Case) -> String {
+        rule_inflect(word, kind, Some(gender), case)
+    }
+}
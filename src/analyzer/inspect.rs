@@ -0,0 +1,87 @@
+use itertools::Itertools;
+
+use super::{LemmaRowId, OpCLid, Tag};
+use crate::{errors::ParseErr, morph::grammemes::Form, MorphAnalyzer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Словарный разбор слова вместе со строкой связанных лемм `OpenCorpora`.
+///
+/// По строке лемм собираются нормальная форма и все склонения/спряжения разбора.
+pub struct LemmaRow {
+    pub tag: Tag,
+    pub normal_form: String,
+    pub form: Form,
+    /// Индекс в `LemmasRows`.
+    pub row: LemmaRowId,
+    /// `OpenCorpora's LemmaId`, слитые между собой через `links`.
+    pub lemmas: Vec<OpCLid>,
+    /// Количество форм всех лемм строки в обратном индексе словаря.
+    pub forms: usize,
+}
+
+impl MorphAnalyzer {
+    /// Строки лемм всех словарных разборов слова в порядке хранения в словаре.
+    pub(crate) fn lemma_rows_word(&self, word: &str) -> Result<Vec<LemmaRow>, ParseErr> {
+        let id = self
+            .fst
+            .get(word.as_bytes())
+            .ok_or_else(|| ParseErr::UnknownWord(word.to_string()))?;
+
+        self.get_parse(id)?
+            .iter()
+            .map(|parse| {
+                let lemmas = self.get_row_id(parse.lemma_row_id)?.to_owned();
+                let mut forms = 0;
+                for id in lemmas.iter().unique() {
+                    for (_, parse_id) in self.lemma_forms.get(id).into_iter().flatten() {
+                        forms += self
+                            .get_parse(*parse_id)?
+                            .iter()
+                            .filter(|parse| parse.form.id() == Some(*id as u64))
+                            .count();
+                    }
+                }
+
+                Ok(LemmaRow {
+                    tag: self.get_tag(parse.tag)?.to_owned(),
+                    normal_form: self.get_lemmas(parse.normal_form)?.to_string(),
+                    form: parse.form,
+                    row: parse.lemma_row_id,
+                    lemmas,
+                    forms,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test_case("стали" => vec!["сталь [1]"; 5].into_iter().chain(["стать [2, 8]"]).collect::<Vec<_>>())]
+    #[test_case("ножи" => vec!["нож [3, 3]"; 4])]
+    fn test_lemma_rows(word: &str) -> Vec<String> {
+        let anal = shared_analyzer("data/test/tagger_dict.xml");
+
+        let rows = anal.lemma_rows_word(word).unwrap();
+        assert!(rows
+            .iter()
+            .all(|row| !row.lemmas.is_empty() && row.forms > 0));
+        rows.into_iter()
+            .map(|row| format!("{} {:?}", row.normal_form, row.lemmas))
+            .collect()
+    }
+
+    #[test]
+    fn test_lemma_rows_unknown() {
+        let anal = shared_analyzer("data/test/tagger_dict.xml");
+
+        assert!(matches!(
+            anal.lemma_rows_word("бутявка"),
+            Err(ParseErr::UnknownWord(word)) if word == "бутявка"
+        ));
+    }
+}
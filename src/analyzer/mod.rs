@@ -1,5 +1,4 @@
 use crate::{
-    analyzer::vangovanie::VangovanieRes,
     errors::{MopsErr, MopsResult, ParseErr},
     morph::grammemes::{Form, Grammem},
     InflectWord, Method, MorphAnalyzer, NormalizedWord, ParsedWord, Vangovanie,
@@ -10,7 +9,7 @@ use serde::{Deserialize, Serialize};
 use smallstr::SmallString;
 use smallvec::SmallVec;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     path::PathBuf,
 };
 use tracing::debug;
@@ -30,16 +29,55 @@ pub const SMALLLEMMA: usize = 16;
 
 /// Сборка словаря
 pub(crate) mod dictionary;
-pub use dictionary::Dictionary;
-
+pub use dictionary::{Dictionary, Meta};
+
+/// Параллельная обработка слов пачкой.
+pub(crate) mod batch;
+/// Кэш результатов для частых слов.
+pub(crate) mod cache;
+pub(crate) use cache::AnalyzerCache;
+pub use cache::{CacheConfig, CacheReport, CacheStats};
 /// Сборка префиксного поиска по fst::Stream для улучшения производительности.
 pub(crate) mod declension;
+/// Разбор и склонение ФИО.
+pub(crate) mod fio;
+pub use fio::{Fio, FioKind, FioPart};
 /// Вспомогательные функции морфологизатора для разборов слов.
 pub(crate) mod morpholyzer;
+/// Запись чисел словами.
+pub(crate) mod numeral;
+/// Образование отчеств от имен.
+pub(crate) mod patronymic;
+/// Шаблоны сообщений с согласованием слов.
+pub(crate) mod template;
+pub use numeral::{Numeral, NumeralKind, MAX_NUMERAL};
+pub use template::{Template, TemplateArg};
+/// Анализ текста по токенам.
+pub(crate) mod text;
+pub use text::{AnalyzedToken, PseudoTag, TextAnalysis, TokenAnalysis};
+/// Снятие неоднозначности разбора по контексту.
+pub(crate) mod tagger;
+pub use tagger::{Tagger, TAGGER_FILE};
+/// Сравнение двух собранных словарей.
+pub(crate) mod diff;
+pub use diff::{Change, DictionaryDiff, Lemma, SetDiff, WordDiff};
+/// Статистика содержимого словаря.
+pub(crate) mod stats;
+/// Пользовательские словари поверх основного.
+pub(crate) mod user;
+pub use stats::{DictionaryStats, MemoryStats};
 /// Предугадывание слов.
 pub(crate) mod vangovanie;
+pub use vangovanie::VangovanieRes;
+/// Отладочный просмотр словаря: строки лемм разборов слова.
+pub(crate) mod inspect;
+pub use inspect::LemmaRow;
+/// Разборы без аллокаций, заимствующие данные словаря.
+pub(crate) mod view;
+pub use view::{ParseIter, ParsedWordRef};
 
 pub mod pretty_display;
+pub use pretty_display::Table;
 
 /// Набор граммем слова.
 pub type Tag = SmallVec<[Grammem; SMALLTAG]>;
@@ -56,6 +94,11 @@ pub type ParseTable = Vec<Vec<Parse>>;
 pub type OpCLid = u32;
 /// Все слитые между собой для нормализации OpenCorpora's LemmaId.
 pub type LemmasRows = Vec<Vec<OpCLid>>;
+/// Обратный индекс: OpenCorpora's LemmaId -> формы леммы вместе с их индексами в `ParseTable`,
+/// то есть пары ключ-значение fst, по алфавиту.
+pub type LemmaForms = BTreeMap<OpCLid, Vec<(String, u64)>>;
+/// Нормальная форма -> чередующиеся начала ее форм, например, "человек" -> "лю".
+pub type PrefixAlternations = BTreeMap<String, Vec<String>>;
 
 #[derive(
     Debug, PartialEq, Eq, Ord, PartialOrd, Clone, Serialize, Deserialize, Allocative, Hash,
@@ -95,26 +138,36 @@ pub struct VangaItem {
 pub struct ParsedWords(pub Vec<ParsedWord>);
 
 impl ParsedWords {
+    /// Слова нет в словаре, и предсказать его разбор не удалось.
+    pub fn is_unknown(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn find(self, memes: Vec<Grammem>) -> Option<ParsedWord> {
         self.0
             .into_iter()
-            .find(|w| memes.iter().all(|meme| w.clone().tag().contains(meme)))
+            .find(|w| memes.iter().all(|meme| w.tags.contains(meme)))
     }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
 /// Вектор нормализованных слов.
 pub struct NormalizedWords(pub Vec<NormalizedWord>);
 
 impl NormalizedWords {
+    /// Слова нет в словаре, и предсказать его нормальную форму не удалось.
+    pub fn is_unknown(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn find(self, memes: Vec<Grammem>) -> Option<NormalizedWord> {
         self.0
             .into_iter()
-            .find(|w| memes.iter().all(|meme| w.clone().tag().contains(meme)))
+            .find(|w| memes.iter().all(|meme| w.tags.contains(meme)))
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 /// Вектор слов в соответствующей форме.
 pub struct InflectWords(pub Vec<InflectWord>);
 
@@ -122,30 +175,42 @@ impl InflectWords {
     pub fn find(self, memes: Vec<Grammem>) -> Option<InflectWord> {
         self.0
             .into_iter()
-            .find(|w| memes.iter().all(|meme| w.clone().tag().contains(meme)))
+            .find(|w| memes.iter().all(|meme| w.tags.contains(meme)))
     }
 }
 
 impl MorphAnalyzer {
     /// Создание анализатора из словаря.
     pub fn from_dictionary(dictionary: Dictionary, fst: PathBuf) -> MopsResult<Self> {
+        Ok(Self::from_fst(dictionary, Self::to_bytes_map(&fst)?))
+    }
+
+    /// Создание анализатора из словаря и уже загруженного fst.
+    pub(crate) fn from_fst(dictionary: Dictionary, fst: Map<Vec<u8>>) -> Self {
         let Dictionary {
-            meta: _,
+            meta,
             word_parses,
             tags,
             lemmas,
             paradigms,
             lemmas_rows,
+            lemma_forms,
+            alternations,
         } = dictionary;
 
-        Ok(Self {
-            fst: Self::to_bytes_map(&fst)?,
+        Self {
+            meta,
+            fst,
             word_parses,
             tags,
             lemmas,
             paradigms,
             lemmas_rows,
-        })
+            lemma_forms,
+            alternations,
+            cache: None,
+            user: None,
+        }
     }
 
     /// Взятие бинарного представления из словаря на диске в RAM.
@@ -158,7 +223,17 @@ impl MorphAnalyzer {
     }
 
     /// Парсинг слова.
+    ///
+    /// Разборы по пользовательским словарям идут перед разборами по основному.
     pub fn parse_word(&self, word: &str) -> Result<ParsedWords, ParseErr> {
+        match self.layered(word, |anal| Ok(anal.parse_dictionary(word)?.0))? {
+            Some(parsed) => Ok(ParsedWords(parsed)),
+            None => self.parse_dictionary(word),
+        }
+    }
+
+    /// Парсинг слова без пользовательских словарей.
+    fn parse_dictionary(&self, word: &str) -> Result<ParsedWords, ParseErr> {
         let map = &self.fst;
         let mut parsed = ParsedWords::default();
 
@@ -184,19 +259,22 @@ impl MorphAnalyzer {
                         ..
                     } in vanga
                     {
-                        let normal_form = match &method {
-                            Vangovanie::KnownPrefix(affix) | Vangovanie::UnknownPrefix(affix) => {
-                                format!("{affix}{normal_form}")
-                            }
-                            Vangovanie::Postfix => return Err(ParseErr::FutureRelease),
+                        // Предсказание по окончанию отключено, остаются только приставки.
+                        let (Vangovanie::KnownPrefix(affix) | Vangovanie::UnknownPrefix(affix)) =
+                            &method
+                        else {
+                            continue;
                         };
 
-                        parsed.0.push(ParsedWord {
+                        let parsed_word = ParsedWord {
                             word: word.to_string(),
                             tags,
-                            normal_form,
+                            normal_form: format!("{affix}{normal_form}"),
                             method: Method::Vangovanie(method),
-                        })
+                        };
+                        if !parsed.0.contains(&parsed_word) {
+                            parsed.0.push(parsed_word)
+                        }
                     }
                 }
             }
@@ -206,7 +284,17 @@ impl MorphAnalyzer {
     }
 
     /// Нормализация слова.
+    ///
+    /// Нормальные формы по пользовательским словарям идут перед нормальными формами по основному.
     pub fn normalized_word(&self, word: &str) -> Result<NormalizedWords, ParseErr> {
+        match self.layered(word, |anal| Ok(anal.normalize_dictionary(word)?.0))? {
+            Some(normalized) => Ok(NormalizedWords(normalized)),
+            None => self.normalize_dictionary(word),
+        }
+    }
+
+    /// Нормализация слова без пользовательских словарей.
+    fn normalize_dictionary(&self, word: &str) -> Result<NormalizedWords, ParseErr> {
         let map = &self.fst;
         let mut normalized = NormalizedWords::default();
 
@@ -219,22 +307,9 @@ impl MorphAnalyzer {
                     if parse.form.is_normal() {
                         normalized.0.push(self.try_into_normalized(parse)?)
                     } else {
-                        // Нам нужно брать только те нормальные формы, которые имеют отношение к соответствующему парсингу.
-                        let lemmas_link = self.get_row_id(parse.lemma_row_id)?;
-
-                        let word = self.get_lemmas(parse.normal_form)?.to_string();
-                        let id = map
-                            .get(&word)
-                            .ok_or_else(|| ParseErr::LostNormalForm(word.clone()))?;
-                        let vec_parses = self.get_parse(id)?;
-
-                        for parse in vec_parses.iter() {
-                            let normalized_word = self.try_into_normalized(parse)?;
-                            if parse.form.is_normal()
-                                && !normalized.0.contains(&normalized_word)
-                                // Нам нужно брать только те нормальные формы, которые имеют отношение к соответствующему парсингу.
-                                && lemmas_link.contains(&(parse.form.id().unwrap() as u32))
-                            {
+                        let word = self.get_lemmas(parse.normal_form)?;
+                        for normalized_word in self.row_normal_forms(word, parse.lemma_row_id)? {
+                            if !normalized.0.contains(&normalized_word) {
                                 normalized.0.push(normalized_word)
                             }
                         }
@@ -247,7 +322,12 @@ impl MorphAnalyzer {
             None => {
                 if let Some(vanga) = self.vangovanie(word)? {
                     for VangovanieRes {
-                        tags, form, method, ..
+                        tags,
+                        form,
+                        method,
+                        normal_form,
+                        lemma_row_id,
+                        ..
                     } in vanga
                     {
                         if form.is_normal() {
@@ -255,9 +335,27 @@ impl MorphAnalyzer {
                                 normal_word: word.to_owned(),
                                 tags,
                                 method: Method::Vangovanie(method),
-                            })
-                        } else {
-                            return Err(ParseErr::FutureRelease);
+                            });
+                            continue;
+                        }
+
+                        // Предсказание по окончанию отключено, остаются только приставки.
+                        let (Vangovanie::KnownPrefix(affix) | Vangovanie::UnknownPrefix(affix)) =
+                            &method
+                        else {
+                            continue;
+                        };
+
+                        // Нормальная форма основы берется из той же строки лемм, приставка переносится на нее.
+                        for stem in self.row_normal_forms(&normal_form, lemma_row_id)? {
+                            let normalized_word = NormalizedWord {
+                                normal_word: format!("{affix}{}", stem.normal_word),
+                                tags: stem.tags,
+                                method: Method::Vangovanie(method.clone()),
+                            };
+                            if !normalized.0.contains(&normalized_word) {
+                                normalized.0.push(normalized_word)
+                            }
                         }
                     }
                 }
@@ -266,6 +364,29 @@ impl MorphAnalyzer {
 
         Ok(normalized)
     }
+
+    /// Нормальные формы слова, относящиеся к строке лемм разбора.
+    fn row_normal_forms(
+        &self,
+        word: &str,
+        lemma_row_id: LemmaRowId,
+    ) -> Result<Vec<NormalizedWord>, ParseErr> {
+        // Нам нужно брать только те нормальные формы, которые имеют отношение к соответствующему парсингу.
+        let lemmas_link = self.get_row_id(lemma_row_id)?;
+
+        let id = self
+            .fst
+            .get(word)
+            .ok_or_else(|| ParseErr::LostNormalForm(word.to_string()))?;
+
+        let mut normal_forms = Vec::new();
+        for parse in self.get_parse(id)?.iter() {
+            if parse.form.is_normal() && lemmas_link.contains(&(parse.form.id().unwrap() as u32)) {
+                normal_forms.push(self.try_into_normalized(parse)?)
+            }
+        }
+        Ok(normal_forms)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -284,6 +405,22 @@ impl MorphAnalyzer {
         &self,
         word: &str,
         grammemes: Option<Vec<Grammem>>,
+    ) -> Result<Option<InflectWords>, ParseErr> {
+        let layered = self.layered(word, |anal| {
+            let inflect = anal.inflect_dictionary(word, grammemes.clone())?;
+            Ok(inflect.map(|inflect| inflect.0).unwrap_or_default())
+        })?;
+        match layered {
+            Some(forms) => Ok((!forms.is_empty()).then_some(InflectWords(forms))),
+            None => self.inflect_dictionary(word, grammemes),
+        }
+    }
+
+    /// Приведение слова к нужной форме без пользовательских словарей.
+    fn inflect_dictionary(
+        &self,
+        word: &str,
+        grammemes: Option<Vec<Grammem>>,
     ) -> Result<Option<InflectWords>, ParseErr> {
         let map = &self.fst;
         let mut inflect = InflectWords::default();
@@ -298,7 +435,7 @@ impl MorphAnalyzer {
                     self.inflect_parse(word, parse, grammemes.clone(), &mut inflect)?;
                 }
             }
-            None => return Err(ParseErr::FutureRelease),
+            None => return Err(ParseErr::UnknownWord(word.to_string())),
         };
 
         if inflect.0.is_empty() {
@@ -317,6 +454,10 @@ impl MorphAnalyzer {
         word: ParsedWord,
         grammemes: Option<Vec<Grammem>>,
     ) -> Result<Option<InflectWords>, ParseErr> {
+        if let Some(user) = self.user_for_parse(&word)? {
+            return user.inflect_parsed_words(word, grammemes);
+        }
+
         let map = &self.fst;
         let mut inflect = InflectWords::default();
 
@@ -334,7 +475,7 @@ impl MorphAnalyzer {
 
                 self.inflect_parse(&word.word(), parse, grammemes, &mut inflect)?;
             }
-            None => return Err(ParseErr::FutureRelease),
+            None => return Err(ParseErr::UnknownWord(word.word())),
         }
 
         if inflect.0.is_empty() {
@@ -369,11 +510,15 @@ impl MorphAnalyzer {
                 .id()
                 .ok_or_else(|| ParseErr::LostLemmaId(word.to_string()))?;
 
-            let mut hash_set: HashMap<(String, Option<String>), Vec<WordForm>> = HashMap::new();
+            if self.lemma_forms.is_empty() {
+                let mut hash_set: HashMap<(String, Option<String>), Vec<WordForm>> = HashMap::new();
 
-            let id_forms = self.id_forms(word, &ids, Some(word_id), &grammemes);
-            self.collect_stream_hashset(word, &grammemes, id_forms, &mut hash_set)?;
-            self.iter_fst(&mut hash_set, inflect)?;
+                let id_forms = self.id_forms(word, &ids, Some(word_id), &grammemes);
+                self.collect_stream_hashset(word, &grammemes, id_forms, &mut hash_set)?;
+                self.iter_fst(&mut hash_set, inflect)?;
+            } else {
+                self.index_forms(&ids, Some(word_id), &grammemes, inflect)?;
+            }
         }
 
         Ok(())
@@ -390,6 +535,14 @@ impl MorphAnalyzer {
     /// ### Warn!
     /// Не быстрая функция.
     pub(crate) fn declension_word(&self, word: &str) -> Result<Vec<InflectWords>, ParseErr> {
+        match self.layered(word, |anal| anal.declension_dictionary(word))? {
+            Some(inflects) => Ok(inflects),
+            None => self.declension_dictionary(word),
+        }
+    }
+
+    /// Проход по всем склонениям/спряжениям слова без пользовательских словарей.
+    fn declension_dictionary(&self, word: &str) -> Result<Vec<InflectWords>, ParseErr> {
         let map = &self.fst;
         let mut inflects = Vec::new();
 
@@ -412,7 +565,7 @@ impl MorphAnalyzer {
                 }
             }
 
-            None => return Err(ParseErr::FutureRelease),
+            None => return Err(ParseErr::UnknownWord(word.to_string())),
         }
 
         Ok(inflects)
@@ -430,6 +583,10 @@ impl MorphAnalyzer {
         &self,
         word: &ParsedWord,
     ) -> Result<Option<InflectWords>, ParseErr> {
+        if let Some(user) = self.user_for_parse(word)? {
+            return user.declension_parsed_word(word);
+        }
+
         let map = &self.fst;
         let mut inflect = InflectWords::default();
 
@@ -449,7 +606,7 @@ impl MorphAnalyzer {
                 let ids = self.get_row_id(parse.lemma_row_id)?;
                 self.declension_ids(&word.word(), ids, &mut inflect)?;
             }
-            None => return Err(ParseErr::FutureRelease),
+            None => return Err(ParseErr::UnknownWord(word.word())),
         }
 
         if inflect.0.is_empty() {
@@ -462,13 +619,18 @@ impl MorphAnalyzer {
     /// Склонение/спряжение всех слов, стоящих в одной связи
     /// (`ids` - id лемм из `OpenCorpora`, которые как-то связаны через `links`).
     ///
-    /// При этом используется префиксное ограничение для `fst::Stream`, чтобы сократить время хождения по словарю.
+    /// Формы берутся из обратного индекса словаря. Для словарей, собранных без индекса,
+    /// формы ищутся проходом по `fst::Stream` в префиксных ограничениях.
     fn declension_ids(
         &self,
         word: &str,
         ids: &[u32],
         inflect: &mut InflectWords,
     ) -> Result<(), ParseErr> {
+        if !self.lemma_forms.is_empty() {
+            return self.index_forms(ids, None, &None, inflect);
+        }
+
         let mut hash_set: HashMap<(String, Option<String>), Vec<WordForm>> = HashMap::new();
 
         let id_forms = self.id_forms(word, ids, None, &None);
@@ -482,9 +644,12 @@ mod tests {
     use super::*;
     use crate::{
         grams,
-        morph::grammemes::{Case, Gender, ParteSpeech},
+        morph::grammemes::{Case, Gender, Number, ParteSpeech},
+        test_infrastructure::infrastructure::{analyzer, shared_analyzer},
         Method,
     };
+    use itertools::Itertools;
+    use test_case::test_case;
 
     #[test]
     fn test_find_parsed() {
@@ -518,6 +683,95 @@ mod tests {
         )
     }
 
+    #[test_case("стали")]
+    #[test_case("ножей")]
+    #[test_case("новыми")]
+    #[test_case("нами")]
+    fn test_lemma_forms_index(word: &str) {
+        let indexed = shared_analyzer("data/test/tagger_dict.xml");
+        assert!(!indexed.lemma_forms.is_empty());
+
+        let (_tmp_dir, mut scanned) = analyzer("data/test/tagger_dict.xml");
+        scanned.lemma_forms.clear();
+
+        let forms = |inflects: Vec<InflectWords>| {
+            inflects
+                .into_iter()
+                .flat_map(|inflect| inflect.0)
+                .map(|inflect| format!("{inflect:?}"))
+                .sorted()
+                .collect_vec()
+        };
+
+        assert!(!forms(indexed.declension(word).unwrap()).is_empty());
+        assert_eq!(
+            forms(indexed.declension(word).unwrap()),
+            forms(scanned.declension(word).unwrap())
+        );
+        assert_eq!(
+            forms(indexed.inflect_inizio(word).unwrap().into_iter().collect()),
+            forms(scanned.inflect_inizio(word).unwrap().into_iter().collect())
+        );
+        let plural = grams![Number::Plural];
+        assert_eq!(
+            forms(
+                indexed
+                    .inflect_forms(word, plural.clone())
+                    .unwrap()
+                    .into_iter()
+                    .collect()
+            ),
+            forms(
+                scanned
+                    .inflect_forms(word, plural)
+                    .unwrap()
+                    .into_iter()
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn test_unknown_word() {
+        let anal = shared_analyzer("data/test/template_dict.xml");
+
+        let word = "кваквакря";
+        assert!(anal.parse(word).unwrap().is_unknown());
+        assert!(anal.normalize(word).unwrap().is_unknown());
+
+        let unknown = |err| matches!(err, MopsErr::Parse(ParseErr::UnknownWord(w)) if w == word);
+        assert!(unknown(anal.inflect_inizio(word).unwrap_err()));
+        assert!(unknown(anal.declension(word).unwrap_err()));
+
+        // Предсказанное по приставке слово нормализуется по словарной основе.
+        let normalized = anal.normalize("суперфайлы").unwrap();
+        assert!(!normalized.is_unknown());
+        assert!(normalized
+            .0
+            .iter()
+            .all(|normal| normal.word() == "суперфайл"
+                && normal.method()
+                    == Method::Vangovanie(Vangovanie::KnownPrefix("супер".to_string()))));
+    }
+
+    #[test]
+    fn test_unknown_homonym() {
+        let anal = shared_analyzer("data/test/homonym_dict.xml");
+
+        // У "стать" есть омоним-существительное, но "стали" относится только к глаголу.
+        let normalized = anal.normalize("перестали").unwrap();
+        assert_eq!(normalized.0.len(), 1);
+        assert_eq!(normalized.0[0].word(), "перестать");
+        assert_eq!(
+            Grammem::pos_in_tag(&normalized.0[0].tag()),
+            Some(ParteSpeech::Verb)
+        );
+
+        let parsed = anal.parse("перестали").unwrap();
+        assert!(parsed.0.iter().all(|parse| parse.normal_form == "перестать"
+            && Grammem::pos_in_tag(&parse.tags) == Some(ParteSpeech::Verb)));
+    }
+
     #[test]
     fn test_inflect_form() {
         let anal = MorphAnalyzer::open("data/result/").unwrap();
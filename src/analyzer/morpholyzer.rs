@@ -108,10 +108,56 @@ impl MorphAnalyzer {
         })
     }
 
+    /// Формы слов лемм `ids` напрямую из обратного индекса словаря.
+    ///
+    /// Фильтрация разборов та же, что в `id_forms`: без граммем и с `word_id`
+    /// ищется только начальная форма леммы `word_id`.
+    pub(crate) fn index_forms(
+        &self,
+        ids: &[u32],
+        word_id: Option<u64>,
+        grammemes: &Option<Vec<Grammem>>,
+        inflect: &mut InflectWords,
+    ) -> Result<(), ParseErr> {
+        let ids = match (grammemes.is_none(), word_id) {
+            (true, Some(word_id)) => vec![word_id as u32],
+            _ => ids.to_vec(),
+        };
+
+        for id in ids {
+            for (form, parse_id) in self.lemma_forms.get(&id).into_iter().flatten() {
+                for parse in self.get_parse(*parse_id)? {
+                    if parse.form.id() != Some(id as u64) {
+                        continue;
+                    }
+                    if word_id.is_some()
+                        && grammemes.is_none()
+                        && !(parse.form.is_inizio() || parse.form.is_normal())
+                    {
+                        continue;
+                    }
+                    if let Some(grammemes) = grammemes.as_ref() {
+                        let tag = self.get_tag(parse.tag)?;
+                        if !grammemes.iter().all(|item| tag.contains(item)) {
+                            continue;
+                        }
+                    }
+
+                    let inflect_word = self.try_into_inflect(form.to_owned(), parse)?;
+                    if !inflect.0.contains(&inflect_word) {
+                        inflect.0.push(inflect_word);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Сбор префикс-ограничений для `fst::Stream`.
     ///
     /// Префикс-ограничения - это пара префиксов, внутри которых Streamer проходит fst-словарь,
-    /// игнорируя все остальные части словаря.
+    /// игнорируя все остальные части словаря. Нужны только словарям без обратного индекса.
     pub(crate) fn collect_stream_hashset<'a>(
         &'a self,
         word: &str,
@@ -131,7 +177,8 @@ impl MorphAnalyzer {
             let normal_form = self.get_lemmas(parse.normal_form)?;
 
             for (first, last) in
-                alphabet_stream(word, normal_form, tag.to_owned()).map_err(ParseErr::Declension)?
+                alphabet_stream(word, normal_form, tag.to_owned(), &self.alternations)
+                    .map_err(ParseErr::Declension)?
             {
                 let word_form = WordForm {
                     i,
@@ -149,7 +196,8 @@ impl MorphAnalyzer {
         Ok(())
     }
 
-    /// Итерация по fst::Stream с учетом префиксных ограничений для сокращения прохода.
+    /// Итерация по fst::Stream с учетом префиксных ограничений для сокращения прохода,
+    /// если у словаря нет обратного индекса.
     /// При итерации в `InflectWords` сохраняются только те формы,
     /// которые соответствуют индексу в fst::Map -> WordForm { i, ..}.
     pub(crate) fn iter_fst(
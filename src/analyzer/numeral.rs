@@ -0,0 +1,973 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::{
+    errors::{NumeralErr, ParseErr},
+    morph::grammemes::{Animacy, Case, Gender, Grammem, Number, Other, ParteSpeech},
+    Method, MorphAnalyzer, ParsedWord,
+};
+
+#[derive(Debug, Clone, Copy, derive_more::Display, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Разряд числительного.
+pub enum NumeralKind {
+    /// Количественное: "двадцать один".
+    Cardinal,
+    /// Порядковое: "двадцать первый".
+    Ordinal,
+    /// Собирательное: "двое".
+    Collective,
+}
+
+/// Наибольшее число, которое записывается словами: до триллионов включительно.
+pub const MAX_NUMERAL: u64 = 999_999_999_999_999;
+
+/// Количественные и порядковые числительные для единиц.
+const UNITS: [(&str, &str); 10] = [
+    ("ноль", "нулевой"),
+    ("один", "первый"),
+    ("два", "второй"),
+    ("три", "третий"),
+    ("четыре", "четвёртый"),
+    ("пять", "пятый"),
+    ("шесть", "шестой"),
+    ("семь", "седьмой"),
+    ("восемь", "восьмой"),
+    ("девять", "девятый"),
+];
+
+/// Количественные и порядковые числительные от десяти до девятнадцати.
+const TEENS: [(&str, &str); 10] = [
+    ("десять", "десятый"),
+    ("одиннадцать", "одиннадцатый"),
+    ("двенадцать", "двенадцатый"),
+    ("тринадцать", "тринадцатый"),
+    ("четырнадцать", "четырнадцатый"),
+    ("пятнадцать", "пятнадцатый"),
+    ("шестнадцать", "шестнадцатый"),
+    ("семнадцать", "семнадцатый"),
+    ("восемнадцать", "восемнадцатый"),
+    ("девятнадцать", "девятнадцатый"),
+];
+
+/// Количественные и порядковые числительные для десятков.
+const TENS: [(&str, &str); 10] = [
+    ("", ""),
+    ("", ""),
+    ("двадцать", "двадцатый"),
+    ("тридцать", "тридцатый"),
+    ("сорок", "сороковой"),
+    ("пятьдесят", "пятидесятый"),
+    ("шестьдесят", "шестидесятый"),
+    ("семьдесят", "семидесятый"),
+    ("восемьдесят", "восьмидесятый"),
+    ("девяносто", "девяностый"),
+];
+
+/// Количественные и порядковые числительные для сотен.
+const HUNDREDS: [(&str, &str); 10] = [
+    ("", ""),
+    ("сто", "сотый"),
+    ("двести", "двухсотый"),
+    ("триста", "трёхсотый"),
+    ("четыреста", "четырёхсотый"),
+    ("пятьсот", "пятисотый"),
+    ("шестьсот", "шестисотый"),
+    ("семьсот", "семисотый"),
+    ("восемьсот", "восьмисотый"),
+    ("девятьсот", "девятисотый"),
+];
+
+/// Названия разрядов: тысячи, миллионы, миллиарды, триллионы.
+const SCALES: [(&str, &str); 4] = [
+    ("тысяча", "тысячный"),
+    ("миллион", "миллионный"),
+    ("миллиард", "миллиардный"),
+    ("триллион", "триллионный"),
+];
+
+/// Собирательные числительные от двух до десяти.
+const COLLECTIVES: [&str; 9] = [
+    "двое",
+    "трое",
+    "четверо",
+    "пятеро",
+    "шестеро",
+    "семеро",
+    "восьмеро",
+    "девятеро",
+    "десятеро",
+];
+
+/// Основы в сложных порядковых числительных, которые не совпадают с родительным падежом:
+/// "стотысячный", "девяностомиллионный", "двадцатиоднотысячный".
+const COMPOUND_STEMS: [(&str, &str); 3] =
+    [("один", "одно"), ("сто", "сто"), ("девяносто", "девяносто")];
+
+/// Слово числительного до склонения: лемма, разряд и граммемы нужной формы.
+struct NumeralWord {
+    lemma: &'static str,
+    kind: NumeralKind,
+    grammemes: Vec<Grammem>,
+}
+
+impl NumeralWord {
+    fn new(lemma: &'static str, kind: NumeralKind, grammemes: Vec<Grammem>) -> Self {
+        Self {
+            lemma,
+            kind,
+            grammemes,
+        }
+    }
+}
+
+/// Часть записи числа: отдельное слово или сложное порядковое числительное ("двухтысячный").
+enum NumeralPiece {
+    Word(NumeralWord),
+    Compound(Vec<NumeralWord>, NumeralWord),
+}
+
+impl MorphAnalyzer {
+    /// Запись числа словами в нужном разряде, падеже, роде и числе.
+    ///
+    /// Из граммем берутся падеж (по умолчанию именительный), род (по умолчанию мужской)
+    /// и число (по умолчанию единственное, влияет только на порядковые числительные).
+    pub(crate) fn numeral_spell(
+        &self,
+        number: u64,
+        kind: NumeralKind,
+        grammemes: &[Grammem],
+    ) -> Result<String, ParseErr> {
+        if number > MAX_NUMERAL {
+            return Err(ParseErr::Numeral(NumeralErr::TooLarge(number)));
+        }
+
+        let case = grammemes
+            .iter()
+            .find_map(|grammem| match grammem {
+                Grammem::Case(case) => Some(numeral_case(*case)),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let gender = grammemes
+            .iter()
+            .find_map(|grammem| match grammem {
+                Grammem::Gender(gender) => Some(*gender),
+                _ => None,
+            })
+            .unwrap_or(Gender::Masculine);
+        let plural = grammemes.contains(&Grammem::Number(Number::Plural));
+
+        let pieces = match kind {
+            NumeralKind::Cardinal => cardinal_pieces(number, case, gender),
+            NumeralKind::Ordinal => ordinal_pieces(number, case, gender, plural),
+            NumeralKind::Collective => {
+                let lemma = (2..=10)
+                    .contains(&number)
+                    .then(|| COLLECTIVES[number as usize - 2])
+                    .ok_or(ParseErr::Numeral(NumeralErr::NoCollective(number)))?;
+                vec![NumeralPiece::Word(NumeralWord::new(
+                    lemma,
+                    kind,
+                    vec![Grammem::Case(case)],
+                ))]
+            }
+        };
+
+        let mut words = Vec::with_capacity(pieces.len());
+        for piece in pieces.iter() {
+            words.push(match piece {
+                NumeralPiece::Word(word) => self.numeral_form(word)?,
+                NumeralPiece::Compound(stems, last) => {
+                    let mut compound = String::new();
+                    for stem in stems {
+                        match COMPOUND_STEMS
+                            .iter()
+                            .find(|(lemma, _)| *lemma == stem.lemma)
+                        {
+                            Some((_, fixed)) => compound.push_str(fixed),
+                            None => compound.push_str(&self.numeral_form(stem)?),
+                        }
+                    }
+                    compound.push_str(&self.numeral_form(last)?);
+                    compound
+                }
+            });
+        }
+
+        let spelled = words.join(" ");
+        debug!("{number} -> {spelled}");
+        Ok(spelled)
+    }
+
+    /// Запись числа словами по его цифровой записи: "21" - количественное,
+    /// "21-й", "21-го" - порядковое числительное.
+    pub(crate) fn numeral_spell_str(
+        &self,
+        number: &str,
+        grammemes: &[Grammem],
+    ) -> Result<String, ParseErr> {
+        let not_a_number = || ParseErr::Numeral(NumeralErr::NotANumber(number.to_string()));
+
+        let (digits, kind) = match number.trim().split_once('-') {
+            Some((digits, suffix))
+                if !suffix.is_empty()
+                    && suffix.chars().count() <= 3
+                    && suffix.chars().all(|c| ('а'..='я').contains(&c)) =>
+            {
+                (digits, NumeralKind::Ordinal)
+            }
+            Some(_) => return Err(not_a_number()),
+            None => (number.trim(), NumeralKind::Cardinal),
+        };
+
+        let digits = digits
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(not_a_number());
+        }
+        let number = digits.parse::<u64>().map_err(|_| not_a_number())?;
+
+        self.numeral_spell(number, kind, grammemes)
+    }
+
+    /// Склонение слова числительного по словарю.
+    ///
+    /// Не у всех форм числительных в словаре есть род и число ("двух", "пяти"),
+    /// поэтому граммемы ослабляются, пока не найдется подходящая форма.
+    /// Для винительного падежа предпочитается неодушевленная форма.
+    fn numeral_form(&self, word: &NumeralWord) -> Result<String, ParseErr> {
+        let parse = self.numeral_lemma(word.lemma, word.kind)?;
+        let case = word
+            .grammemes
+            .iter()
+            .filter(|grammem| matches!(grammem, Grammem::Case(_)))
+            .copied()
+            .collect_vec();
+
+        let forms = self
+            .inflect_parsed_words(parse, Some(case))?
+            .unwrap_or_default();
+
+        for grammemes in relaxed_grammemes(&word.grammemes) {
+            let mut inanimate = grammemes.clone();
+            inanimate.push(Grammem::Animacy(Animacy::Inanimate));
+
+            if let Some(form) = forms
+                .clone()
+                .find(inanimate)
+                .or_else(|| forms.clone().find(grammemes))
+            {
+                return Ok(form.word());
+            }
+        }
+
+        Err(ParseErr::Numeral(NumeralErr::NoForm {
+            lemma: word.lemma.to_string(),
+            grammemes: word.grammemes.to_owned(),
+        }))
+    }
+
+    /// Словарный разбор леммы числительного нужного разряда.
+    ///
+    /// Количественные числительные в словаре бывают не только `NUMR`:
+    /// "тысяча" и "ноль" - существительные.
+    fn numeral_lemma(&self, lemma: &str, kind: NumeralKind) -> Result<ParsedWord, ParseErr> {
+        let parses = self
+            .parse_word(lemma)?
+            .0
+            .into_iter()
+            .filter(|parse| parse.method == Method::Dictionary)
+            .filter(|parse| parse.normal_form.replace('ё', "е") == lemma.replace('ё', "е"))
+            .collect_vec();
+
+        let is_kind = |parse: &ParsedWord| match kind {
+            NumeralKind::Cardinal => {
+                !parse.tags.contains(&Grammem::Other(Other::Ordinal))
+                    && !parse.tags.contains(&Grammem::Other(Other::Collection))
+                    && [
+                        ParteSpeech::Number,
+                        ParteSpeech::Noun,
+                        ParteSpeech::AdjectiveFull,
+                    ]
+                    .contains(&Grammem::pos_in_tag(&parse.tags).unwrap_or(ParteSpeech::Number))
+            }
+            NumeralKind::Ordinal => parse.tags.contains(&Grammem::Other(Other::Ordinal)),
+            NumeralKind::Collective => parse.tags.contains(&Grammem::Other(Other::Collection)),
+        };
+        let is_numr = |parse: &ParsedWord| {
+            parse
+                .tags
+                .contains(&Grammem::ParteSpeech(ParteSpeech::Number))
+        };
+
+        parses
+            .iter()
+            .filter(|parse| is_kind(parse))
+            .sorted_by_key(|parse| !is_numr(parse))
+            .next()
+            .cloned()
+            .ok_or_else(|| ParseErr::Numeral(NumeralErr::NotInDictionary(lemma.to_string())))
+    }
+}
+
+/// Приведение падежа к одному из шести основных.
+fn numeral_case(case: Case) -> Case {
+    match case {
+        Case::Gen2 => Case::Genetivus,
+        Case::Acc2 => Case::Accusativus,
+        Case::Loc2 => Case::Locativus,
+        Case::Vocativus | Case::Fixed => Case::Nominativus,
+        case => case,
+    }
+}
+
+/// Варианты граммем от самого точного к самому общему: без рода, без числа, без того и другого.
+fn relaxed_grammemes(grammemes: &[Grammem]) -> Vec<Vec<Grammem>> {
+    let without = |skip: &dyn Fn(&Grammem) -> bool| {
+        grammemes
+            .iter()
+            .filter(|grammem| !skip(grammem))
+            .copied()
+            .collect_vec()
+    };
+
+    [
+        grammemes.to_vec(),
+        without(&|grammem| matches!(grammem, Grammem::Gender(_))),
+        without(&|grammem| matches!(grammem, Grammem::Number(_))),
+        without(&|grammem| matches!(grammem, Grammem::Gender(_) | Grammem::Number(_))),
+    ]
+    .into_iter()
+    .unique()
+    .collect()
+}
+
+/// Ненулевые тройки цифр числа от старшего разряда к младшему: (разряд, значение).
+fn triads(number: u64) -> Vec<(usize, u64)> {
+    let mut triads = Vec::new();
+    let (mut rest, mut scale) = (number, 0);
+
+    while rest > 0 {
+        if rest % 1000 > 0 {
+            triads.push((scale, rest % 1000));
+        }
+        rest /= 1000;
+        scale += 1;
+    }
+
+    triads.reverse();
+    triads
+}
+
+/// Количественные и порядковые леммы трехзначного числа.
+fn triad_lemmas(triad: u64) -> Vec<(&'static str, &'static str)> {
+    let (hundreds, rest) = ((triad / 100) as usize, (triad % 100) as usize);
+    let mut lemmas = Vec::with_capacity(3);
+
+    if hundreds > 0 {
+        lemmas.push(HUNDREDS[hundreds]);
+    }
+    match rest {
+        0 => {}
+        1..=9 => lemmas.push(UNITS[rest]),
+        10..=19 => lemmas.push(TEENS[rest - 10]),
+        _ => {
+            lemmas.push(TENS[rest / 10]);
+            if rest % 10 > 0 {
+                lemmas.push(UNITS[rest % 10]);
+            }
+        }
+    }
+
+    lemmas
+}
+
+/// Род трехзначного числа: тысячи - женского рода, миллионы и выше - мужского.
+fn triad_gender(scale: usize, gender: Gender) -> Gender {
+    match scale {
+        0 => gender,
+        1 => Gender::Feminine,
+        _ => Gender::Masculine,
+    }
+}
+
+/// Граммемы существительного, согласованного с числом:
+/// "одна тысяча", "две тысячи", "пять тысяч", "двумя тысячами".
+pub(crate) fn counted_grammemes(triad: u64, case: Case) -> Vec<Grammem> {
+    let (last, last_two) = (triad % 10, triad % 100);
+    let one = last == 1 && last_two != 11;
+    let few = (2..=4).contains(&last) && !(12..=14).contains(&last_two);
+
+    let (case, number) = match case {
+        Case::Nominativus | Case::Accusativus if one => (case, Number::Singular),
+        Case::Nominativus | Case::Accusativus if few => (Case::Genetivus, Number::Singular),
+        Case::Nominativus | Case::Accusativus => (Case::Genetivus, Number::Plural),
+        case if one => (case, Number::Singular),
+        case => (case, Number::Plural),
+    };
+
+    vec![Grammem::Case(case), Grammem::Number(number)]
+}
+
+/// Количественное числительное из нескольких слов с согласованием разрядов.
+fn cardinal_pieces(number: u64, case: Case, gender: Gender) -> Vec<NumeralPiece> {
+    if number == 0 {
+        return vec![NumeralPiece::Word(NumeralWord::new(
+            UNITS[0].0,
+            NumeralKind::Cardinal,
+            vec![Grammem::Case(case), Grammem::Number(Number::Singular)],
+        ))];
+    }
+
+    triads(number)
+        .into_iter()
+        .flat_map(|(scale, triad)| triad_cardinal(scale, triad, case, gender))
+        .collect()
+}
+
+/// Слова трехзначного числа вместе с названием его разряда.
+/// Одна тысяча записывается просто как "тысяча".
+fn triad_cardinal(scale: usize, triad: u64, case: Case, gender: Gender) -> Vec<NumeralPiece> {
+    let mut pieces = Vec::new();
+    let grammemes = vec![
+        Grammem::Case(case),
+        Grammem::Gender(triad_gender(scale, gender)),
+    ];
+
+    if !(scale == 1 && triad == 1) {
+        pieces.extend(triad_lemmas(triad).into_iter().map(|(lemma, _)| {
+            NumeralPiece::Word(NumeralWord::new(
+                lemma,
+                NumeralKind::Cardinal,
+                grammemes.clone(),
+            ))
+        }));
+    }
+    if scale > 0 {
+        pieces.push(NumeralPiece::Word(NumeralWord::new(
+            SCALES[scale - 1].0,
+            NumeralKind::Cardinal,
+            counted_grammemes(triad, case),
+        )));
+    }
+
+    pieces
+}
+
+/// Порядковое числительное: все слова, кроме последнего, остаются количественными
+/// в именительном падеже, последнее - порядковое в нужной форме.
+///
+/// Круглые тысячи, миллионы и т.д. образуют сложное слово: "двухтысячный", "стомиллионный".
+fn ordinal_pieces(number: u64, case: Case, gender: Gender, plural: bool) -> Vec<NumeralPiece> {
+    let grammemes = if plural {
+        vec![Grammem::Case(case), Grammem::Number(Number::Plural)]
+    } else {
+        vec![
+            Grammem::Case(case),
+            Grammem::Gender(gender),
+            Grammem::Number(Number::Singular),
+        ]
+    };
+
+    let triads = triads(number);
+    let Some(((last_scale, last_triad), higher)) = triads.split_last() else {
+        return vec![NumeralPiece::Word(NumeralWord::new(
+            UNITS[0].1,
+            NumeralKind::Ordinal,
+            grammemes,
+        ))];
+    };
+
+    let mut pieces = higher
+        .iter()
+        .flat_map(|(scale, triad)| triad_cardinal(*scale, *triad, Case::Nominativus, gender))
+        .collect_vec();
+
+    if *last_scale == 0 {
+        let lemmas = triad_lemmas(*last_triad);
+        if let Some(((_, ordinal), cardinals)) = lemmas.split_last() {
+            pieces.extend(cardinals.iter().map(|(lemma, _)| {
+                NumeralPiece::Word(NumeralWord::new(
+                    lemma,
+                    NumeralKind::Cardinal,
+                    vec![Grammem::Case(Case::Nominativus)],
+                ))
+            }));
+            pieces.push(NumeralPiece::Word(NumeralWord::new(
+                ordinal,
+                NumeralKind::Ordinal,
+                grammemes,
+            )));
+        }
+    } else {
+        let stems = if *last_triad == 1 {
+            Vec::new()
+        } else {
+            triad_lemmas(*last_triad)
+                .into_iter()
+                .map(|(lemma, _)| {
+                    NumeralWord::new(
+                        lemma,
+                        NumeralKind::Cardinal,
+                        vec![Grammem::Case(Case::Genetivus)],
+                    )
+                })
+                .collect()
+        };
+        pieces.push(NumeralPiece::Compound(
+            stems,
+            NumeralWord::new(SCALES[last_scale - 1].1, NumeralKind::Ordinal, grammemes),
+        ));
+    }
+
+    pieces
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Числительное, записанное словами: значение, разряд, падеж и число словосочетания.
+pub struct Numeral {
+    value: u64,
+    kind: NumeralKind,
+    case: Option<Case>,
+    number: Option<Number>,
+}
+
+impl Numeral {
+    /// Значение числительного.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Разряд числительного.
+    pub fn kind(&self) -> NumeralKind {
+        self.kind
+    }
+
+    /// Падеж словосочетания, если его удалось определить.
+    pub fn case(&self) -> Option<Case> {
+        self.case
+    }
+
+    /// Число словосочетания, если его удалось определить.
+    pub fn number(&self) -> Option<Number> {
+        self.number
+    }
+}
+
+/// Значение леммы числительного: число, разряд и является ли лемма названием разряда.
+type LemmaValue = (u64, NumeralKind, bool);
+
+/// Слово числительного, распознанное в тексте.
+struct NumeralToken {
+    value: u64,
+    kind: NumeralKind,
+    /// Название разряда: "тысяча", "миллион", "тысячный".
+    scale: bool,
+    /// Множитель сложного порядкового числительного: "двухтысячный" -> 2.
+    prefix: Option<u64>,
+    /// Возможные падежи и числа слова.
+    forms: Vec<(Case, Option<Number>)>,
+}
+
+/// Порядок выбора падежа, если словосочетание допускает несколько.
+const CASES: [Case; 6] = [
+    Case::Nominativus,
+    Case::Genetivus,
+    Case::Dativus,
+    Case::Accusativus,
+    Case::Ablativus,
+    Case::Locativus,
+];
+
+impl MorphAnalyzer {
+    /// Распознавание числительного, записанного словами: "двадцати пяти тысяч" -> 25000.
+    ///
+    /// Каждое слово нормализуется, по нормальной форме определяется его значение,
+    /// после чего значения складываются с учетом разрядов.
+    /// Падеж определяется по согласованию всех слов, для порядковых - по последнему слову.
+    pub(crate) fn numeral_parse(&self, text: &str) -> Result<Numeral, ParseErr> {
+        let words = text
+            .split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect_vec();
+
+        let mut tokens = Vec::with_capacity(words.len());
+        for word in words.iter() {
+            tokens.push(self.numeral_token(word)?);
+        }
+
+        let value = numeral_value(&tokens, text)?;
+        let kind = tokens
+            .last()
+            .map(|token| token.kind)
+            .ok_or_else(|| ParseErr::Numeral(NumeralErr::NotANumeral(text.to_string())))?;
+        let (case, number) = numeral_grammemes(&tokens);
+        debug!("{text} -> {value}, {kind}, {case:?}, {number:?}");
+
+        Ok(Numeral {
+            value,
+            kind,
+            case,
+            number,
+        })
+    }
+
+    /// Распознавание одного слова числительного по его нормальной форме.
+    fn numeral_token(&self, word: &str) -> Result<NumeralToken, ParseErr> {
+        let mut found = None;
+        for normalized in self.normalized_word(word)?.0 {
+            let lemma = normalized.word();
+            if let Some(value) = lemma_value(&lemma) {
+                found = Some((lemma, value, None));
+                break;
+            }
+            if let Some((value, prefix)) = self.compound_ordinal(&lemma)? {
+                found = Some((lemma, value, Some(prefix)));
+                break;
+            }
+        }
+
+        let (lemma, (value, kind, scale), prefix) =
+            found.ok_or_else(|| ParseErr::Numeral(NumeralErr::NotANumeral(word.to_string())))?;
+
+        let forms = self
+            .parse_word(word)?
+            .0
+            .into_iter()
+            .filter(|parse| parse.normal_form.replace('ё', "е") == lemma.replace('ё', "е"))
+            .filter_map(|parse| {
+                let case = parse.tags.iter().find_map(|grammem| match grammem {
+                    Grammem::Case(case) => Some(numeral_case(*case)),
+                    _ => None,
+                })?;
+                let number = parse.tags.iter().find_map(|grammem| match grammem {
+                    Grammem::Number(number @ (Number::Singular | Number::Plural)) => Some(*number),
+                    _ => None,
+                });
+                Some((case, number))
+            })
+            .unique()
+            .collect();
+
+        Ok(NumeralToken {
+            value,
+            kind,
+            scale,
+            prefix,
+            forms,
+        })
+    }
+
+    /// Сложное порядковое числительное: "двухтысячный" -> (1000, 2).
+    fn compound_ordinal(&self, lemma: &str) -> Result<Option<(LemmaValue, u64)>, ParseErr> {
+        let lemma = lemma.replace('ё', "е");
+
+        for (i, (_, ordinal)) in SCALES.iter().enumerate() {
+            let Some(prefix) = lemma.strip_suffix(ordinal) else {
+                continue;
+            };
+            if prefix.is_empty() {
+                continue;
+            }
+
+            if let Some(triad) = self.compound_prefix(prefix)? {
+                let scale = 1000u64.pow(i as u32 + 1);
+                return Ok(Some(((scale, NumeralKind::Ordinal, true), triad)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Значение основы сложного порядкового числительного: "двадцатиодно" -> 21.
+    ///
+    /// Основа жадно разбивается на слова в родительном падеже ("двадцати", "двух")
+    /// или на особые основы ("сто", "одно").
+    fn compound_prefix(&self, prefix: &str) -> Result<Option<u64>, ParseErr> {
+        let chars = prefix.chars().collect_vec();
+        let (mut start, mut triad, mut limit) = (0, 0, 1000);
+
+        'pieces: while start < chars.len() {
+            for end in (start + 1..=chars.len()).rev() {
+                let piece = chars[start..end].iter().collect::<String>();
+
+                if let Some(value) = self.compound_stem_value(&piece)? {
+                    if value >= limit {
+                        return Ok(None);
+                    }
+                    triad += value;
+                    limit = next_limit(value);
+                    start = end;
+                    continue 'pieces;
+                }
+            }
+
+            return Ok(None);
+        }
+
+        Ok(Some(triad))
+    }
+
+    /// Значение одной основы сложного порядкового числительного.
+    fn compound_stem_value(&self, piece: &str) -> Result<Option<u64>, ParseErr> {
+        if let Some((lemma, _)) = COMPOUND_STEMS.iter().find(|(_, stem)| *stem == piece) {
+            return Ok(lemma_value(lemma).map(|(value, ..)| value));
+        }
+        if self.fst.get(piece).is_none() {
+            return Ok(None);
+        }
+
+        Ok(self
+            .parse_word(piece)?
+            .0
+            .iter()
+            .filter(|parse| parse.tags.contains(&Grammem::Case(Case::Genetivus)))
+            .find_map(|parse| match lemma_value(&parse.normal_form) {
+                Some((value, NumeralKind::Cardinal, false)) if value > 0 => Some(value),
+                _ => None,
+            }))
+    }
+}
+
+/// Значение леммы числительного по таблицам количественных, порядковых и собирательных.
+fn lemma_value(lemma: &str) -> Option<LemmaValue> {
+    let lemma = lemma.replace('ё', "е");
+    let is = |other: &str| !other.is_empty() && other.replace('ё', "е") == lemma;
+
+    let small = UNITS
+        .iter()
+        .zip(0..)
+        .chain(TEENS.iter().zip(10..))
+        .chain(TENS.iter().zip((0..).step_by(10)))
+        .chain(HUNDREDS.iter().zip((0..).step_by(100)));
+    for ((cardinal, ordinal), value) in small {
+        if is(cardinal) {
+            return Some((value, NumeralKind::Cardinal, false));
+        }
+        if is(ordinal) {
+            return Some((value, NumeralKind::Ordinal, false));
+        }
+    }
+
+    for ((cardinal, ordinal), scale) in SCALES.iter().zip(1..) {
+        let value = 1000u64.pow(scale);
+        if is(cardinal) {
+            return Some((value, NumeralKind::Cardinal, true));
+        }
+        if is(ordinal) {
+            return Some((value, NumeralKind::Ordinal, true));
+        }
+    }
+
+    COLLECTIVES
+        .iter()
+        .zip(2..)
+        .find(|(collective, _)| is(collective))
+        .map(|(_, value)| (value, NumeralKind::Collective, false))
+}
+
+/// Ограничение на следующее слово внутри трехзначного числа:
+/// после сотен идут десятки, после десятков - единицы.
+fn next_limit(value: u64) -> u64 {
+    match value {
+        100.. => 100,
+        20.. => 10,
+        _ => 1,
+    }
+}
+
+/// Сложение значений слов числительного с учетом разрядов и порядка слов.
+fn numeral_value(tokens: &[NumeralToken], text: &str) -> Result<u64, ParseErr> {
+    let wrong_order = || ParseErr::Numeral(NumeralErr::WrongOrder(text.to_string()));
+    let too_large = || ParseErr::Numeral(NumeralErr::NotANumber(text.to_string()));
+
+    let (mut total, mut current, mut limit, mut last_scale) = (0u64, 0u64, 1000, u64::MAX);
+
+    for (i, token) in tokens.iter().enumerate() {
+        let single = tokens.len() == 1;
+        // Порядковое числительное может быть только последним словом,
+        // собирательное и ноль - только единственным.
+        if (token.kind == NumeralKind::Ordinal && i + 1 != tokens.len())
+            || (token.kind == NumeralKind::Collective && !single)
+            || (token.value == 0 && !single)
+        {
+            return Err(wrong_order());
+        }
+
+        if token.scale {
+            if token.value >= last_scale {
+                return Err(wrong_order());
+            }
+            let factor = match token.prefix {
+                Some(_) if current > 0 => return Err(wrong_order()),
+                Some(prefix) => prefix,
+                None => current.max(1),
+            };
+
+            total = factor
+                .checked_mul(token.value)
+                .and_then(|value| total.checked_add(value))
+                .ok_or_else(too_large)?;
+            (current, limit, last_scale) = (0, 1000, token.value);
+        } else {
+            if token.value >= limit {
+                return Err(wrong_order());
+            }
+            current += token.value;
+            limit = next_limit(token.value);
+        }
+    }
+
+    total.checked_add(current).ok_or_else(too_large)
+}
+
+/// Падеж и число словосочетания числительного.
+///
+/// Для количественных числительных падеж должен подходить всем словам,
+/// а название разряда согласуется с трехзначным числом перед ним: "двадцати пяти тысяч".
+/// Для порядковых и собирательных падеж определяется по последнему слову.
+fn numeral_grammemes(tokens: &[NumeralToken]) -> (Option<Case>, Option<Number>) {
+    let Some(last) = tokens.last() else {
+        return (None, None);
+    };
+
+    let fits = |case: Case| {
+        if last.kind != NumeralKind::Cardinal {
+            return last.forms.iter().any(|(form, _)| *form == case);
+        }
+
+        let mut triad = 0;
+        tokens.iter().all(|token| {
+            if token.scale {
+                let expected = counted_grammemes(triad.max(1), case);
+                triad = 0;
+                token.forms.iter().any(|(form, number)| {
+                    expected.contains(&Grammem::Case(*form))
+                        && number.is_none_or(|number| expected.contains(&Grammem::Number(number)))
+                })
+            } else {
+                triad += token.value;
+                token.forms.iter().any(|(form, _)| *form == case)
+            }
+        })
+    };
+
+    let case = CASES.into_iter().find(|case| fits(*case));
+    let number = case.and_then(|case| {
+        last.forms
+            .iter()
+            .filter(|(form, _)| *form == case)
+            .find_map(|(_, number)| *number)
+    });
+
+    (case, number)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{grams, test_infrastructure::infrastructure::shared_analyzer};
+    use test_case::test_case;
+
+    #[test_case(0, grams![Case::Nominativus] => "ноль")]
+    #[test_case(21, grams![Case::Nominativus] => "двадцать один")]
+    #[test_case(21, grams![Case::Genetivus] => "двадцати одного")]
+    #[test_case(21, grams![Case::Nominativus, Gender::Feminine] => "двадцать одна")]
+    #[test_case(42, grams![Case::Accusativus, Gender::Feminine] => "сорок две")]
+    #[test_case(1000, grams![Case::Nominativus] => "тысяча")]
+    #[test_case(2_500, grams![Case::Ablativus] => "двумя тысячами пятьюстами")]
+    #[test_case(11_000, grams![Case::Nominativus] => "одиннадцать тысяч")]
+    #[test_case(21_000, grams![Case::Dativus] => "двадцати одной тысяче")]
+    #[test_case(1_000_001, grams![Case::Nominativus, Gender::Neutral] => "один миллион одно")]
+    #[test_case(1_000_000_000_000, grams![Case::Locativus] => "одном триллионе")]
+    fn test_cardinal(number: u64, grammemes: Vec<Grammem>) -> String {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        anal.spell_number(number, NumeralKind::Cardinal, grammemes)
+            .unwrap()
+    }
+
+    #[test_case(1, grams![Case::Nominativus] => "первый")]
+    #[test_case(21, grams![Case::Nominativus] => "двадцать первый")]
+    #[test_case(21, grams![Case::Genetivus, Gender::Feminine] => "двадцать первой")]
+    #[test_case(40, grams![Case::Locativus, Gender::Neutral] => "сороковом")]
+    #[test_case(103, grams![Case::Nominativus, Number::Plural] => "сто третьи")]
+    #[test_case(200, grams![Case::Dativus] => "двухсотому")]
+    #[test_case(1000, grams![Case::Nominativus] => "тысячный")]
+    #[test_case(2021, grams![Case::Genetivus] => "две тысячи двадцать первого")]
+    #[test_case(21_000, grams![Case::Nominativus] => "двадцатиоднотысячный")]
+    #[test_case(100_000, grams![Case::Nominativus, Gender::Feminine] => "стотысячная")]
+    #[test_case(3_000_000, grams![Case::Ablativus] => "трехмиллионным")]
+    fn test_ordinal(number: u64, grammemes: Vec<Grammem>) -> String {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        anal.spell_number(number, NumeralKind::Ordinal, grammemes)
+            .unwrap()
+    }
+
+    #[test_case(2, Case::Nominativus => "двое")]
+    #[test_case(3, Case::Genetivus => "троих")]
+    #[test_case(10, Case::Ablativus => "десятерыми")]
+    fn test_collective(number: u64, case: Case) -> String {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        anal.spell_number(number, NumeralKind::Collective, grams![case])
+            .unwrap()
+    }
+
+    #[test_case("21", grams![Case::Nominativus, Gender::Feminine] => "двадцать одна")]
+    #[test_case("21-й", grams![Case::Nominativus] => "двадцать первый")]
+    #[test_case(" 1 000 ", grams![Case::Genetivus] => "тысячи")]
+    fn test_spell_str(number: &str, grammemes: Vec<Grammem>) -> String {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        anal.spell_number_str(number, grammemes).unwrap()
+    }
+
+    #[test]
+    fn test_numeral_errors() {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+
+        assert!(anal
+            .spell_number(MAX_NUMERAL + 1, NumeralKind::Cardinal, vec![])
+            .is_err());
+        assert!(anal
+            .spell_number(11, NumeralKind::Collective, vec![])
+            .is_err());
+        assert!(anal.spell_number_str("21-abc", vec![]).is_err());
+        assert!(anal.spell_number_str("два", vec![]).is_err());
+    }
+
+    #[test_case("двадцать один" => (21, NumeralKind::Cardinal, Some(Case::Nominativus)))]
+    #[test_case("двадцати пяти тысяч" => (25_000, NumeralKind::Cardinal, Some(Case::Genetivus)))]
+    #[test_case("двумя тысячами пятьюстами" => (2_500, NumeralKind::Cardinal, Some(Case::Ablativus)))]
+    #[test_case("Миллион двести тысяч" => (1_200_000, NumeralKind::Cardinal, Some(Case::Nominativus)); "capitalized")]
+    #[test_case("тысячу" => (1_000, NumeralKind::Cardinal, Some(Case::Accusativus)))]
+    #[test_case("сто первого" => (101, NumeralKind::Ordinal, Some(Case::Genetivus)))]
+    #[test_case("две тысячи двадцать первом" => (2_021, NumeralKind::Ordinal, Some(Case::Locativus)))]
+    #[test_case("двухтысячного" => (2_000, NumeralKind::Ordinal, Some(Case::Genetivus)))]
+    #[test_case("троих" => (3, NumeralKind::Collective, Some(Case::Genetivus)))]
+    fn test_parse_numeral(text: &str) -> (u64, NumeralKind, Option<Case>) {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        let numeral = anal.parse_numeral(text).unwrap();
+        (numeral.value(), numeral.kind(), numeral.case())
+    }
+
+    #[test_case("двадцати пяти тысяч" => Some(Number::Plural))]
+    #[test_case("сто первого" => Some(Number::Singular))]
+    #[test_case("сто третьих" => Some(Number::Plural))]
+    fn test_parse_numeral_number(text: &str) -> Option<Number> {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        anal.parse_numeral(text).unwrap().number()
+    }
+
+    #[test_case("пять сто")]
+    #[test_case("тысяча миллион")]
+    #[test_case("первый второй")]
+    #[test_case("двадцать пятнадцать")]
+    #[test_case("рубль")]
+    #[test_case("")]
+    fn test_parse_numeral_errors(text: &str) {
+        let anal = shared_analyzer("data/test/numeral_dict.xml");
+        assert!(anal.parse_numeral(text).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use itertools::Itertools;
+use tracing::debug;
+
+use crate::{
+    analyzer::fio::{fio_case, restore_register, rule_inflect, FioKind},
+    errors::{FioErr, ParseErr},
+    morph::grammemes::{Case, Gender, Grammem},
+    MorphAnalyzer,
+};
+
+/// Имена, отчества от которых образуются не по общим правилам:
+/// имя, мужское отчество, женское отчество.
+const PATRONYMIC_EXCEPTIONS: [(&str, &str, &str); 13] = [
+    ("илья", "ильич", "ильинична"),
+    ("кузьма", "кузьмич", "кузьминична"),
+    ("фома", "фомич", "фоминична"),
+    ("лука", "лукич", "лукинична"),
+    ("савва", "саввич", "саввична"),
+    ("никола", "николаевич", "николаевна"),
+    ("иона", "ионович", "ионовна"),
+    ("лев", "львович", "львовна"),
+    ("павел", "павлович", "павловна"),
+    ("пётр", "петрович", "петровна"),
+    ("петр", "петрович", "петровна"),
+    ("михаил", "михайлович", "михайловна"),
+    ("яков", "яковлевич", "яковлевна"),
+];
+
+const VOWELS: &str = "аеёиоуыэюя";
+
+impl MorphAnalyzer {
+    /// Образование отчества от имени в нужном роде и падеже.
+    ///
+    /// Имя приводится к нормальной форме по словарю, если оно там есть.
+    /// Если в словаре есть отчество с образованной формой, оно склоняется по словарю,
+    /// иначе - по продуктивным окончаниям.
+    pub(crate) fn patronymic_make(
+        &self,
+        first_name: &str,
+        gender: Gender,
+        case: Case,
+    ) -> Result<String, ParseErr> {
+        let case = fio_case(case)?;
+        if !matches!(gender, Gender::Masculine | Gender::Feminine) {
+            return Err(ParseErr::Fio(FioErr::UnsupportedGender(gender)));
+        }
+
+        let word = first_name.trim();
+        if word.is_empty() {
+            return Err(ParseErr::Fio(FioErr::EmptyFio));
+        }
+        if !word.chars().all(|c| c.is_alphabetic() || c == '-') {
+            return Err(ParseErr::Fio(FioErr::NotAName(first_name.to_string())));
+        }
+
+        let name = self.patronymic_name(&word.to_lowercase())?;
+        let patronymic = derive_patronymic(&name, gender)
+            .ok_or_else(|| ParseErr::Fio(FioErr::NotAName(first_name.to_string())))?;
+        debug!("Отчество от '{name}': {patronymic}");
+
+        let parse = self.fio_candidates(&patronymic)?.into_iter().find(|parse| {
+            parse.tags.contains(&FioKind::Patronymic.grammem())
+                && parse.tags.contains(&Grammem::Gender(gender))
+                && parse.tags.contains(&Grammem::Case(Case::Nominativus))
+        });
+
+        let inflected = match parse {
+            Some(parse) => self
+                .fio_inflect_parse(&parse, case)?
+                .map(|parse| parse.word),
+            None => None,
+        }
+        .unwrap_or_else(|| rule_inflect(&patronymic, FioKind::Patronymic, Some(gender), case));
+
+        Ok(restore_register(word, &inflected))
+    }
+
+    /// Нормальная форма мужского имени по словарю: "ивану" -> "иван".
+    fn patronymic_name(&self, word: &str) -> Result<String, ParseErr> {
+        let names = self
+            .fio_candidates(word)?
+            .into_iter()
+            .filter(|parse| {
+                parse.tags.contains(&FioKind::Name.grammem())
+                    && parse.tags.contains(&Grammem::Gender(Gender::Masculine))
+            })
+            .collect_vec();
+
+        Ok(names
+            .iter()
+            .find(|parse| parse.tags.contains(&Grammem::Case(Case::Nominativus)))
+            .or(names.first())
+            .map(|parse| parse.normal_form.to_owned())
+            .unwrap_or_else(|| word.to_string()))
+    }
+}
+
+/// Образование отчества в именительном падеже по продуктивным правилам.
+///
+/// Имя ожидается в нормальной форме и нижнем регистре.
+pub(crate) fn derive_patronymic(name: &str, gender: Gender) -> Option<String> {
+    let feminine = gender == Gender::Feminine;
+
+    if let Some((_, masc, femn)) = PATRONYMIC_EXCEPTIONS
+        .iter()
+        .find(|(exception, ..)| *exception == name)
+    {
+        return Some(if feminine { femn } else { masc }.to_string());
+    }
+
+    let chars = name.chars().collect_vec();
+    let (stem, masc, femn) = match chars.as_slice() {
+        [] => return None,
+        // Дмитрий -> Дмитриевич, но Василий -> Васильевич.
+        [.., a, b, 'и', 'й'] if !VOWELS.contains(*a) && !VOWELS.contains(*b) => {
+            (&chars[..chars.len() - 1], "евич", "евна")
+        }
+        [.., 'и', 'й'] => (&chars[..chars.len() - 2], "ьевич", "ьевна"),
+        [.., 'й' | 'ь'] => (&chars[..chars.len() - 1], "евич", "евна"),
+        [.., 'а' | 'я'] => (&chars[..chars.len() - 1], "ич", "ична"),
+        [.., 'ж' | 'ш' | 'ч' | 'щ' | 'ц'] => (&chars[..], "евич", "евна"),
+        [.., last] if VOWELS.contains(*last) => (&chars[..], "вич", "вна"),
+        _ => (&chars[..], "ович", "овна"),
+    };
+
+    let stem = stem.iter().collect::<String>();
+    Some(format!("{stem}{}", if feminine { femn } else { masc }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test_case("Иван", Gender::Masculine, Case::Nominativus => "Иванович"; "ivan_masc")]
+    #[test_case("Иван", Gender::Feminine, Case::Nominativus => "Ивановна"; "ivan_femn")]
+    #[test_case("ивану", Gender::Masculine, Case::Dativus => "ивановичу"; "ivan_inflected")]
+    #[test_case("Илья", Gender::Masculine, Case::Ablativus => "Ильичом"; "ilya_masc")]
+    #[test_case("Илья", Gender::Feminine, Case::Genetivus => "Ильиничны"; "ilya_femn")]
+    #[test_case("Никита", Gender::Feminine, Case::Nominativus => "Никитична"; "nikita_femn")]
+    #[test_case("Никита", Gender::Masculine, Case::Dativus => "Никитичу"; "nikita_masc")]
+    #[test_case("Сергей", Gender::Masculine, Case::Locativus => "Сергеевиче"; "sergey")]
+    #[test_case("Пётр", Gender::Feminine, Case::Accusativus => "Петровну"; "petr")]
+    #[test_case("Игорь", Gender::Masculine, Case::Ablativus => "Игоревичем"; "igor")]
+    fn test_patronymic(name: &str, gender: Gender, case: Case) -> String {
+        let anal = shared_analyzer("data/test/fio_dict.xml");
+
+        anal.patronymic(name, gender, case).unwrap()
+    }
+
+    #[test_case("василий", Gender::Masculine => Some("васильевич".to_string()))]
+    #[test_case("дмитрий", Gender::Feminine => Some("дмитриевна".to_string()))]
+    #[test_case("николай", Gender::Masculine => Some("николаевич".to_string()))]
+    #[test_case("лев", Gender::Masculine => Some("львович".to_string()))]
+    #[test_case("михаил", Gender::Feminine => Some("михайловна".to_string()))]
+    #[test_case("януш", Gender::Masculine => Some("янушевич".to_string()))]
+    #[test_case("", Gender::Masculine => None)]
+    fn test_derive_patronymic(name: &str, gender: Gender) -> Option<String> {
+        derive_patronymic(name, gender)
+    }
+}
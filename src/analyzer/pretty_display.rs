@@ -1,8 +1,9 @@
 use smallstr::SmallString;
 
-use super::{InflectWords, NormalizedWords, Parse, ParsedWords, Tag};
+use super::{Fio, InflectWords, LemmaRow, NormalizedWords, Parse, ParsedWords, Tag, VangovanieRes};
 use crate::{
     errors::{Bound, ParseErr},
+    json::tag_string,
     InflectWord, MorphAnalyzer, NormalizedWord, ParsedWord, SMALLLEMMA,
 };
 
@@ -144,7 +145,7 @@ impl std::fmt::Display for InflectWords {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let len = self.0.len();
         let iter = self.0.iter().enumerate();
-        let last = iter.clone().last();
+        let last = iter.clone().next_back();
         if len > 1 {
             for (i, inflect) in iter.take(len - 1) {
                 writeln!(f, "Parse {i}: {},", inflect)?;
@@ -157,10 +158,195 @@ impl std::fmt::Display for InflectWords {
     }
 }
 
+impl std::fmt::Display for Fio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let len = self.parts().len();
+        for (i, part) in self.parts().iter().enumerate() {
+            write!(f, "{}", part.word())?;
+            if i + 1 < len {
+                write!(f, " ")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Таблица для вывода в терминал: столбцы выравниваются по ширине в символах.
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I, S>(header: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        Self {
+            header: header.into_iter().map(|cell| cell.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Добавление строки. Недостающие ячейки остаются пустыми, лишние отбрасываются.
+    pub fn push<I, S>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        let mut row = row
+            .into_iter()
+            .map(|cell| cell.to_string())
+            .take(self.header.len())
+            .collect::<Vec<_>>();
+        row.resize(self.header.len(), String::new());
+        self.rows.push(row);
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths = self
+            .header
+            .iter()
+            .map(|cell| cell.chars().count())
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        widths
+    }
+}
+
+impl std::fmt::Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths = self.widths();
+        let line = |f: &mut std::fmt::Formatter<'_>| {
+            for width in &widths {
+                write!(f, "+{}", "-".repeat(width + 2))?;
+            }
+            writeln!(f, "+")
+        };
+        let row = |f: &mut std::fmt::Formatter<'_>, cells: &[String]| {
+            for (cell, width) in cells.iter().zip(&widths) {
+                write!(f, "| {cell:<width$} ")?;
+            }
+            writeln!(f, "|")
+        };
+
+        line(f)?;
+        row(f, &self.header)?;
+        line(f)?;
+        for cells in &self.rows {
+            row(f, cells)?;
+        }
+        line(f)
+    }
+}
+
+impl From<&ParsedWords> for Table {
+    fn from(parsed: &ParsedWords) -> Self {
+        let mut table = Table::new(["#", "word", "normal_form", "tag", "method"]);
+        for (i, word) in parsed.0.iter().enumerate() {
+            table.push([
+                i.to_string(),
+                word.word.clone(),
+                word.normal_form.clone(),
+                tag_string(&word.tags),
+                word.method.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+impl From<&NormalizedWords> for Table {
+    fn from(normalized: &NormalizedWords) -> Self {
+        let mut table = Table::new(["#", "normal_form", "tag", "method"]);
+        for (i, word) in normalized.0.iter().enumerate() {
+            table.push([
+                i.to_string(),
+                word.normal_word.clone(),
+                tag_string(&word.tags),
+                word.method.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+impl From<&InflectWords> for Table {
+    fn from(inflect: &InflectWords) -> Self {
+        let mut table = Table::new(["#", "form", "normal_form", "tag", "method"]);
+        for (i, word) in inflect.0.iter().enumerate() {
+            table.push([
+                i.to_string(),
+                word.inflect_form.clone(),
+                word.normal_form.clone(),
+                tag_string(&word.tags),
+                word.method.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+impl From<&[LemmaRow]> for Table {
+    fn from(rows: &[LemmaRow]) -> Self {
+        let mut table = Table::new(["#", "normal_form", "tag", "form", "row", "lemmas", "forms"]);
+        for (i, row) in rows.iter().enumerate() {
+            let lemmas = row
+                .lemmas
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            table.push([
+                i.to_string(),
+                row.normal_form.clone(),
+                tag_string(&row.tag),
+                row.form.to_string(),
+                row.row.to_string(),
+                lemmas,
+                row.forms.to_string(),
+            ]);
+        }
+        table
+    }
+}
+
+impl From<&[VangovanieRes]> for Table {
+    fn from(vangas: &[VangovanieRes]) -> Self {
+        let mut table = Table::new(["#", "normal_form", "tag", "form", "method", "score"]);
+        for (i, vanga) in vangas.iter().enumerate() {
+            table.push([
+                i.to_string(),
+                vanga.normal_form().to_string(),
+                tag_string(vanga.tag()),
+                vanga.form().to_string(),
+                vanga.method().to_string(),
+                format!("{:.3}", vanga.score()),
+            ]);
+        }
+        table
+    }
+}
+
 #[cfg(test)]
 mod test {
     use smallvec::SmallVec;
 
+    use super::Table;
     use crate::{
         analyzer::{Method::Dictionary, ParsedWords},
         grams,
@@ -204,4 +390,39 @@ mod test {
         ]);
         assert_eq!(parses.to_string(), result);
     }
+
+    #[test]
+    fn test_table() {
+        let parses = ParsedWords(vec![ParsedWord {
+            word: "москве".to_string(),
+            tags: SmallVec::from(grams![ParteSpeech::Noun, Case::Locativus, Other::Geography]),
+            normal_form: "москва".to_string(),
+            method: Dictionary,
+        }]);
+        let table = Table::from(&parses);
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(
+            table.to_string(),
+            "\
++---+--------+-------------+----------------+------------+
+| # | word   | normal_form | tag            | method     |
++---+--------+-------------+----------------+------------+
+| 0 | москве | москва      | NOUN,loct,Geox | Dictionary |
++---+--------+-------------+----------------+------------+
+"
+        );
+    }
+
+    #[test]
+    fn test_table_row_width() {
+        let mut table = Table::new(["a", "b"]);
+        table.push(["1"]);
+        table.push(["1", "2", "3"]);
+
+        assert_eq!(
+            table.to_string(),
+            "+---+---+\n| a | b |\n+---+---+\n| 1 |   |\n| 1 | 2 |\n+---+---+\n"
+        );
+    }
 }
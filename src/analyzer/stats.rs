@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use allocative::size_of_unique_allocated_data;
+use serde::Serialize;
+
+use super::{Meta, Tag};
+use crate::{morph::grammemes::Grammem, MorphAnalyzer};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Содержимое словаря: размеры, память и распределения разборов.
+pub struct DictionaryStats {
+    pub meta: Meta,
+    /// Словоформы в fst.
+    pub wordforms: usize,
+    /// Разборы всех словоформ.
+    pub parses: usize,
+    /// Нормальные формы.
+    pub lemmas: usize,
+    /// Различные теги.
+    pub tags: usize,
+    /// Строки слитых между собой лемм `OpenCorpora`.
+    pub lemma_rows: usize,
+    /// Леммы `OpenCorpora` в обратном индексе форм, 0 у словарей без индекса.
+    pub opencorpora_lemmas: usize,
+    /// Парадигмы Ванги.
+    pub paradigms: usize,
+    pub memory: MemoryStats,
+    /// Количество разборов по частям речи `OpenCorpora`, "-" - без части речи.
+    pub pos: BTreeMap<String, usize>,
+    /// Количество разборов по тегам, по убыванию.
+    pub tag_frequency: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+/// Память на куче по компонентам словаря, в байтах.
+pub struct MemoryStats {
+    pub fst: usize,
+    pub word_parses: usize,
+    pub tags: usize,
+    pub lemmas: usize,
+    pub paradigms: usize,
+    pub lemma_rows: usize,
+    pub lemma_forms: usize,
+    pub alternations: usize,
+}
+
+impl MemoryStats {
+    pub fn total(&self) -> usize {
+        self.fst
+            + self.word_parses
+            + self.tags
+            + self.lemmas
+            + self.paradigms
+            + self.lemma_rows
+            + self.lemma_forms
+            + self.alternations
+    }
+
+    /// Компоненты с именами в порядке объявления.
+    pub fn components(&self) -> [(&'static str, usize); 8] {
+        [
+            ("fst", self.fst),
+            ("word_parses", self.word_parses),
+            ("tags", self.tags),
+            ("lemmas", self.lemmas),
+            ("paradigms", self.paradigms),
+            ("lemma_rows", self.lemma_rows),
+            ("lemma_forms", self.lemma_forms),
+            ("alternations", self.alternations),
+        ]
+    }
+}
+
+/// Память под вектор Small-значений: сам вектор и вынесенные на кучу значения.
+fn small_heap<T>(items: &Vec<T>, spilled: impl Fn(&T) -> usize) -> usize {
+    items.capacity() * std::mem::size_of::<T>() + items.iter().map(spilled).sum::<usize>()
+}
+
+impl MorphAnalyzer {
+    pub(crate) fn dictionary_stats(&self) -> DictionaryStats {
+        let mut by_tag = vec![0; self.tags.len()];
+        for parse in self.word_parses.iter().flatten() {
+            if let Some(count) = by_tag.get_mut(parse.tag) {
+                *count += 1;
+            }
+        }
+
+        let mut pos = BTreeMap::new();
+        for (tag, count) in self.tags.iter().zip(&by_tag) {
+            let name = Grammem::pos_in_tag(tag)
+                .map(|pos| Grammem::ParteSpeech(pos).opencorpora())
+                .unwrap_or_else(|| "-".to_string());
+            *pos.entry(name).or_insert(0) += count;
+        }
+
+        let mut tag_frequency = self
+            .tags
+            .iter()
+            .zip(by_tag)
+            .filter(|(_, count)| *count > 0)
+            .map(|(tag, count)| (crate::json::tag_string(tag), count))
+            .collect::<Vec<_>>();
+        tag_frequency.sort_by(|(a_tag, a), (b_tag, b)| b.cmp(a).then_with(|| a_tag.cmp(b_tag)));
+
+        DictionaryStats {
+            meta: self.meta.clone(),
+            wordforms: self.fst.len(),
+            parses: self.word_parses.iter().map(Vec::len).sum(),
+            lemmas: self.lemmas.len(),
+            tags: self.tags.len(),
+            lemma_rows: self.lemmas_rows.len(),
+            opencorpora_lemmas: self.lemma_forms.len(),
+            paradigms: self.paradigms.len(),
+            memory: self.memory_stats(),
+            pos,
+            tag_frequency,
+        }
+    }
+
+    fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            fst: self.fst.as_fst().size(),
+            word_parses: size_of_unique_allocated_data(&self.word_parses),
+            tags: small_heap(&self.tags, |tag: &Tag| match tag.spilled() {
+                true => tag.capacity() * std::mem::size_of::<Grammem>(),
+                false => 0,
+            }),
+            lemmas: small_heap(&self.lemmas, |lemma| match lemma.spilled() {
+                true => lemma.capacity(),
+                false => 0,
+            }),
+            paradigms: size_of_unique_allocated_data(&self.paradigms),
+            lemma_rows: size_of_unique_allocated_data(&self.lemmas_rows),
+            lemma_forms: size_of_unique_allocated_data(&self.lemma_forms),
+            alternations: size_of_unique_allocated_data(&self.alternations),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test_infrastructure::infrastructure::analyzer, MorphAnalyzer};
+
+    #[test]
+    fn test_stats() {
+        let (tmp_dir, _) = analyzer("data/test/tagger_dict.xml");
+        // Мета-информация должна пережить сохранение и открытие словаря.
+        let anal = MorphAnalyzer::open(tmp_dir.path()).unwrap();
+        let stats = anal.stats();
+
+        assert_eq!(stats.meta.version(), "0.92");
+        assert_eq!(stats.meta.revision(), 417257);
+        assert_eq!(stats.wordforms, anal.fst.len());
+        assert_eq!(stats.tags, anal.tags.len());
+        assert_eq!(stats.pos.values().sum::<usize>(), stats.parses);
+        assert_eq!(
+            stats
+                .tag_frequency
+                .iter()
+                .map(|(_, count)| count)
+                .sum::<usize>(),
+            stats.parses
+        );
+        assert!(stats
+            .tag_frequency
+            .windows(2)
+            .all(|pair| pair[0].1 >= pair[1].1));
+        assert_eq!(stats.pos.get("NOUN"), Some(&48));
+        assert!(stats.memory.fst > 0 && stats.memory.word_parses > 0);
+        // Таблицы сборки словаря входят в общий вес анализатора.
+        assert!(stats.memory.lemma_forms > 0 && stats.memory.alternations > 0);
+        assert!(
+            allocative::size_of_unique_allocated_data(&anal)
+                >= stats.memory.lemma_forms + stats.memory.alternations
+        );
+        assert_eq!(
+            stats.memory.total(),
+            stats
+                .memory
+                .components()
+                .iter()
+                .map(|(_, size)| size)
+                .sum::<usize>()
+        );
+    }
+}
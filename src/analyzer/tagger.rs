@@ -0,0 +1,408 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs::File, io::Write, path::Path, str::FromStr};
+use tracing::debug;
+
+use crate::{
+    analyzer::{
+        text::{AnalyzedToken, PseudoTag, TokenAnalysis},
+        ParsedWords,
+    },
+    errors::{MopsErr, MopsResult, ParseErr},
+    morph::grammemes::{Case, Grammem, Number, ParteSpeech},
+    opencorpora::corpus::{AnnotationOpenCorpora, CorpusGram},
+    tokenizer::tokenize,
+    MorphAnalyzer,
+};
+
+/// Файл модели рядом со словарем.
+pub const TAGGER_FILE: &str = "tagger.json";
+
+/// Сглаживание Лапласа для неизвестных переходов и слов.
+const SMOOTHING: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Состояние скрытой марковской модели: укрупненный тег слова.
+pub(crate) enum TagState {
+    /// Начало текста.
+    Start,
+    Pseudo(PseudoTag),
+    Word {
+        pos: Option<ParteSpeech>,
+        case: Option<Case>,
+        number: Option<Number>,
+    },
+}
+
+impl TagState {
+    fn from_tag(tag: &[Grammem]) -> Self {
+        Self::Word {
+            pos: Grammem::pos_in_tag(tag),
+            case: tag.iter().find_map(|grammem| match grammem {
+                Grammem::Case(case) => Some(*case),
+                _ => None,
+            }),
+            number: tag.iter().find_map(|grammem| match grammem {
+                Grammem::Number(number @ (Number::Singular | Number::Plural)) => Some(*number),
+                _ => None,
+            }),
+        }
+    }
+
+    /// Состояние по граммемам корпуса, среди которых могут быть псевдотеги.
+    fn from_corpus(grams: &[CorpusGram]) -> Self {
+        let pseudo = grams.iter().find_map(|gram| {
+            serde_json::from_value(serde_json::Value::String(gram.v.to_owned())).ok()
+        });
+
+        match pseudo {
+            Some(pseudo) => Self::Pseudo(pseudo),
+            None => Self::from_tag(
+                &grams
+                    .iter()
+                    .filter_map(|gram| Grammem::from_str(&gram.v).ok())
+                    .collect_vec(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "TaggerModel", into = "TaggerModel")]
+/// Снятие неоднозначности разбора по контексту.
+///
+/// Скрытая марковская модель первого порядка над укрупненными тегами:
+/// вероятности переходов между тегами и тегов для слов считаются по размеченному корпусу,
+/// лучшая последовательность разборов выбирается алгоритмом Витерби.
+pub struct Tagger {
+    states: Vec<TagState>,
+    index: HashMap<TagState, usize>,
+    transitions: HashMap<(usize, usize), u32>,
+    outgoing: HashMap<usize, u32>,
+    emissions: HashMap<String, HashMap<usize, u32>>,
+    /// Сколько раз тег встретился в корпусе, для перехода от P(тег|слово) к P(слово|тег).
+    tag_counts: HashMap<usize, u32>,
+    tokens: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+/// Хранимое представление модели: словари с составными ключами в json не записываются.
+struct TaggerModel {
+    states: Vec<TagState>,
+    transitions: Vec<(usize, usize, u32)>,
+    emissions: Vec<(String, Vec<(usize, u32)>)>,
+}
+
+impl From<TaggerModel> for Tagger {
+    fn from(model: TaggerModel) -> Self {
+        let mut tagger = Tagger::default();
+        for state in model.states {
+            tagger.state_id(state);
+        }
+        for (prev, next, count) in model.transitions {
+            tagger.transitions.insert((prev, next), count);
+            *tagger.outgoing.entry(prev).or_default() += count;
+        }
+        for (word, counts) in model.emissions {
+            for (state, count) in &counts {
+                *tagger.tag_counts.entry(*state).or_default() += count;
+                tagger.tokens += count;
+            }
+            tagger.emissions.insert(word, counts.into_iter().collect());
+        }
+        tagger
+    }
+}
+
+impl From<Tagger> for TaggerModel {
+    fn from(tagger: Tagger) -> Self {
+        Self {
+            states: tagger.states,
+            transitions: tagger
+                .transitions
+                .into_iter()
+                .map(|((prev, next), count)| (prev, next, count))
+                .sorted()
+                .collect(),
+            emissions: tagger
+                .emissions
+                .into_iter()
+                .map(|(word, counts)| (word, counts.into_iter().sorted().collect()))
+                .sorted()
+                .collect(),
+        }
+    }
+}
+
+impl Tagger {
+    /// Обучение на размеченном корпусе `OpenCorpora` (`annot.opcorpora.xml`).
+    pub fn train_opencorpora<P: AsRef<Path>>(corpus: P) -> MopsResult<Self> {
+        let corpus = AnnotationOpenCorpora::init_from_path(corpus)?;
+        Ok(Self::train(&corpus))
+    }
+
+    /// Сохранение модели в `tagger.json` в папке словаря.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> MopsResult<()> {
+        let path = dir.as_ref().join(TAGGER_FILE);
+        let mut writer =
+            File::create(&path).map_err(|error| MopsErr::File { file: path, error })?;
+
+        let bytes = serde_json::to_vec(self).map_err(MopsErr::Serde)?;
+        writer.write_all(&bytes).map_err(MopsErr::IO)
+    }
+
+    /// Открытие модели из `tagger.json` в папке словаря.
+    pub fn open<P: AsRef<Path>>(dir: P) -> MopsResult<Self> {
+        let path = dir.as_ref().join(TAGGER_FILE);
+        let buf =
+            std::fs::read_to_string(&path).map_err(|error| MopsErr::File { file: path, error })?;
+        serde_json::from_str(&buf).map_err(MopsErr::Serde)
+    }
+
+    /// Подсчет переходов и тегов слов.
+    ///
+    /// Токены с неснятой неоднозначностью пропускаются и разрывают цепочку переходов.
+    pub(crate) fn train(corpus: &AnnotationOpenCorpora) -> Self {
+        let mut tagger = Self::default();
+        let start = tagger.state_id(TagState::Start);
+
+        for sentence in corpus.sentences() {
+            let mut prev = Some(start);
+
+            for token in sentence {
+                let [variant] = token.tfr.variants.as_slice() else {
+                    prev = None;
+                    continue;
+                };
+                let state = tagger.state_id(TagState::from_corpus(&variant.l.grams));
+
+                if let Some(prev) = prev {
+                    *tagger.transitions.entry((prev, state)).or_default() += 1;
+                    *tagger.outgoing.entry(prev).or_default() += 1;
+                }
+                *tagger
+                    .emissions
+                    .entry(token.text.to_lowercase())
+                    .or_default()
+                    .entry(state)
+                    .or_default() += 1;
+                *tagger.tag_counts.entry(state).or_default() += 1;
+                tagger.tokens += 1;
+
+                prev = Some(state);
+            }
+        }
+
+        debug!("Состояний теггера: {}", tagger.states.len());
+        tagger
+    }
+
+    fn state_id(&mut self, state: TagState) -> usize {
+        match self.index.get(&state) {
+            Some(id) => *id,
+            None => {
+                self.states.push(state);
+                self.index.insert(state, self.states.len() - 1);
+                self.states.len() - 1
+            }
+        }
+    }
+
+    /// Логарифм вероятности перехода между тегами.
+    fn transition(&self, prev: TagState, next: TagState) -> f64 {
+        let prev = self.index.get(&prev);
+        let count = prev
+            .zip(self.index.get(&next))
+            .and_then(|(prev, next)| self.transitions.get(&(*prev, *next)))
+            .copied()
+            .unwrap_or_default();
+        let total = prev
+            .and_then(|prev| self.outgoing.get(prev))
+            .copied()
+            .unwrap_or_default();
+
+        ((count as f64 + SMOOTHING) / (total as f64 + SMOOTHING * (self.states.len() + 1) as f64))
+            .ln()
+    }
+
+    /// Логарифм вероятности слова для тега среди `variants` возможных тегов слова.
+    ///
+    /// По формуле Байеса P(слово|тег) = P(тег|слово) * P(слово) / P(тег), а P(слово) одинакова
+    /// для всех вариантов слова и отбрасывается. Для слов не из корпуса выбор остается за переходами.
+    fn emission(&self, word: &str, state: TagState, variants: usize) -> f64 {
+        let Some(counts) = self.emissions.get(word) else {
+            return 0.0;
+        };
+        let state = self.index.get(&state);
+        let count = state
+            .and_then(|state| counts.get(state))
+            .copied()
+            .unwrap_or_default();
+        let total: u32 = counts.values().sum();
+        let tag_count = state
+            .and_then(|state| self.tag_counts.get(state))
+            .copied()
+            .unwrap_or_default();
+
+        let tag_word = (count as f64 + SMOOTHING) / (total as f64 + SMOOTHING * variants as f64);
+        let tag = (tag_count as f64 + SMOOTHING)
+            / (self.tokens as f64 + SMOOTHING * (self.states.len() + 1) as f64);
+        tag_word.ln() - tag.ln()
+    }
+
+    /// Алгоритм Витерби: индекс лучшего варианта для каждого слова.
+    pub(crate) fn best_path(&self, words: &[String], lattice: &[Vec<TagState>]) -> Vec<usize> {
+        let mut scores: Vec<Vec<f64>> = Vec::with_capacity(lattice.len());
+        let mut back: Vec<Vec<usize>> = Vec::with_capacity(lattice.len());
+
+        for (i, (word, states)) in words.iter().zip(lattice).enumerate() {
+            let (score, from) = states
+                .iter()
+                .map(|state| {
+                    let emission = self.emission(word, *state, states.len());
+                    match i {
+                        0 => (self.transition(TagState::Start, *state) + emission, 0),
+                        _ => lattice[i - 1]
+                            .iter()
+                            .zip(&scores[i - 1])
+                            .enumerate()
+                            .map(|(k, (prev, score))| {
+                                (score + self.transition(*prev, *state) + emission, k)
+                            })
+                            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+                            .unwrap_or((emission, 0)),
+                    }
+                })
+                .unzip();
+            scores.push(score);
+            back.push(from);
+        }
+
+        let mut path = vec![0; lattice.len()];
+        if let Some(last) = scores.last() {
+            let mut best = last
+                .iter()
+                .position_max_by(|a, b| a.total_cmp(b))
+                .unwrap_or_default();
+            for i in (0..lattice.len()).rev() {
+                path[i] = best;
+                best = back[i].get(best).copied().unwrap_or_default();
+            }
+        }
+        path
+    }
+}
+
+impl MorphAnalyzer {
+    /// Анализ текста с выбором одного разбора для каждого слова по контексту.
+    pub(crate) fn text_disambiguate<'a>(
+        &self,
+        tagger: &Tagger,
+        text: &'a str,
+    ) -> Result<Vec<AnalyzedToken<'a>>, ParseErr> {
+        let mut tokens = tokenize(text)
+            .map(|token| self.text_token(token))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Разборы с одинаковым укрупненным тегом неразличимы, берется первый из них.
+        let candidates = tokens
+            .iter()
+            .map(|token| match &token.analysis {
+                TokenAnalysis::Parsed(parses) => parses
+                    .0
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, parse)| (TagState::from_tag(&parse.tags), idx))
+                    .unique_by(|(state, _)| *state)
+                    .collect_vec(),
+                TokenAnalysis::Pseudo(tag) => vec![(TagState::Pseudo(*tag), 0)],
+            })
+            .collect_vec();
+
+        let words = tokens
+            .iter()
+            .map(|token| token.token.text().to_lowercase())
+            .collect_vec();
+        let lattice = candidates
+            .iter()
+            .map(|states| states.iter().map(|(state, _)| *state).collect_vec())
+            .collect_vec();
+
+        for ((token, states), best) in tokens
+            .iter_mut()
+            .zip(&candidates)
+            .zip(tagger.best_path(&words, &lattice))
+        {
+            if let TokenAnalysis::Parsed(parses) = &mut token.analysis {
+                let parse = parses.0.swap_remove(states[best].1);
+                *parses = ParsedWords(vec![parse]);
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::shared_analyzer;
+    use tempfile::tempdir;
+    use test_case::test_case;
+
+    #[test]
+    fn test_train() {
+        let corpus = AnnotationOpenCorpora::init_from_path("data/test/corpus.xml").unwrap();
+        assert_eq!(corpus.sentences().count(), 5);
+
+        let tagger = Tagger::train(&corpus);
+        let verb = TagState::Word {
+            pos: Some(ParteSpeech::Verb),
+            case: None,
+            number: Some(Number::Plural),
+        };
+        let pronoun = TagState::Word {
+            pos: Some(ParteSpeech::NounPronoun),
+            case: Some(Case::Nominativus),
+            number: Some(Number::Plural),
+        };
+
+        assert_eq!(tagger.emissions["стали"].values().sum::<u32>(), 4);
+        assert!(tagger.transition(pronoun, verb) > tagger.transition(pronoun, TagState::Start));
+        let number = tagger.index[&TagState::Pseudo(PseudoTag::Number)];
+        assert!(!tagger.transitions.keys().any(|(_, next)| *next == number));
+    }
+
+    #[test]
+    fn test_save_open() {
+        let tmp_dir = tempdir().unwrap();
+        let tagger = Tagger::train_opencorpora("data/test/corpus.xml").unwrap();
+        tagger.save(tmp_dir.path()).unwrap();
+
+        let opened = Tagger::open(tmp_dir.path()).unwrap();
+        assert_eq!(opened.states, tagger.states);
+        assert_eq!(opened.transitions, tagger.transitions);
+        assert_eq!(opened.outgoing, tagger.outgoing);
+        assert_eq!(opened.emissions, tagger.emissions);
+        assert_eq!(opened.tag_counts, tagger.tag_counts);
+        assert_eq!(opened.tokens, tagger.tokens);
+    }
+
+    #[test_case("Нож из стали." => vec!["нож", "из", "сталь", "."]; "noun")]
+    #[test_case("Мы стали." => vec!["мы", "стать", "."]; "verb")]
+    #[test_case("Цены стали новыми." => vec!["цена", "стать", "новый", "."]; "verb_adjf")]
+    #[test_case("Ножи из новой стали" => vec!["нож", "из", "новый", "сталь"]; "noun_adjf")]
+    fn test_disambiguate(text: &str) -> Vec<String> {
+        let anal = shared_analyzer("data/test/tagger_dict.xml");
+        let tagger = Tagger::train_opencorpora("data/test/corpus.xml").unwrap();
+
+        anal.disambiguate_text(&tagger, text)
+            .unwrap()
+            .iter()
+            .map(|token| {
+                assert!(token.parses().is_none_or(|parses| parses.0.len() == 1));
+                token.normal_form()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,347 @@
+use itertools::Itertools;
+use std::{collections::HashMap, mem::discriminant, str::FromStr};
+use tracing::debug;
+
+use crate::{
+    analyzer::{fio::restore_register, numeral::counted_grammemes},
+    errors::{ParseErr, TemplateErr},
+    morph::grammemes::{Case, Gender, Grammem, Number, ParteSpeech},
+    MorphAnalyzer,
+};
+
+#[derive(Debug, Clone, derive_more::Display, PartialEq, Eq, Hash)]
+/// Аргумент шаблона, с которым могут согласовываться слова.
+pub enum TemplateArg {
+    /// Текст без грамматических признаков.
+    #[display(fmt = "{}", _0)]
+    Text(String),
+    /// Текст с родом: имя пользователя, название объекта.
+    #[display(fmt = "{}", _0)]
+    Gendered(String, Gender),
+    /// Счетчик, с которым согласуются существительные.
+    #[display(fmt = "{}", _0)]
+    Number(u64),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Шаблон сообщения с подстановками.
+///
+/// Подстановка записывается в фигурных скобках: `{слова:граммемы@аргумент}`.
+/// - `{user}` - значение аргумента как есть;
+/// - `{файл:gent,plur}` - слово в форме с указанными граммемами `OpenCorpora`;
+/// - `{изменение@n}` - слово, согласованное с числом или родом аргумента;
+/// - `{новый файл:datv@n}` - каждое слово склоняется, явные граммемы важнее согласования.
+///
+/// Фигурные скобки в тексте экранируются удвоением: `{{`, `}}`.
+pub struct Template {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Часть шаблона: текст как есть или подстановка.
+enum TemplatePart {
+    Text(String),
+    Slot(Slot),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Подстановка: слова или имя аргумента, граммемы и аргумент для согласования.
+struct Slot {
+    words: String,
+    grammemes: Vec<Grammem>,
+    target: Option<String>,
+}
+
+impl FromStr for Template {
+    type Err = ParseErr;
+
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.char_indices().peekable();
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|(_, c)| *c == '{').is_some() => text.push('{'),
+                '}' if chars.next_if(|(_, c)| *c == '}').is_some() => text.push('}'),
+                '}' => return Err(ParseErr::Template(TemplateErr::Unopened(idx))),
+                '{' => {
+                    let mut content = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => content.push(c),
+                            None => return Err(ParseErr::Template(TemplateErr::Unclosed(idx))),
+                        }
+                    }
+
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(TemplatePart::Slot(Slot::parse(&content, idx)?));
+                }
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl Slot {
+    /// Разбор содержимого фигурных скобок: `слова:граммемы@аргумент`.
+    fn parse(content: &str, idx: usize) -> Result<Self, ParseErr> {
+        let (rest, target) = match content.rsplit_once('@') {
+            Some((rest, target)) => (rest, Some(target.trim())),
+            None => (content, None),
+        };
+        let (words, grammemes) = rest.split_once(':').unwrap_or((rest, ""));
+
+        let words = words.split_whitespace().join(" ");
+        if words.is_empty() || target.is_some_and(str::is_empty) {
+            return Err(ParseErr::Template(TemplateErr::EmptyPlaceholder(idx)));
+        }
+
+        let grammemes = grammemes
+            .split(',')
+            .map(str::trim)
+            .filter(|grammem| !grammem.is_empty())
+            .map(Grammem::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            words,
+            grammemes,
+            target: target.map(str::to_string),
+        })
+    }
+}
+
+impl MorphAnalyzer {
+    /// Заполнение шаблона аргументами с согласованием слов.
+    pub(crate) fn template_render(
+        &self,
+        template: &Template,
+        args: &HashMap<&str, TemplateArg>,
+    ) -> Result<String, ParseErr> {
+        let mut rendered = String::new();
+
+        for part in &template.parts {
+            match part {
+                TemplatePart::Text(text) => rendered.push_str(text),
+                TemplatePart::Slot(slot) => rendered.push_str(&self.template_slot(slot, args)?),
+            }
+        }
+
+        Ok(rendered)
+    }
+
+    /// Текст одной подстановки.
+    fn template_slot(
+        &self,
+        slot: &Slot,
+        args: &HashMap<&str, TemplateArg>,
+    ) -> Result<String, ParseErr> {
+        let target = match &slot.target {
+            Some(name) => Some((
+                name.as_str(),
+                args.get(name.as_str()).ok_or_else(|| {
+                    ParseErr::Template(TemplateErr::UnknownArgument(name.to_owned()))
+                })?,
+            )),
+            None => None,
+        };
+
+        let words = match args.get(slot.words.as_str()) {
+            Some(TemplateArg::Number(number)) => return Ok(number.to_string()),
+            Some(arg) => arg.to_string(),
+            None => slot.words.to_owned(),
+        };
+        if slot.grammemes.is_empty() && target.is_none() {
+            return Ok(words);
+        }
+
+        Ok(words
+            .split_whitespace()
+            .map(|word| self.template_inflect(word, &slot.grammemes, target))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" "))
+    }
+
+    /// Постановка слова в форму с явными граммемами и граммемами согласования.
+    ///
+    /// Несогласуемые признаки слова (часть речи, время, вид) сохраняются:
+    /// "сделал" в женском роде - "сделала", но не "сделанная".
+    fn template_inflect(
+        &self,
+        word: &str,
+        explicit: &[Grammem],
+        target: Option<(&str, &TemplateArg)>,
+    ) -> Result<String, ParseErr> {
+        let mut grammemes = explicit.to_vec();
+
+        for parse in self.parse_word(&word.to_lowercase())?.0 {
+            let pos = Grammem::pos_in_tag(&parse.tags);
+            grammemes = match target {
+                Some((name, arg)) => {
+                    merge_grammemes(explicit, agreement(name, arg, pos, explicit)?)
+                }
+                None => explicit.to_vec(),
+            };
+            let desired = desired_tag(&parse.tags, &grammemes, pos);
+            debug!("{word}: {desired:?}");
+
+            if let Some(form) = self
+                .inflect_parsed_words(parse, Some(grammemes.to_owned()))?
+                .and_then(|forms| forms.find(desired))
+            {
+                return Ok(restore_register(word, &form.word()));
+            }
+        }
+
+        Err(ParseErr::Template(TemplateErr::NoForm {
+            word: word.to_string(),
+            grammemes,
+        }))
+    }
+}
+
+/// Граммемы согласования слова с аргументом.
+///
+/// С родом согласуются прилагательные, причастия и глаголы прошедшего времени.
+/// С числом существительные согласуются по правилам счета: "1 файл", "2 файла", "5 файлов".
+fn agreement(
+    name: &str,
+    arg: &TemplateArg,
+    pos: Option<ParteSpeech>,
+    explicit: &[Grammem],
+) -> Result<Vec<Grammem>, ParseErr> {
+    let number = match arg {
+        TemplateArg::Text(_) => {
+            return Err(ParseErr::Template(TemplateErr::NoAgreement(
+                name.to_string(),
+            )))
+        }
+        TemplateArg::Gendered(_, gender) => {
+            return Ok(vec![
+                Grammem::Gender(*gender),
+                Grammem::Number(Number::Singular),
+            ])
+        }
+        TemplateArg::Number(number) => *number,
+    };
+
+    let case = explicit
+        .iter()
+        .find_map(|grammem| match grammem {
+            Grammem::Case(case) => Some(*case),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let one = number % 10 == 1 && number % 100 != 11;
+    let count = Grammem::Number(if one {
+        Number::Singular
+    } else {
+        Number::Plural
+    });
+
+    Ok(match pos {
+        Some(ParteSpeech::Noun | ParteSpeech::Number) => counted_grammemes(number, case),
+        Some(ParteSpeech::AdjectiveFull | ParteSpeech::ParticipleFull) => match case {
+            Case::Nominativus | Case::Accusativus if !one => vec![
+                Grammem::Case(Case::Genetivus),
+                Grammem::Number(Number::Plural),
+            ],
+            case => vec![Grammem::Case(case), count],
+        },
+        _ => vec![count],
+    })
+}
+
+/// Явные граммемы с граммемами согласования тех категорий, что не заданы явно.
+fn merge_grammemes(explicit: &[Grammem], agreement: Vec<Grammem>) -> Vec<Grammem> {
+    let agreement = agreement.into_iter().filter(|grammem| {
+        !explicit
+            .iter()
+            .any(|meme| discriminant(meme) == discriminant(grammem))
+    });
+
+    explicit.iter().copied().chain(agreement).collect()
+}
+
+/// Тег искомой формы: тег слова, в котором категории запрошенных граммем заменены.
+///
+/// Во множественном числе род есть только у существительных.
+fn desired_tag(tag: &[Grammem], grammemes: &[Grammem], pos: Option<ParteSpeech>) -> Vec<Grammem> {
+    let plural = grammemes.contains(&Grammem::Number(Number::Plural));
+
+    tag.iter()
+        .filter(|meme| !matches!(meme, Grammem::Other(_)))
+        .filter(|meme| {
+            !grammemes
+                .iter()
+                .any(|grammem| discriminant(*meme) == discriminant(grammem))
+        })
+        .filter(|meme| {
+            !(plural && pos != Some(ParteSpeech::Noun) && matches!(meme, Grammem::Gender(_)))
+        })
+        .chain(grammemes)
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test_case(Gender::Masculine, 1 => "Иван сделал 1 изменение"; "masc_one")]
+    #[test_case(Gender::Feminine, 3 => "Иван сделала 3 изменения"; "femn_few")]
+    #[test_case(Gender::Feminine, 11 => "Иван сделала 11 изменений"; "femn_teen")]
+    #[test_case(Gender::Neutral, 21 => "Иван сделало 21 изменение"; "neut_many_one")]
+    fn test_render_agreement(gender: Gender, n: u64) -> String {
+        let anal = shared_analyzer("data/test/template_dict.xml");
+        let args = HashMap::from([
+            ("user", TemplateArg::Gendered("Иван".to_string(), gender)),
+            ("n", TemplateArg::Number(n)),
+        ]);
+
+        anal.render("{user} {сделал@user} {n} {изменение@n}", &args)
+            .unwrap()
+    }
+
+    #[test_case("{новый файл@n}", 1 => "новый файл"; "adjf_one")]
+    #[test_case("{новый файл@n}", 2 => "новых файла"; "adjf_few")]
+    #[test_case("{новый файл@n}", 25 => "новых файлов"; "adjf_many")]
+    #[test_case("{новый файл:datv@n}", 2 => "новым файлам"; "adjf_datv")]
+    #[test_case("{Написал@n}", 5 => "Написали"; "verb_plur")]
+    #[test_case("{пользователь:ablt,plur}", 0 => "пользователями"; "explicit")]
+    #[test_case("{{{n}}}", 7 => "{7}"; "escaped")]
+    fn test_render(template: &str, n: u64) -> String {
+        let anal = shared_analyzer("data/test/template_dict.xml");
+        let args = HashMap::from([("n", TemplateArg::Number(n))]);
+
+        anal.render(template, &args).unwrap()
+    }
+
+    #[test_case("{user} {сделал@user}" => matches ParseErr::Template(TemplateErr::NoAgreement(_)); "no_agreement")]
+    #[test_case("{сделал@кто}" => matches ParseErr::Template(TemplateErr::UnknownArgument(_)); "unknown_argument")]
+    #[test_case("{файл:gent,xxxx}" => matches ParseErr::UnknownGrammem(_); "unknown_grammem")]
+    #[test_case("{файл:gent" => matches ParseErr::Template(TemplateErr::Unclosed(0)); "unclosed")]
+    #[test_case("файл}" => matches ParseErr::Template(TemplateErr::Unopened(8)); "unopened")]
+    #[test_case("{ :gent}" => matches ParseErr::Template(TemplateErr::EmptyPlaceholder(0)); "empty")]
+    #[test_case("{изменение:femn}" => matches ParseErr::Template(TemplateErr::NoForm { .. }); "no_form")]
+    fn test_render_errors(template: &str) -> ParseErr {
+        let anal = shared_analyzer("data/test/template_dict.xml");
+        let args = HashMap::from([("user", TemplateArg::Text("Иван".to_string()))]);
+
+        template
+            .parse::<Template>()
+            .and_then(|template| anal.template_render(&template, &args))
+            .unwrap_err()
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analyzer::ParsedWords,
+    errors::{MopsErr, MopsResult, ParseErr},
+    tokenizer::{tokenize, Token, TokenKind, Tokens},
+    MorphAnalyzer, Normalized,
+};
+
+/// Римские цифры от больших к меньшим с вычитательными парами.
+const ROMAN: [(&str, u16); 13] = [
+    ("M", 1000),
+    ("CM", 900),
+    ("D", 500),
+    ("CD", 400),
+    ("C", 100),
+    ("XC", 90),
+    ("L", 50),
+    ("XL", 40),
+    ("X", 10),
+    ("IX", 9),
+    ("V", 5),
+    ("IV", 4),
+    ("I", 1),
+];
+
+#[rustfmt::skip]
+#[derive(Debug, Clone, Copy, derive_more::Display, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Псевдотеги токенов, которые не разбираются по словарю, аналогично Pymorphy2.
+pub enum PseudoTag {
+    #[serde(rename = "PNCT")]
+    #[display(fmt = "PNCT")]
+    /// Пунктуация
+    Punctuation,
+    #[serde(rename = "NUMB")]
+    #[display(fmt = "NUMB")]
+    /// Число
+    Number,
+    #[serde(rename = "LATN")]
+    #[display(fmt = "LATN")]
+    /// Слово латиницей
+    Latin,
+    #[serde(rename = "ROMN")]
+    #[display(fmt = "ROMN")]
+    /// Римское число
+    Roman,
+    #[serde(rename = "UNKN")]
+    #[display(fmt = "UNKN")]
+    /// Все остальное: ссылки, эмодзи, неизвестные слова
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Результат анализа токена: разборы слова или псевдотег.
+pub enum TokenAnalysis {
+    Parsed(ParsedWords),
+    Pseudo(PseudoTag),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Токен текста с результатом анализа.
+pub struct AnalyzedToken<'a> {
+    pub(crate) token: Token<'a>,
+    pub(crate) analysis: TokenAnalysis,
+}
+
+impl<'a> AnalyzedToken<'a> {
+    /// Токен со смещениями в исходном тексте.
+    pub fn token(&self) -> &Token<'a> {
+        &self.token
+    }
+
+    pub fn analysis(&self) -> &TokenAnalysis {
+        &self.analysis
+    }
+
+    /// Разборы слова, если токен разобран по словарю или вангованием.
+    pub fn parses(&self) -> Option<&ParsedWords> {
+        match &self.analysis {
+            TokenAnalysis::Parsed(parses) => Some(parses),
+            TokenAnalysis::Pseudo(_) => None,
+        }
+    }
+
+    pub fn pseudo_tag(&self) -> Option<PseudoTag> {
+        match self.analysis {
+            TokenAnalysis::Parsed(_) => None,
+            TokenAnalysis::Pseudo(tag) => Some(tag),
+        }
+    }
+
+    /// Нормальная форма первого разбора, для псевдотегов - токен в нижнем регистре.
+    pub fn normal_form(&self) -> Normalized {
+        match &self.analysis {
+            TokenAnalysis::Parsed(parses) => parses
+                .0
+                .first()
+                .map(|parse| parse.normal_form())
+                .unwrap_or_else(|| self.token.text().to_lowercase()),
+            TokenAnalysis::Pseudo(_) => self.token.text().to_lowercase(),
+        }
+    }
+}
+
+/// Ленивый анализ текста по токенам.
+pub struct TextAnalysis<'a> {
+    analyzer: &'a MorphAnalyzer,
+    tokens: Tokens<'a>,
+}
+
+impl<'a> TextAnalysis<'a> {
+    pub(crate) fn new(analyzer: &'a MorphAnalyzer, text: &'a str) -> Self {
+        Self {
+            analyzer,
+            tokens: tokenize(text),
+        }
+    }
+}
+
+impl<'a> Iterator for TextAnalysis<'a> {
+    type Item = MopsResult<AnalyzedToken<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokens
+            .next()
+            .map(|token| self.analyzer.text_token(token).map_err(MopsErr::Parse))
+    }
+}
+
+impl MorphAnalyzer {
+    /// Анализ одного токена: слова разбираются, остальным назначается псевдотег.
+    pub(crate) fn text_token<'a>(&self, token: Token<'a>) -> Result<AnalyzedToken<'a>, ParseErr> {
+        let pseudo = match token.kind() {
+            TokenKind::Word => None,
+            TokenKind::Number => Some(PseudoTag::Number),
+            TokenKind::Punctuation => Some(PseudoTag::Punctuation),
+            TokenKind::Latin if is_roman(token.text()) => Some(PseudoTag::Roman),
+            TokenKind::Latin => Some(PseudoTag::Latin),
+            TokenKind::Url
+            | TokenKind::Email
+            | TokenKind::Hashtag
+            | TokenKind::Mention
+            | TokenKind::Emoji
+            | TokenKind::Other => Some(PseudoTag::Unknown),
+        };
+
+        let analysis = match pseudo {
+            Some(tag) => TokenAnalysis::Pseudo(tag),
+            None => {
+                let parses = self.parse_word(&token.text().to_lowercase())?;
+                if parses.is_unknown() {
+                    TokenAnalysis::Pseudo(PseudoTag::Unknown)
+                } else {
+                    TokenAnalysis::Parsed(parses)
+                }
+            }
+        };
+
+        Ok(AnalyzedToken { token, analysis })
+    }
+}
+
+/// Римское число в каноничной записи от 1 до 3999: "XIV", но не "IIII" и не "xiv".
+fn is_roman(word: &str) -> bool {
+    let mut rest = word;
+    let mut value = 0;
+    for (numeral, number) in ROMAN {
+        while let Some(tail) = rest.strip_prefix(numeral) {
+            rest = tail;
+            value += number;
+        }
+    }
+    if !rest.is_empty() || value == 0 || value > 3999 {
+        return false;
+    }
+
+    let mut canonical = String::new();
+    for (numeral, number) in ROMAN {
+        while value >= number {
+            canonical.push_str(numeral);
+            value -= number;
+        }
+    }
+    canonical == word
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test]
+    fn test_analyze_text() {
+        let anal = shared_analyzer("data/test/template_dict.xml");
+
+        let text = "Пользователь сделал 2024 изменения, см. XIV iPhone https://ya.ru #тег 🙂";
+        let analyzed = anal
+            .analyze_text(text)
+            .collect::<MopsResult<Vec<_>>>()
+            .unwrap();
+
+        let tags = analyzed
+            .iter()
+            .map(|token| (token.normal_form(), token.pseudo_tag()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            vec![
+                ("пользователь".to_string(), None),
+                ("сделать".to_string(), None),
+                ("2024".to_string(), Some(PseudoTag::Number)),
+                ("изменение".to_string(), None),
+                (",".to_string(), Some(PseudoTag::Punctuation)),
+                ("см".to_string(), Some(PseudoTag::Unknown)),
+                (".".to_string(), Some(PseudoTag::Punctuation)),
+                ("xiv".to_string(), Some(PseudoTag::Roman)),
+                ("iphone".to_string(), Some(PseudoTag::Latin)),
+                ("https://ya.ru".to_string(), Some(PseudoTag::Unknown)),
+                ("#тег".to_string(), Some(PseudoTag::Unknown)),
+                ("🙂".to_string(), Some(PseudoTag::Unknown)),
+            ]
+        );
+
+        let change = &analyzed[3];
+        assert_eq!(&text[change.token().bytes()], "изменения");
+        assert!(change.parses().is_some());
+
+        assert_eq!(
+            anal.lemmatize_text("Пользователь сделал 5 изменений!")
+                .unwrap(),
+            vec!["пользователь", "сделать", "5", "изменение"]
+        );
+    }
+
+    #[test_case("XIV" => true)]
+    #[test_case("MCMXCIX" => true)]
+    #[test_case("I" => true)]
+    #[test_case("IIII" => false)]
+    #[test_case("IC" => false)]
+    #[test_case("xiv" => false)]
+    #[test_case("iPhone" => false)]
+    fn test_is_roman(word: &str) -> bool {
+        is_roman(word)
+    }
+}
@@ -0,0 +1,517 @@
+use std::path::{Path, PathBuf};
+
+use fst::{Map, MapBuilder};
+
+use super::{Dictionary, Tag};
+use crate::{
+    errors::{MopsErr, MopsResult, ParseErr, UserDictErr},
+    morph::grammemes::Grammem,
+    opencorpora::{
+        dictionary::{Gram, GramWord, Lemma, Lemmata, Links, NormalForm},
+        DictionaryOpenCorpora,
+    },
+    Method, MorphAnalyzer, ParsedWord,
+};
+
+/// Начало второго столбца TSV-словаря, после которого идет слово-образец.
+const LIKE: &str = "like ";
+
+/// Лемма пользовательского словаря: нормальная форма и все формы с полными тегами.
+/// Первая форма - нормальная.
+struct UserLemma {
+    normal_form: String,
+    forms: Vec<(String, Tag)>,
+}
+
+impl UserLemma {
+    /// Лемма в виде `OpenCorpora`: общие для всех форм граммемы выносятся в `<l>`.
+    fn into_opencorpora(self, id: u64) -> Lemma {
+        let UserLemma { normal_form, forms } = self;
+        let common = forms
+            .first()
+            .map(|(_, tag)| {
+                tag.iter()
+                    .filter(|grammem| forms.iter().all(|(_, tag)| tag.contains(grammem)))
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let gram = |grammemes: &mut dyn Iterator<Item = &Grammem>| {
+            Some(grammemes.map(|v| Gram { v: *v }).collect())
+        };
+
+        Lemma {
+            id,
+            normal_form: NormalForm {
+                text: normal_form,
+                gram: gram(&mut common.iter()),
+            },
+            forms: Some(
+                forms
+                    .iter()
+                    .map(|(text, tag)| GramWord {
+                        text: text.clone(),
+                        gram: gram(&mut tag.iter().filter(|grammem| !common.contains(grammem))),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Длина в байтах общего начала двух слов.
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((i, _), _)| i)
+        .unwrap_or(a.len().min(b.len()))
+}
+
+/// Граммемы `OpenCorpora` через запятую.
+fn grammemes(grams: &str) -> Result<Vec<Grammem>, ParseErr> {
+    grams
+        .split(',')
+        .map(str::trim)
+        .filter(|gram| !gram.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+impl MorphAnalyzer {
+    /// Лемма по образцу: формы слова-образца с заменой его окончания на окончание леммы.
+    ///
+    /// Образец - нормальная форма в словаре, `grammemes` выбирают один из его разборов.
+    ///
+    /// # Example
+    /// "смена" по "цена": цена -> смена, цены -> смены, ценами -> сменами.
+    fn like_lemma(&self, lemma: &str, model: &str, grammemes: &[Grammem]) -> MopsResult<UserLemma> {
+        let parse = self
+            .parse_word(model)
+            .map_err(MopsErr::Parse)?
+            .0
+            .into_iter()
+            .find(|parse| {
+                parse.method == Method::Dictionary
+                    && parse.normal_form == model
+                    && grammemes.iter().all(|grammem| parse.tags.contains(grammem))
+            })
+            .ok_or_else(|| UserDictErr::UnknownModel(model.to_string()))?;
+
+        let mut forms = vec![(parse.word.clone(), parse.tags.clone())];
+        let declension = self
+            .declension_parsed_word(&parse)
+            .map_err(MopsErr::Parse)?;
+        for form in declension.into_iter().flat_map(|inflect| inflect.0) {
+            let form = (form.inflect_form, form.tags);
+            if !forms.contains(&form) {
+                forms.push(form);
+            }
+        }
+
+        // Неизменяемая часть образца - общее начало всех его форм.
+        let stem = forms
+            .iter()
+            .map(|(form, _)| common_prefix(model, form))
+            .min()
+            .unwrap_or(0);
+        let ending = &model[stem..];
+        let lemma_stem = lemma
+            .strip_suffix(ending)
+            .ok_or_else(|| UserDictErr::ModelMismatch {
+                lemma: lemma.to_string(),
+                model: model.to_string(),
+                ending: ending.to_string(),
+            })?;
+
+        Ok(UserLemma {
+            normal_form: lemma.to_string(),
+            forms: forms
+                .into_iter()
+                .map(|(form, tag)| (format!("{lemma_stem}{}", &form[stem..]), tag))
+                .collect(),
+        })
+    }
+
+    /// Чтение TSV-словаря.
+    ///
+    /// Строки вида "лемма<TAB>форма<TAB>граммемы через запятую" собираются в леммы по первому столбцу,
+    /// среди форм должна быть сама лемма. Строка "лемма<TAB>like слово[<TAB>граммемы]"
+    /// дает лемму по образцу, см. `like_lemma`. Пустые строки и строки с `#` пропускаются.
+    fn read_tsv(&self, path: &Path) -> MopsResult<Vec<UserLemma>> {
+        let text = std::fs::read_to_string(path).map_err(|error| MopsErr::File {
+            file: path.into(),
+            error,
+        })?;
+        let grammem_err = |line: usize| {
+            move |error| UserDictErr::Grammem {
+                file: path.into(),
+                line,
+                error,
+            }
+        };
+
+        let mut lemmas: Vec<UserLemma> = Vec::new();
+        // Леммы, собираемые из отдельных форм, в порядке появления.
+        let mut form_lemmas: Vec<UserLemma> = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let columns = line.split('\t').map(str::trim).collect::<Vec<_>>();
+            match columns.as_slice() {
+                [lemma, like, grams @ ..] if like.starts_with(LIKE) && grams.len() <= 1 => {
+                    let grams = grams
+                        .first()
+                        .map(|grams| grammemes(grams))
+                        .transpose()
+                        .map_err(grammem_err(line_number))?
+                        .unwrap_or_default();
+                    let model = like[LIKE.len()..].trim().to_lowercase();
+                    lemmas.push(self.like_lemma(&lemma.to_lowercase(), &model, &grams)?);
+                }
+                [lemma, form, grams] => {
+                    let tag = Tag::from_vec(grammemes(grams).map_err(grammem_err(line_number))?);
+                    let (lemma, form) = (lemma.to_lowercase(), form.to_lowercase());
+
+                    match form_lemmas
+                        .iter_mut()
+                        .find(|known| known.normal_form == lemma)
+                    {
+                        Some(known) => known.forms.push((form, tag)),
+                        None => form_lemmas.push(UserLemma {
+                            normal_form: lemma,
+                            forms: vec![(form, tag)],
+                        }),
+                    }
+                }
+                _ => {
+                    return Err(UserDictErr::Format {
+                        file: path.into(),
+                        line: line_number,
+                    }
+                    .into())
+                }
+            }
+        }
+
+        for mut lemma in form_lemmas {
+            // Нормальная форма должна идти первой, как в `OpenCorpora`.
+            let normal = lemma
+                .forms
+                .iter()
+                .position(|(form, _)| *form == lemma.normal_form)
+                .ok_or_else(|| UserDictErr::NoNormalForm(lemma.normal_form.clone()))?;
+            let normal = lemma.forms.remove(normal);
+            lemma.forms.insert(0, normal);
+            lemmas.push(lemma);
+        }
+
+        Ok(lemmas)
+    }
+
+    /// Все пользовательские словари в одном словаре `OpenCorpora`.
+    ///
+    /// Id лемм каждого следующего словаря сдвигаются за id предыдущих, связи - вместе с ними.
+    fn user_opencorpora(&self, paths: &[PathBuf]) -> MopsResult<DictionaryOpenCorpora> {
+        let mut lemmas: Vec<Lemma> = Vec::new();
+        let mut links = Links::default();
+
+        for path in paths {
+            let offset = lemmas.iter().map(|lemma| lemma.id).max().unwrap_or(0);
+
+            let dict = match path.extension().is_some_and(|ext| ext == "xml") {
+                true => DictionaryOpenCorpora::init_from_path(path)?,
+                false => DictionaryOpenCorpora {
+                    version: String::new(),
+                    revision: 0,
+                    lemmata: Lemmata {
+                        lemmas: self
+                            .read_tsv(path)?
+                            .into_iter()
+                            .zip(1..)
+                            .map(|(lemma, id)| lemma.into_opencorpora(id))
+                            .collect(),
+                    },
+                    links: Links::default(),
+                },
+            };
+
+            lemmas.extend(dict.lemmata.lemmas.into_iter().map(|mut lemma| {
+                lemma.id += offset;
+                lemma
+            }));
+            links
+                .links
+                .extend(dict.links.links.into_iter().map(|mut link| {
+                    link.lemma_id += offset;
+                    link.variant += offset;
+                    link
+                }));
+        }
+
+        Ok(DictionaryOpenCorpora {
+            version: String::new(),
+            revision: 0,
+            lemmata: Lemmata { lemmas },
+            links,
+        })
+    }
+
+    /// Сборка пользовательских словарей в памяти в отдельный анализатор.
+    /// Леммы по образцу берут формы из `self`. Без лемм анализатора нет.
+    pub(crate) fn user_analyzer(&self, paths: &[PathBuf]) -> MopsResult<Option<MorphAnalyzer>> {
+        let dict = self.user_opencorpora(paths)?;
+        if dict.lemmata.lemmas.is_empty() {
+            return Ok(None);
+        }
+
+        let (dictionary, fst) =
+            Dictionary::build(dict, MapBuilder::memory(), self.meta.language().clone())?;
+        let fst = Map::new(fst).map_err(MopsErr::FSTMap)?;
+
+        Ok(Some(Self::from_fst(dictionary, fst)))
+    }
+
+    /// Пользовательский словарь, если в нем есть слово.
+    pub(crate) fn user_for(&self, word: &str) -> Option<&MorphAnalyzer> {
+        self.user
+            .as_deref()
+            .filter(|user| user.fst.contains_key(word))
+    }
+
+    /// Пользовательский словарь, если разбор получен по нему.
+    pub(crate) fn user_for_parse(
+        &self,
+        parse: &ParsedWord,
+    ) -> Result<Option<&MorphAnalyzer>, ParseErr> {
+        let Some(user) = self.user_for(&parse.word) else {
+            return Ok(None);
+        };
+
+        let own = user.parse_word(&parse.word)?.0.iter().any(|user_parse| {
+            user_parse.tags == parse.tags && user_parse.normal_form == parse.normal_form
+        });
+        Ok(own.then_some(user))
+    }
+
+    /// Результат `lookup` по пользовательскому словарю, за которым идет результат по основному,
+    /// если слово есть и в нем. Повторы отбрасываются.
+    ///
+    /// `None`, если слова нет в пользовательском словаре.
+    pub(crate) fn layered<T: PartialEq>(
+        &self,
+        word: &str,
+        lookup: impl Fn(&Self) -> Result<Vec<T>, ParseErr>,
+    ) -> Result<Option<Vec<T>>, ParseErr> {
+        let Some(user) = self.user_for(word) else {
+            return Ok(None);
+        };
+
+        let mut items = lookup(user)?;
+        if self.fst.contains_key(word) {
+            for item in lookup(self)? {
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+
+        Ok(Some(items))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::{analyzer, shared_analyzer};
+    use itertools::Itertools;
+    use std::sync::OnceLock;
+    use tempfile::{tempdir, TempDir};
+    use test_case::test_case;
+
+    fn anal() -> &'static MorphAnalyzer {
+        static ANAL: OnceLock<(TempDir, MorphAnalyzer)> = OnceLock::new();
+        &ANAL
+            .get_or_init(|| {
+                let (tmp_dir, anal) = analyzer("data/test/tagger_dict.xml");
+                let anal = anal
+                    .with_user_dictionaries(&["data/test/user_dict.xml", "data/test/user_dict.tsv"])
+                    .unwrap();
+                (tmp_dir, anal)
+            })
+            .1
+    }
+
+    fn user_err(tsv: &str) -> MopsErr {
+        let tmp_dir = tempdir().unwrap();
+        let path = tmp_dir.path().join("user.tsv");
+        std::fs::write(&path, tsv).unwrap();
+        analyzer("data/test/tagger_dict.xml")
+            .1
+            .with_user_dictionaries(&[path])
+            .unwrap_err()
+    }
+
+    fn show(words: impl IntoIterator<Item = (String, Tag)>) -> Vec<String> {
+        words
+            .into_iter()
+            .map(|(word, tag)| format!("{word} {}", crate::json::tag_string(&tag)))
+            .collect()
+    }
+
+    #[test_case("кринжа" => vec!["кринж NOUN,inan,gent,masc,sing"])]
+    #[test_case("сменами" => vec!["смена NOUN,inan,ablt,femn,plur"])]
+    #[test_case("яндексовой" => vec!["яндексовый ADJF,gent,femn,sing,Qual", "яндексовый ADJF,datv,femn,sing,Qual", "яндексовый ADJF,ablt,femn,sing,Qual", "яндексовый ADJF,loct,femn,sing,Qual"])]
+    #[test_case("гуглишь" => vec!["гуглить VERB,impf,indc,sing,tran,pres,2per"])]
+    // Разбор пользовательского словаря идет перед разбором основного.
+    #[test_case("из" => vec!["из NOUN,inan,Fixd,nomn,neut,sing", "из PREP"])]
+    fn test_parse(word: &str) -> Vec<String> {
+        show(
+            anal()
+                .parse(word)
+                .unwrap()
+                .0
+                .into_iter()
+                .map(|parse| (parse.normal_form, parse.tags)),
+        )
+    }
+
+    #[test_case("кринжи" => vec!["кринж"])]
+    #[test_case("сменой" => vec!["смена"])]
+    #[test_case("гуглили" => vec!["гуглить"])]
+    #[test_case("стали" => vec!["сталь", "стать"])]
+    fn test_normalize(word: &str) -> Vec<String> {
+        anal()
+            .normalize(word)
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|normal| normal.normal_word)
+            .unique()
+            .collect()
+    }
+
+    #[test]
+    fn test_inflect() {
+        let inflect = |word, grammemes: &[&str]| {
+            let grammemes = grammemes.iter().map(|gram| gram.parse().unwrap()).collect();
+            show(
+                anal()
+                    .inflect_forms(word, grammemes)
+                    .unwrap()
+                    .unwrap()
+                    .0
+                    .into_iter()
+                    .map(|form| (form.inflect_form, form.tags)),
+            )
+        };
+
+        assert_eq!(
+            inflect("кринж", &["plur", "nomn"]),
+            vec!["кринжи NOUN,inan,nomn,masc,plur"]
+        );
+        assert_eq!(
+            inflect("смена", &["plur", "gent"]),
+            vec!["смен NOUN,inan,gent,femn,plur"]
+        );
+        assert_eq!(
+            inflect("гуглить", &["past", "femn"]),
+            vec!["гуглила VERB,impf,femn,indc,sing,tran,past"]
+        );
+
+        let parse = anal().parse_get("кринжа", 0).unwrap().unwrap();
+        assert_eq!(
+            anal()
+                .inflect_parsed_inizio(parse)
+                .unwrap()
+                .unwrap()
+                .0
+                .into_iter()
+                .map(|form| form.inflect_form)
+                .collect_vec(),
+            vec!["кринж"]
+        );
+    }
+
+    #[test]
+    fn test_declension() {
+        let forms = |word| {
+            anal()
+                .declension(word)
+                .unwrap()
+                .into_iter()
+                .map(|inflect| {
+                    inflect
+                        .0
+                        .into_iter()
+                        .map(|form| form.inflect_form)
+                        .sorted()
+                        .dedup()
+                        .collect_vec()
+                })
+                .collect_vec()
+        };
+
+        assert_eq!(
+            forms("смену"),
+            vec![vec![
+                "смен",
+                "смена",
+                "сменам",
+                "сменами",
+                "сменах",
+                "смене",
+                "сменой",
+                "смену",
+                "смены"
+            ]]
+        );
+        assert_eq!(forms("кринж"), vec![vec!["кринж", "кринжа", "кринжи"]]);
+        // Пользовательская лемма и предлог из основного словаря.
+        assert_eq!(forms("из").len(), 2);
+
+        let parse = anal().parse_get("яндексовое", 0).unwrap().unwrap();
+        let declension = anal().declension_parsed(&parse).unwrap().unwrap();
+        let model = anal().declension("новый").unwrap();
+        assert_eq!(declension.0.len(), model[0].0.len());
+    }
+
+    #[test]
+    fn test_known() {
+        assert!(anal().is_known("кринжа"));
+        assert!(anal().is_known("ножи"));
+        assert!(!anal().is_known("бутявка"));
+        // Без пользовательских словарей слова нет.
+        assert!(!shared_analyzer("data/test/tagger_dict.xml").is_known("кринжа"));
+        assert!(analyzer("data/test/tagger_dict.xml")
+            .1
+            .with_user_dictionaries::<PathBuf>(&[])
+            .unwrap()
+            .user
+            .is_none());
+    }
+
+    #[test_case("кринж\tкринжа\tNOUN,gent\n" => "Lemma 'кринж' has no form equal to it"; "no_normal")]
+    #[test_case("кринж\tкринж\tNOUN,xxxx\n" => "<file>:1: Unknown grammem 'xxxx'"; "grammem")]
+    #[test_case("# мемы\n\nкринж\tкринж\n" => "<file>:3: expected 'lemma<TAB>form<TAB>grammemes' or 'lemma<TAB>like word'"; "format")]
+    #[test_case("смена\tlike сталь\tVERB\n" => "Model word 'сталь' is not a normal form in the dictionary"; "model_grammemes")]
+    #[test_case("смена\tlike бутявка\n" => "Model word 'бутявка' is not a normal form in the dictionary"; "model_unknown")]
+    #[test_case("смена\tlike новый\n" => "Lemma 'смена' doesn't end like model word 'новый' ('-ый')"; "model_mismatch")]
+    fn test_errors(tsv: &str) -> String {
+        match user_err(tsv) {
+            MopsErr::UserDictionary(UserDictErr::Format { line, .. }) => {
+                format!("<file>:{line}: expected 'lemma<TAB>form<TAB>grammemes' or 'lemma<TAB>like word'")
+            }
+            MopsErr::UserDictionary(UserDictErr::Grammem { line, error, .. }) => {
+                format!("<file>:{line}: {error}")
+            }
+            MopsErr::UserDictionary(err) => err.to_string(),
+            err => panic!("{err}"),
+        }
+    }
+}
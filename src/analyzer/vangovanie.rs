@@ -1,13 +1,16 @@
 use smallstr::SmallString;
 use tracing::debug;
 
-use super::Tag;
+use super::{LemmaRowId, Tag};
 use crate::{
     errors::ParseErr,
     morph::{grammemes::Form, UNPRODUCTIVE},
     MorphAnalyzer, Vangovanie, SMALLLEMMA,
 };
 
+/// Слова длиннее, в буквах, не предсказываются: это не слова, а склеенный текст или мусор.
+pub const MAX_VANGA_CHARS: usize = 64;
+
 /// Приставки, которые не меняют парсинга слово.
 ///
 /// Взято из Pymorphy2.
@@ -164,17 +167,39 @@ pub struct VangovanieRes {
     pub(crate) tags: Tag,
     pub(crate) form: Form,
     pub(crate) normal_form: SmallString<[u8; SMALLLEMMA]>,
+    /// Строка лемм словарной основы, по которой предсказано слово.
+    pub(crate) lemma_row_id: LemmaRowId,
     pub(crate) method: Vangovanie,
     pub(crate) score: f32,
 }
 
 impl VangovanieRes {
+    pub fn tag(&self) -> &Tag {
+        &self.tags
+    }
+
+    pub fn form(&self) -> Form {
+        self.form
+    }
+
+    pub fn normal_form(&self) -> &str {
+        &self.normal_form
+    }
+
+    pub fn method(&self) -> &Vangovanie {
+        &self.method
+    }
+
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+
     /// Сортировка результатов Вангования в зависимости от частотности встреченного тега.
     pub fn sort(vec: &mut [Self]) {
         let len = vec.len();
 
         vec.iter_mut().for_each(|vanga| vanga.score /= len as f32);
-        vec.sort_by(|a, b| ((b.score * 100.0) as u8).cmp(&((a.score * 100.0) as u8)));
+        vec.sort_by_key(|vanga| std::cmp::Reverse((vanga.score * 100.0) as u8));
     }
 }
 
@@ -185,6 +210,10 @@ impl MorphAnalyzer {
     pub fn vangovanie(&self, word: &str) -> Result<Option<Vec<VangovanieRes>>, ParseErr> {
         let mut words_vangas = Vec::new();
 
+        if word.chars().nth(MAX_VANGA_CHARS).is_some() {
+            return Ok(None);
+        }
+
         // Алгоритм работы со словами с дефисом. release 0.2.1
         // if let Some((_first, _second)) = word.split_once('-') {
         // #[allow(clippy::single_match)]
@@ -400,6 +429,7 @@ impl MorphAnalyzer {
                 form: parse.form.switch_vanga(),
                 method: method.clone(),
                 normal_form: self.get_lemmas(parse.normal_form)?.to_owned(),
+                lemma_row_id: parse.lemma_row_id,
                 score,
             };
 
@@ -414,11 +444,9 @@ impl MorphAnalyzer {
 
 #[cfg(test)]
 mod test_probability {
-    use tempfile::tempdir;
-
     use crate::{
         morph::grammemes::{Grammem, ParteSpeech},
-        test_infrastructure::infrastructure::make_dict,
+        test_infrastructure::infrastructure::shared_analyzer,
         MorphAnalyzer,
     };
     use itertools::Itertools;
@@ -452,10 +480,7 @@ mod test_probability {
                 .next()
                 .flatten();
             if let Some((t, pos)) = t.to_owned() {
-                if pos.is_none() {
-                    eprintln!("Res is {t:?}");
-                } else {
-                    let pos = pos.unwrap();
+                if let Some(pos) = pos {
                     if pos != needed_pos {
                         eprintln!(
                             "{word} - {:?}",
@@ -463,11 +488,13 @@ mod test_probability {
                                 .unwrap()
                                 .expect("Some")
                                 .iter()
-                                .map(|t| (Grammem::pos_in_tag(&t.tags)))
+                                .map(|t| Grammem::pos_in_tag(&t.tags))
                                 .collect_vec()
                         );
                         panic!()
                     };
+                } else {
+                    eprintln!("Res is {t:?}");
                 }
             }
         }
@@ -481,11 +508,7 @@ mod test_probability {
     #[test_case("воздушно-канального" => ParteSpeech::AdjectiveFull)]
     #[test_case("интернет-магазина" => ParteSpeech::Noun)]
     fn eprintln_vangovanie_defis(word: &str) -> ParteSpeech {
-        let tmp_dir = tempdir().unwrap();
-        let fst = tmp_dir.path().join("defis.fst");
-
-        let dict = make_dict("data/test/small_dict.xml", fst.clone());
-        let anal = MorphAnalyzer::init(dict, fst).unwrap();
+        let anal = shared_analyzer("data/test/small_dict.xml");
         anal.vangovanie(word)
             .unwrap()
             .expect("Some")
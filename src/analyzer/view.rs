@@ -0,0 +1,192 @@
+use std::borrow::Cow;
+
+use crate::{
+    analyzer::{Parse, Tag},
+    errors::{MopsErr, MopsResult, ParseErr},
+    morph::grammemes::Grammem,
+    Method, MorphAnalyzer, ParsedWord,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Разбор слова без аллокаций: тег и нормальная форма заимствуются из словаря анализатора.
+///
+/// Разборы вангования вычисляются на лету и хранятся внутри.
+pub struct ParsedWordRef<'a> {
+    word: &'a str,
+    tag: Cow<'a, Tag>,
+    normal_form: Cow<'a, str>,
+    method: Method,
+}
+
+impl<'a> ParsedWordRef<'a> {
+    pub fn word(&self) -> &'a str {
+        self.word
+    }
+
+    pub fn tag(&self) -> &[Grammem] {
+        &self.tag
+    }
+
+    pub fn normal_form(&self) -> &str {
+        &self.normal_form
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Содержит ли тег все граммемы.
+    pub fn contains(&self, grammemes: &[Grammem]) -> bool {
+        grammemes.iter().all(|grammem| self.tag.contains(grammem))
+    }
+
+    /// Преобразование в независимый от анализатора `ParsedWord`.
+    pub fn into_owned(self) -> ParsedWord {
+        ParsedWord {
+            word: self.word.to_string(),
+            tags: self.tag.into_owned(),
+            normal_form: self.normal_form.into_owned(),
+            method: self.method,
+        }
+    }
+}
+
+impl<'a> From<ParsedWordRef<'a>> for ParsedWord {
+    fn from(parse: ParsedWordRef<'a>) -> Self {
+        parse.into_owned()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Источник разборов: словарь или готовые разборы вангования и пользовательских словарей.
+enum ParseSource<'a> {
+    Dictionary(std::slice::Iter<'a, Parse>),
+    Owned(std::vec::IntoIter<ParsedWord>),
+}
+
+#[derive(Debug, Clone)]
+/// Ленивый итератор по разборам слова.
+///
+/// Словарные разборы выдаются в порядке хранения в словаре, без сортировки:
+/// порядок может отличаться от `parse`, где они отсортированы.
+pub struct ParseIter<'a> {
+    analyzer: &'a MorphAnalyzer,
+    word: &'a str,
+    source: ParseSource<'a>,
+}
+
+impl<'a> ParseIter<'a> {
+    /// Первый разбор, содержащий все граммемы.
+    pub fn find_grammemes(self, grammemes: &[Grammem]) -> MopsResult<Option<ParsedWordRef<'a>>> {
+        for parse in self {
+            let parse = parse?;
+            if parse.contains(grammemes) {
+                return Ok(Some(parse));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a> Iterator for ParseIter<'a> {
+    type Item = MopsResult<ParsedWordRef<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.source {
+            ParseSource::Dictionary(parses) => parses.next().map(|parse| {
+                self.analyzer
+                    .parse_ref(self.word, parse)
+                    .map_err(MopsErr::Parse)
+            }),
+            ParseSource::Owned(parses) => parses.next().map(|parse| {
+                Ok(ParsedWordRef {
+                    word: self.word,
+                    tag: Cow::Owned(parse.tags),
+                    normal_form: Cow::Owned(parse.normal_form),
+                    method: parse.method,
+                })
+            }),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.source {
+            ParseSource::Dictionary(parses) => parses.size_hint(),
+            ParseSource::Owned(parses) => parses.size_hint(),
+        }
+    }
+}
+
+impl MorphAnalyzer {
+    /// Ленивый разбор слова: словарные разборы не копируются до запроса.
+    pub(crate) fn parse_view<'a>(&'a self, word: &'a str) -> Result<ParseIter<'a>, ParseErr> {
+        let source = match self.fst.get(word.as_bytes()) {
+            Some(id) if self.user_for(word).is_none() => {
+                ParseSource::Dictionary(self.get_parse(id)?.iter())
+            }
+            _ => ParseSource::Owned(self.parse_word(word)?.0.into_iter()),
+        };
+
+        Ok(ParseIter {
+            analyzer: self,
+            word,
+            source,
+        })
+    }
+
+    /// Заимствующий разбор слова из словаря.
+    fn parse_ref<'a>(
+        &'a self,
+        word: &'a str,
+        parse: &Parse,
+    ) -> Result<ParsedWordRef<'a>, ParseErr> {
+        Ok(ParsedWordRef {
+            word,
+            tag: Cow::Borrowed(self.get_tag(parse.tag)?),
+            normal_form: Cow::Borrowed(self.get_lemmas(parse.normal_form)?.as_str()),
+            method: Method::Dictionary,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{morph::grammemes::Case, test_infrastructure::infrastructure::shared_analyzer};
+    use itertools::Itertools;
+    use test_case::test_case;
+
+    #[test_case("стали"; "noun_verb")]
+    #[test_case("ножей"; "noun")]
+    fn test_parse_view(word: &str) {
+        let anal = shared_analyzer("data/test/tagger_dict.xml");
+
+        let views = anal
+            .parse_iter(word)
+            .unwrap()
+            .collect::<MopsResult<Vec<_>>>()
+            .unwrap();
+        assert!(views.iter().all(|view| matches!(view.tag, Cow::Borrowed(_))
+            && matches!(view.normal_form, Cow::Borrowed(_))));
+
+        let owned = views
+            .into_iter()
+            .map(ParsedWord::from)
+            .sorted()
+            .collect_vec();
+        assert_eq!(owned, anal.parse(word).unwrap().0);
+
+        let genetivus = anal
+            .parse_iter(word)
+            .unwrap()
+            .find_grammemes(&[Grammem::Case(Case::Genetivus)])
+            .unwrap()
+            .map(ParsedWordRef::into_owned);
+        assert_eq!(
+            genetivus,
+            anal.parse(word)
+                .unwrap()
+                .find(vec![Grammem::Case(Case::Genetivus)])
+        );
+    }
+}
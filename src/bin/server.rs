@@ -0,0 +1,74 @@
+use clap::Parser;
+use mimalloc::MiMalloc;
+use morph_rs::{server, CacheConfig, Language, MorphAnalyzer};
+use std::{net::SocketAddr, path::PathBuf, sync::Arc};
+use tracing::info;
+
+#[global_allocator]
+static GLOBAL: MiMalloc = MiMalloc;
+
+#[derive(Debug, Parser)]
+#[clap(author, version, about = "HTTP JSON API морфологического анализатора", long_about = None)]
+struct Args {
+    /// Адрес, на котором принимаются запросы.
+    #[clap(short, long, default_value = "127.0.0.1:8080")]
+    addr: SocketAddr,
+
+    /// Словарь OpenCorpora для сборки с `--init`.
+    #[clap(short, long = "dict", default_value = "dict.opcorpora.xml")]
+    dictionary: PathBuf,
+
+    /// Каталог собранного словаря.
+    #[clap(long, default_value = "data/result/")]
+    db: PathBuf,
+
+    #[clap(short, default_value = "russian")]
+    language: Language,
+
+    #[clap(short, long, default_value_t = false)]
+    /// Собрать словарь из `--dict` перед запуском.
+    init: bool,
+
+    /// Размер кэша частых слов на каждый вид запроса, 0 - без кэша.
+    #[clap(long, default_value_t = 0)]
+    cache: usize,
+
+    /// Пользовательские словари поверх основного: `.xml` в формате OpenCorpora или TSV.
+    #[clap(long)]
+    user: Vec<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let Args {
+        addr,
+        dictionary,
+        db,
+        language,
+        init,
+        cache,
+        user,
+    } = Args::parse();
+
+    let start = std::time::Instant::now();
+    let mut morph = match init {
+        true => {
+            std::fs::create_dir_all(&db)?;
+            let dict = MorphAnalyzer::create(dictionary, db.clone(), language)?;
+            MorphAnalyzer::init(dict, &db)?
+        }
+        false => MorphAnalyzer::open(&db)?,
+    }
+    .with_user_dictionaries(&user)?;
+    if cache > 0 {
+        morph = morph.with_cache(CacheConfig::new(cache));
+    }
+    info!("Словарь открывается за: {:?}", start.elapsed());
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    server::serve(listener, Arc::new(morph), server::shutdown_signal()).await?;
+
+    Ok(())
+}
@@ -0,0 +1,508 @@
+//! C-интерфейс для встраивания анализатора в сервисы не на Rust.
+//!
+//! Анализатор и результаты передаются непрозрачными указателями, каждый из которых
+//! освобождается своей функцией `mops_*_free`. Функции возвращают `MopsStatus`,
+//! текст последней ошибки потока доступен через `mops_last_error`.
+//!
+//! Заголовок `include/morph_rs.h` генерируется `cbindgen` при сборке с `--features capi`,
+//! пример использования - `tests/c/test_capi.c`.
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+};
+
+use crate::{
+    errors::{MopsErr, ParseErr},
+    json::tag_string,
+    morph::grammemes::Grammem,
+    InflectWords, MorphAnalyzer, NormalizedWords, ParsedWords,
+};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Коды возврата, повторяющие варианты `MopsErr` и `ParseErr`.
+pub enum MopsStatus {
+    Ok = 0,
+    /// Передан нулевой указатель.
+    NullPointer = 1,
+    /// Строка не в UTF-8.
+    InvalidUtf8 = 2,
+    /// Паника внутри анализатора.
+    Panic = 3,
+
+    File = 10,
+    Io = 11,
+    Xml = 12,
+    Serde = 13,
+    Dictionary = 14,
+    Fst = 15,
+    UserDictionary = 16,
+
+    OutOfBound = 20,
+    LostNormalForm = 21,
+    LostLemmaId = 22,
+    LostLemmasRow = 23,
+    LostParse = 24,
+    UnknownWord = 25,
+    NoPos = 26,
+    Declension = 27,
+    BinaryTag = 28,
+    Fio = 29,
+    Numeral = 30,
+    UnknownGrammem = 31,
+    Template = 32,
+}
+
+impl From<&ParseErr> for MopsStatus {
+    fn from(err: &ParseErr) -> Self {
+        match err {
+            ParseErr::OutOfBound { .. } => Self::OutOfBound,
+            ParseErr::LostNormalForm(_) => Self::LostNormalForm,
+            ParseErr::LostLemmaId(_) => Self::LostLemmaId,
+            ParseErr::LostLemmasRow(_) => Self::LostLemmasRow,
+            ParseErr::LostParse(_) => Self::LostParse,
+            ParseErr::UnknownWord(_) => Self::UnknownWord,
+            ParseErr::NoPos(_) => Self::NoPos,
+            ParseErr::Declension(_) => Self::Declension,
+            ParseErr::BinaryTag(_) => Self::BinaryTag,
+            ParseErr::Fio(_) => Self::Fio,
+            ParseErr::Numeral(_) => Self::Numeral,
+            ParseErr::UnknownGrammem(_) => Self::UnknownGrammem,
+            ParseErr::Template(_) => Self::Template,
+        }
+    }
+}
+
+impl From<&MopsErr> for MopsStatus {
+    fn from(err: &MopsErr) -> Self {
+        match err {
+            MopsErr::File { .. } => Self::File,
+            MopsErr::IO(_) => Self::Io,
+            MopsErr::XMLde(_) => Self::Xml,
+            MopsErr::Serde(_) => Self::Serde,
+            MopsErr::Dictionary(_) => Self::Dictionary,
+            MopsErr::FSTMap(_) => Self::Fst,
+            MopsErr::UserDictionary(_) => Self::UserDictionary,
+            MopsErr::Parse(err) => err.into(),
+        }
+    }
+}
+
+/// Открытый анализатор.
+pub struct MopsAnalyzer(MorphAnalyzer);
+
+#[repr(C)]
+/// Одно слово результата. Строки принадлежат `MopsWords` и живут до `mops_words_free`.
+pub struct MopsWord {
+    /// Слово: разобранное, нормальная форма или измененная форма.
+    pub word: *const c_char,
+    /// Нормальная форма слова.
+    pub normal_form: *const c_char,
+    /// Граммемы `OpenCorpora` через запятую: "NOUN,inan,femn,sing,gent".
+    pub tag: *const c_char,
+    /// Способ разбора: "Dictionary", "KnowPrefix(...)", "UnknowPrefix(...)", "Postfix".
+    pub method: *const c_char,
+}
+
+/// Результат разбора, нормализации или изменения формы.
+pub struct MopsWords {
+    words: Vec<MopsWord>,
+    _strings: Vec<CString>,
+}
+
+impl MopsWords {
+    fn new(rows: impl IntoIterator<Item = [String; 4]>) -> Self {
+        let mut strings = Vec::new();
+        let mut words = Vec::new();
+
+        for row in rows {
+            // Нулевых байтов в словаре нет, а входное слово пришло C-строкой.
+            let [word, normal_form, tag, method] =
+                row.map(|field| CString::new(field).unwrap_or_default());
+            words.push(MopsWord {
+                word: word.as_ptr(),
+                normal_form: normal_form.as_ptr(),
+                tag: tag.as_ptr(),
+                method: method.as_ptr(),
+            });
+            // Буфер `CString` не переезжает при перемещении, указатели остаются валидны.
+            strings.extend([word, normal_form, tag, method]);
+        }
+
+        Self {
+            words,
+            _strings: strings,
+        }
+    }
+
+    fn from_parsed(parsed: ParsedWords) -> Self {
+        Self::new(parsed.0.into_iter().map(|word| {
+            [
+                word.word(),
+                word.normal_form(),
+                tag_string(&word.tag()),
+                word.method().to_string(),
+            ]
+        }))
+    }
+
+    fn from_normalized(normalized: NormalizedWords) -> Self {
+        Self::new(normalized.0.into_iter().map(|word| {
+            [
+                word.word(),
+                word.word(),
+                tag_string(&word.tag()),
+                word.method().to_string(),
+            ]
+        }))
+    }
+
+    fn from_inflect(inflect: Option<InflectWords>) -> Self {
+        Self::new(inflect.into_iter().flat_map(|words| words.0).map(|word| {
+            [
+                word.word(),
+                word.normal_form.to_owned(),
+                tag_string(&word.tag()),
+                word.method().to_string(),
+            ]
+        }))
+    }
+}
+
+fn set_last_error(message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = CString::new(message).ok());
+}
+
+/// Строка из C-указателя.
+///
+/// # Safety
+/// `string` - нулевой указатель или C-строка.
+unsafe fn c_str<'a>(string: *const c_char) -> Result<&'a str, MopsStatus> {
+    if string.is_null() {
+        set_last_error("Null pointer passed as a string".to_string());
+        return Err(MopsStatus::NullPointer);
+    }
+    CStr::from_ptr(string).to_str().map_err(|err| {
+        set_last_error(err.to_string());
+        MopsStatus::InvalidUtf8
+    })
+}
+
+/// Запуск `call` с переводом ошибок и паник в `MopsStatus`.
+fn guard(call: impl FnOnce() -> Result<(), MopsStatus>) -> MopsStatus {
+    match catch_unwind(AssertUnwindSafe(call)) {
+        Ok(Ok(())) => MopsStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => {
+            set_last_error("Panic in morph-rs".to_string());
+            MopsStatus::Panic
+        }
+    }
+}
+
+fn mops_err(err: MopsErr) -> MopsStatus {
+    let status = MopsStatus::from(&err);
+    set_last_error(err.to_string());
+    status
+}
+
+/// Запись результата `words` в `out`.
+///
+/// # Safety
+/// `out` - валидный указатель.
+unsafe fn write_words(
+    out: *mut *mut MopsWords,
+    words: Result<MopsWords, MopsErr>,
+) -> Result<(), MopsStatus> {
+    let words = words.map_err(mops_err)?;
+    *out = Box::into_raw(Box::new(words));
+    Ok(())
+}
+
+/// Проверка указателей анализатора и результата, общая для всех запросов.
+///
+/// # Safety
+/// `analyzer` - нулевой указатель или результат `mops_open`.
+unsafe fn analyzer_and_out<'a>(
+    analyzer: *const MopsAnalyzer,
+    out: *mut *mut MopsWords,
+) -> Result<&'a MorphAnalyzer, MopsStatus> {
+    if analyzer.is_null() || out.is_null() {
+        set_last_error("Null pointer passed as an analyzer or a result".to_string());
+        return Err(MopsStatus::NullPointer);
+    }
+    *out = ptr::null_mut();
+    Ok(&(*analyzer).0)
+}
+
+/// Открытие собранного словаря из каталога `path`.
+///
+/// # Safety
+/// `path` - C-строка, `out` - валидный указатель. Анализатор освобождается `mops_free`.
+#[no_mangle]
+pub unsafe extern "C" fn mops_open(path: *const c_char, out: *mut *mut MopsAnalyzer) -> MopsStatus {
+    guard(|| {
+        if out.is_null() {
+            set_last_error("Null pointer passed as a result".to_string());
+            return Err(MopsStatus::NullPointer);
+        }
+        *out = ptr::null_mut();
+
+        let morph = MorphAnalyzer::open(c_str(path)?).map_err(mops_err)?;
+        *out = Box::into_raw(Box::new(MopsAnalyzer(morph)));
+        Ok(())
+    })
+}
+
+/// Освобождение анализатора.
+///
+/// # Safety
+/// `analyzer` - нулевой указатель или результат `mops_open`, освобождаемый один раз.
+#[no_mangle]
+pub unsafe extern "C" fn mops_free(analyzer: *mut MopsAnalyzer) {
+    if !analyzer.is_null() {
+        drop(Box::from_raw(analyzer));
+    }
+}
+
+/// Все разборы слова. Для несловарного слова без вангования результат пуст.
+///
+/// # Safety
+/// `analyzer` - результат `mops_open`, `word` - C-строка, `out` - валидный указатель.
+/// Результат освобождается `mops_words_free`.
+#[no_mangle]
+pub unsafe extern "C" fn mops_parse(
+    analyzer: *const MopsAnalyzer,
+    word: *const c_char,
+    out: *mut *mut MopsWords,
+) -> MopsStatus {
+    guard(|| {
+        let morph = analyzer_and_out(analyzer, out)?;
+        let word = c_str(word)?;
+        write_words(out, morph.parse(word).map(MopsWords::from_parsed))
+    })
+}
+
+/// Нормальные формы слова.
+///
+/// # Safety
+/// То же, что у `mops_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn mops_normalize(
+    analyzer: *const MopsAnalyzer,
+    word: *const c_char,
+    out: *mut *mut MopsWords,
+) -> MopsStatus {
+    guard(|| {
+        let morph = analyzer_and_out(analyzer, out)?;
+        let word = c_str(word)?;
+        write_words(out, morph.normalize(word).map(MopsWords::from_normalized))
+    })
+}
+
+/// Формы слова с граммемами `grammemes` через запятую ("plur,ablt").
+/// При нулевом `grammemes` слово приводится к начальной форме.
+///
+/// # Safety
+/// То же, что у `mops_parse`, `grammemes` - нулевой указатель или C-строка.
+#[no_mangle]
+pub unsafe extern "C" fn mops_inflect(
+    analyzer: *const MopsAnalyzer,
+    word: *const c_char,
+    grammemes: *const c_char,
+    out: *mut *mut MopsWords,
+) -> MopsStatus {
+    guard(|| {
+        let morph = analyzer_and_out(analyzer, out)?;
+        let word = c_str(word)?;
+
+        let inflect = if grammemes.is_null() {
+            morph.inflect_inizio(word)
+        } else {
+            let grammemes = c_str(grammemes)?
+                .split(',')
+                .map(str::trim)
+                .filter(|grammem| !grammem.is_empty())
+                .map(str::parse::<Grammem>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| mops_err(MopsErr::Parse(err)))?;
+            morph.inflect_forms(word, grammemes)
+        };
+        write_words(out, inflect.map(MopsWords::from_inflect))
+    })
+}
+
+/// Количество слов в результате.
+///
+/// # Safety
+/// `words` - нулевой указатель или результат запроса.
+#[no_mangle]
+pub unsafe extern "C" fn mops_words_len(words: *const MopsWords) -> usize {
+    words.as_ref().map_or(0, |words| words.words.len())
+}
+
+/// Слово результата по индексу, нулевой указатель за границей результата.
+///
+/// # Safety
+/// `words` - нулевой указатель или результат запроса.
+#[no_mangle]
+pub unsafe extern "C" fn mops_words_get(words: *const MopsWords, index: usize) -> *const MopsWord {
+    words
+        .as_ref()
+        .and_then(|words| words.words.get(index))
+        .map_or(ptr::null(), |word| word as *const MopsWord)
+}
+
+/// Освобождение результата.
+///
+/// # Safety
+/// `words` - нулевой указатель или результат запроса, освобождаемый один раз.
+#[no_mangle]
+pub unsafe extern "C" fn mops_words_free(words: *mut MopsWords) {
+    if !words.is_null() {
+        drop(Box::from_raw(words));
+    }
+}
+
+/// Текст последней ошибки в текущем потоке или нулевой указатель.
+/// Строка действительна до следующего вызова функций `mops_*` в этом потоке.
+#[no_mangle]
+pub extern "C" fn mops_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |err| err.as_ptr())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::analyzer;
+    use test_case::test_case;
+
+    /// Слова результата как (слово, нормальная форма, тег).
+    unsafe fn collect(words: *mut MopsWords) -> Vec<(String, String, String)> {
+        let result = (0..mops_words_len(words))
+            .map(|i| {
+                let word = &*mops_words_get(words, i);
+                let field = |ptr| CStr::from_ptr(ptr).to_str().unwrap().to_string();
+                (field(word.word), field(word.normal_form), field(word.tag))
+            })
+            .collect();
+        assert!(mops_words_get(words, mops_words_len(words)).is_null());
+        mops_words_free(words);
+        result
+    }
+
+    fn open() -> (tempfile::TempDir, *mut MopsAnalyzer) {
+        let (tmp_dir, _) = analyzer("data/test/tagger_dict.xml");
+
+        let path = CString::new(tmp_dir.path().to_str().unwrap()).unwrap();
+        let mut analyzer = ptr::null_mut();
+        assert_eq!(
+            unsafe { mops_open(path.as_ptr(), &mut analyzer) },
+            MopsStatus::Ok
+        );
+        (tmp_dir, analyzer)
+    }
+
+    #[test]
+    fn test_capi() {
+        let (_tmp_dir, analyzer) = open();
+        let word = CString::new("стали").unwrap();
+
+        unsafe {
+            let mut words = ptr::null_mut();
+            assert_eq!(
+                mops_parse(analyzer, word.as_ptr(), &mut words),
+                MopsStatus::Ok
+            );
+            let parsed = collect(words);
+            assert_eq!(parsed.len(), 6);
+            assert!(parsed.contains(&(
+                "стали".to_string(),
+                "сталь".to_string(),
+                "NOUN,inan,gent,femn,sing".to_string()
+            )));
+
+            assert_eq!(
+                mops_normalize(analyzer, word.as_ptr(), &mut words),
+                MopsStatus::Ok
+            );
+            assert!(collect(words).iter().any(|(word, ..)| word == "сталь"));
+
+            let grammemes = CString::new("NOUN, plur,ablt").unwrap();
+            assert_eq!(
+                mops_inflect(analyzer, word.as_ptr(), grammemes.as_ptr(), &mut words),
+                MopsStatus::Ok
+            );
+            assert_eq!(collect(words)[0].0, "сталями");
+
+            assert_eq!(
+                mops_inflect(analyzer, word.as_ptr(), ptr::null(), &mut words),
+                MopsStatus::Ok
+            );
+            assert!(collect(words).iter().any(|(word, ..)| word == "сталь"));
+
+            mops_free(analyzer);
+        }
+    }
+
+    #[test_case("кваквакря", Some("plur") => MopsStatus::UnknownWord)]
+    #[test_case("стали", Some("plur,nosuchgram") => MopsStatus::UnknownGrammem)]
+    #[test_case("стали", Some("") => MopsStatus::Ok)]
+    fn test_capi_status(word: &str, grammemes: Option<&str>) -> MopsStatus {
+        let (_tmp_dir, analyzer) = open();
+        let word = CString::new(word).unwrap();
+        let grammemes = grammemes.map(|grammemes| CString::new(grammemes).unwrap());
+
+        unsafe {
+            let mut words = ptr::null_mut();
+            let status = mops_inflect(
+                analyzer,
+                word.as_ptr(),
+                grammemes.as_ref().map_or(ptr::null(), |g| g.as_ptr()),
+                &mut words,
+            );
+            if status == MopsStatus::Ok {
+                collect(words);
+            } else {
+                assert!(words.is_null());
+                assert!(!mops_last_error().is_null());
+            }
+            mops_free(analyzer);
+            status
+        }
+    }
+
+    #[test]
+    fn test_capi_errors() {
+        let path = CString::new("/nonexistent/morph-rs").unwrap();
+        let mut analyzer = ptr::null_mut();
+        unsafe {
+            assert_ne!(mops_open(path.as_ptr(), &mut analyzer), MopsStatus::Ok);
+            assert!(analyzer.is_null());
+            assert_eq!(
+                mops_open(ptr::null(), &mut analyzer),
+                MopsStatus::NullPointer
+            );
+
+            let mut words = ptr::null_mut();
+            assert_eq!(
+                mops_parse(ptr::null(), ptr::null(), &mut words),
+                MopsStatus::NullPointer
+            );
+            assert_eq!(mops_words_len(ptr::null()), 0);
+            assert!(mops_words_get(ptr::null(), 0).is_null());
+            mops_words_free(ptr::null_mut());
+            mops_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use morph_rs::{Change, DictionaryDiff, Lemma, Meta, SetDiff};
+use std::{fmt::Write as _, fs, io, path::Path};
+
+/// Список слов для сравнения: по слову в строке, пустые строки пропускаются.
+pub fn read_words(path: &Path) -> io::Result<Vec<String>> {
+    Ok(fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect())
+}
+
+fn meta(meta: &Meta) -> String {
+    format!("{} (revision {})", meta.version(), meta.revision())
+}
+
+fn lemma(lemma: &Lemma) -> String {
+    format!("{} {}", lemma.id, lemma.word)
+}
+
+/// Секция изменений множества: счетчики и по строке "+ .." или "- .." на элемент.
+fn set<T>(output: &mut String, name: &str, diff: &SetDiff<T>, show: impl Fn(&T) -> String) {
+    let _ = writeln!(
+        output,
+        "{name}: +{} -{}",
+        diff.added.len(),
+        diff.removed.len()
+    );
+    for item in &diff.added {
+        let _ = writeln!(output, "+ {}", show(item));
+    }
+    for item in &diff.removed {
+        let _ = writeln!(output, "- {}", show(item));
+    }
+}
+
+fn change(output: &mut String, name: &str, change: &Option<Change>) {
+    if let Some(Change { old, new }) = change {
+        let _ = writeln!(output, "  {name}:");
+        for parse in old.iter().filter(|parse| !new.contains(parse)) {
+            let _ = writeln!(output, "  - {parse}");
+        }
+        for parse in new.iter().filter(|parse| !old.contains(parse)) {
+            let _ = writeln!(output, "  + {parse}");
+        }
+        // Те же разборы в другом порядке.
+        if old.iter().all(|parse| new.contains(parse))
+            && new.iter().all(|parse| old.contains(parse))
+        {
+            let _ = writeln!(output, "  ~ order");
+        }
+    }
+}
+
+/// Различия словарей в виде, похожем на `diff`.
+pub fn render(diff: &DictionaryDiff) -> String {
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "OpenCorpora {} -> {}",
+        meta(&diff.old),
+        meta(&diff.new)
+    );
+
+    set(&mut output, "lemmas", &diff.lemmas, lemma);
+    set(&mut output, "tags", &diff.tags, String::clone);
+    set(&mut output, "links", &diff.links, |link| {
+        link.iter().map(lemma).collect::<Vec<_>>().join(", ")
+    });
+
+    let _ = writeln!(output, "words: {}", diff.words.len());
+    for word in &diff.words {
+        let _ = writeln!(output, "~ {}", word.word);
+        change(&mut output, "parse", &word.parse);
+        change(&mut output, "normalize", &word.normalize);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::test_infrastructure::xml_analyzer;
+
+    #[test]
+    fn test_render() {
+        let old = fs::read_to_string("data/test/tagger_dict.xml").unwrap();
+        let new = old.replace(r#"revision="417257""#, r#"revision="417258""#);
+        let preposition = old
+            .lines()
+            .find(|line| line.contains(r#"<lemma id="5""#))
+            .unwrap();
+        let new = new.replace(&format!("{preposition}\n"), "");
+        let (_old_dir, old) = xml_analyzer(&old);
+        let (_new_dir, new) = xml_analyzer(&new);
+
+        let words = ["из", "ножи"].map(String::from);
+        assert_eq!(
+            render(&old.diff(&new, Some(&words))),
+            "\
+OpenCorpora 0.92 (revision 417257) -> 0.92 (revision 417258)
+lemmas: +0 -1
+- 5 из
+tags: +0 -1
+- PREP
+links: +0 -0
+words: 1
+~ из
+  parse:
+  - из PREP Dictionary
+  normalize:
+  - из PREP Dictionary
+"
+        );
+    }
+
+    #[test]
+    fn test_read_words() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("words.txt");
+        fs::write(&path, "стали\n\n  Ножи \n").unwrap();
+
+        assert_eq!(read_words(&path).unwrap(), vec!["стали", "ножи"]);
+    }
+}
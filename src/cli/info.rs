@@ -0,0 +1,97 @@
+use morph_rs::{DictionaryStats, Table};
+use std::fmt::Write as _;
+
+/// Размер в байтах, КиБ или МиБ.
+fn size(bytes: usize) -> String {
+    match bytes {
+        bytes if bytes >= 1024 * 1024 => format!("{:.1} MiB", bytes as f64 / 1024.0 / 1024.0),
+        bytes if bytes >= 1024 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        bytes => format!("{bytes} B"),
+    }
+}
+
+/// Доля от общего количества в процентах.
+fn share(count: usize, total: usize) -> String {
+    format!("{:.2}%", count as f64 * 100.0 / total.max(1) as f64)
+}
+
+/// Сводка словаря таблицами: размеры, память, части речи и `top` самых частых тегов.
+pub fn render(stats: &DictionaryStats, top: usize) -> String {
+    let mut output = String::new();
+    let _ = writeln!(
+        output,
+        "OpenCorpora {} (revision {}), {:?}",
+        stats.meta.version(),
+        stats.meta.revision(),
+        stats.meta.language()
+    );
+
+    let mut counts = Table::new(["", "count"]);
+    for (name, count) in [
+        ("wordforms", stats.wordforms),
+        ("parses", stats.parses),
+        ("lemmas", stats.lemmas),
+        ("tags", stats.tags),
+        ("lemma_rows", stats.lemma_rows),
+        ("opencorpora_lemmas", stats.opencorpora_lemmas),
+        ("paradigms", stats.paradigms),
+    ] {
+        counts.push([name.to_string(), count.to_string()]);
+    }
+    output.push_str(&counts.to_string());
+
+    let total = stats.memory.total();
+    let mut memory = Table::new(["memory", "size", "share"]);
+    for (name, bytes) in stats.memory.components() {
+        memory.push([name.to_string(), size(bytes), share(bytes, total)]);
+    }
+    memory.push(["total".to_string(), size(total), share(total, total)]);
+    output.push_str(&memory.to_string());
+
+    let mut pos = Table::new(["POS", "parses", "share"]);
+    let mut by_count = stats.pos.iter().collect::<Vec<_>>();
+    by_count.sort_by(|(_, a), (_, b)| b.cmp(a));
+    for (name, count) in by_count {
+        pos.push([name.clone(), count.to_string(), share(*count, stats.parses)]);
+    }
+    output.push_str(&pos.to_string());
+
+    let mut tags = Table::new(["tag", "parses", "share"]);
+    for (tag, count) in stats.tag_frequency.iter().take(top) {
+        tags.push([tag.clone(), count.to_string(), share(*count, stats.parses)]);
+    }
+    output.push_str(&tags.to_string());
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::test_infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test_case(512 => "512 B")]
+    #[test_case(2048 => "2.0 KiB")]
+    #[test_case(3 * 1024 * 1024 + 512 * 1024 => "3.5 MiB")]
+    fn test_size(bytes: usize) -> String {
+        size(bytes)
+    }
+
+    #[test]
+    fn test_render() {
+        let morph = shared_analyzer("data/test/tagger_dict.xml");
+
+        let output = render(&morph.stats(), 3);
+        assert!(output.starts_with("OpenCorpora 0.92 (revision 417257), Russian\n"));
+        assert!(output.contains("| wordforms "));
+        assert!(output.contains("| total "));
+        assert!(output.contains("| NOUN "));
+        // Заголовок и три самых частых тега.
+        let tags = output.rsplit("| tag ").next().unwrap();
+        assert_eq!(
+            tags.lines().filter(|line| line.starts_with("| ")).count(),
+            3
+        );
+    }
+}
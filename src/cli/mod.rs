@@ -0,0 +1,16 @@
+/// Вывод различий двух собранных словарей.
+pub mod diff;
+/// Сводка содержимого словаря.
+pub mod info;
+/// Разметка файлов корпуса в TSV, JSON или CoNLL-U.
+pub mod process;
+/// Разовые запросы к словарю с выбором разбора по граммемам и номеру.
+pub mod query;
+/// Интерактивный разбор слов с историей команд.
+#[cfg(feature = "repl")]
+pub mod repl;
+/// Построчная обработка stdin с JSON-результатом на каждую строку.
+pub mod stream;
+/// Тестовые анализаторы для команд: бинарник не видит `pub(crate)` инфраструктуру библиотеки.
+#[cfg(test)]
+pub(crate) mod test_infrastructure;
@@ -0,0 +1,512 @@
+use clap::ValueEnum;
+use morph_rs::{
+    errors::MopsResult, json::tag_string, morph::ud, AnalyzedToken, Method, MorphAnalyzer,
+    TokenAnalysis, Vangovanie,
+};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+use tracing::{error, info};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// Формат размеченного результата.
+pub enum Format {
+    /// Токен, лемма и тег через табуляцию, предложения через пустую строку.
+    Tsv,
+    /// Одна JSON-строка на предложение.
+    Json,
+    /// CoNLL-U с частями речи и признаками Universal Dependencies.
+    Conllu,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Tsv => "tsv",
+            Format::Json => "jsonl",
+            Format::Conllu => "conllu",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Сводка обработки: сколько токенов разобрано каждым способом.
+pub struct Summary {
+    pub files: usize,
+    pub failed: usize,
+    pub sentences: usize,
+    pub tokens: usize,
+    /// Словарные слова и варианты вангования: "Dictionary", "KnownPrefix", "UnknownPrefix", "Postfix".
+    pub methods: BTreeMap<&'static str, usize>,
+    /// Токены без разбора: псевдотеги "PNCT", "NUMB", "LATN", "ROMN", "UNKN".
+    pub pseudo: BTreeMap<String, usize>,
+}
+
+impl Summary {
+    fn count(&mut self, token: &AnalyzedToken) {
+        self.tokens += 1;
+        match token.analysis() {
+            TokenAnalysis::Parsed(parses) => {
+                if let Some(parse) = parses.0.first() {
+                    *self
+                        .methods
+                        .entry(method_name(&parse.method()))
+                        .or_default() += 1;
+                }
+            }
+            TokenAnalysis::Pseudo(tag) => *self.pseudo.entry(tag.to_string()).or_default() += 1,
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        self.files += other.files;
+        self.failed += other.failed;
+        self.sentences += other.sentences;
+        self.tokens += other.tokens;
+        for (method, count) in other.methods {
+            *self.methods.entry(method).or_default() += count;
+        }
+        for (tag, count) in other.pseudo {
+            *self.pseudo.entry(tag).or_default() += count;
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Files: {} (failed: {}), sentences: {}, tokens: {}",
+            self.files, self.failed, self.sentences, self.tokens
+        )?;
+        for (method, count) in &self.methods {
+            writeln!(
+                f,
+                "{method:<16}{count:>10}{:>8.2}%",
+                percent(*count, self.tokens)
+            )?;
+        }
+        for (tag, count) in &self.pseudo {
+            writeln!(
+                f,
+                "{tag:<16}{count:>10}{:>8.2}%",
+                percent(*count, self.tokens)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 * 100.0 / total as f64
+    }
+}
+
+/// Название способа разбора без данных варианта вангования.
+fn method_name(method: &Method) -> &'static str {
+    match method {
+        Method::Dictionary => "Dictionary",
+        Method::Vangovanie(Vangovanie::KnownPrefix(_)) => "KnownPrefix",
+        Method::Vangovanie(Vangovanie::UnknownPrefix(_)) => "UnknownPrefix",
+        Method::Vangovanie(Vangovanie::Postfix) => "Postfix",
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonToken<'a> {
+    token: &'a str,
+    lemma: String,
+    tag: String,
+    /// Способ разбора, для псевдотегов отсутствует.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    method: Option<&'static str>,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSentence<'a> {
+    text: &'a str,
+    tokens: Vec<JsonToken<'a>>,
+}
+
+/// Тег токена: граммемы первого разбора или псевдотег.
+fn token_tag(token: &AnalyzedToken) -> String {
+    match token.analysis() {
+        TokenAnalysis::Parsed(parses) => parses
+            .0
+            .first()
+            .map(|parse| tag_string(&parse.tag()))
+            .unwrap_or_default(),
+        TokenAnalysis::Pseudo(tag) => tag.to_string(),
+    }
+}
+
+/// Разметка текста в формате `format`.
+///
+/// Каждая непустая строка текста считается предложением, `name` идет в `sent_id` CoNLL-U.
+pub fn annotate(
+    morph: &MorphAnalyzer,
+    text: &str,
+    format: Format,
+    name: &str,
+) -> MopsResult<(String, Summary)> {
+    let mut out = String::new();
+    let mut summary = Summary::default();
+
+    for sentence in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let tokens = morph
+            .analyze_text(sentence)
+            .collect::<MopsResult<Vec<_>>>()?;
+        summary.sentences += 1;
+        tokens.iter().for_each(|token| summary.count(token));
+
+        // Запись в `String` не возвращает ошибок.
+        match format {
+            Format::Tsv => {
+                for token in &tokens {
+                    let _ = writeln!(
+                        out,
+                        "{}\t{}\t{}",
+                        token.token().text(),
+                        token.normal_form(),
+                        token_tag(token)
+                    );
+                }
+                out.push('\n');
+            }
+            Format::Json => {
+                let tokens = tokens
+                    .iter()
+                    .map(|token| JsonToken {
+                        token: token.token().text(),
+                        lemma: token.normal_form(),
+                        tag: token_tag(token),
+                        method: token
+                            .parses()
+                            .and_then(|parses| parses.0.first())
+                            .map(|parse| method_name(&parse.method())),
+                        start: token.token().chars().start,
+                        end: token.token().chars().end,
+                    })
+                    .collect();
+                let sentence = JsonSentence {
+                    text: sentence,
+                    tokens,
+                };
+                let _ = writeln!(out, "{}", serde_json::to_string(&sentence)?);
+            }
+            Format::Conllu => {
+                let _ = writeln!(out, "# sent_id = {name}:{}", summary.sentences);
+                let _ = writeln!(out, "# text = {sentence}");
+                for (i, token) in tokens.iter().enumerate() {
+                    let (upos, xpos, feats) = match token.analysis() {
+                        TokenAnalysis::Parsed(parses) => match parses.0.first() {
+                            Some(parse) => {
+                                let tag = parse.tag();
+                                (ud::upos(&tag), tag_string(&tag), ud::feats(&tag))
+                            }
+                            None => ("X", "_".to_string(), "_".to_string()),
+                        },
+                        TokenAnalysis::Pseudo(tag) => {
+                            (ud::upos_pseudo(*tag), tag.to_string(), "_".to_string())
+                        }
+                    };
+                    let space_after = tokens.get(i + 1).is_some_and(|next| {
+                        next.token().bytes().start == token.token().bytes().end
+                    });
+                    let misc = if space_after { "SpaceAfter=No" } else { "_" };
+
+                    let _ = writeln!(
+                        out,
+                        "{}\t{}\t{}\t{upos}\t{xpos}\t{feats}\t_\t_\t_\t{misc}",
+                        i + 1,
+                        token.token().text(),
+                        token.normal_form(),
+                    );
+                }
+                out.push('\n');
+            }
+        }
+    }
+
+    Ok((out, summary))
+}
+
+/// Все файлы входа: файлы как есть, каталоги - рекурсивно, в порядке имен.
+/// Вместе с файлом возвращается его путь относительно входного каталога.
+fn collect_files(inputs: &[PathBuf]) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, PathBuf)>) -> io::Result<()> {
+        let mut entries = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for path in entries {
+            if path.is_dir() {
+                walk(root, &path, files)?;
+            } else {
+                let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+                files.push((path, relative));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            walk(input, input, &mut files)?;
+        } else {
+            let name = input.file_name().map(PathBuf::from).unwrap_or_default();
+            files.push((input.to_owned(), name));
+        }
+    }
+    Ok(files)
+}
+
+/// Разметка файлов `inputs` параллельно.
+///
+/// С `output` результат каждого файла пишется в одноименный файл каталога `output`
+/// с расширением формата, иначе результаты выводятся в stdout в порядке входа по мере готовности.
+pub fn run(
+    morph: &MorphAnalyzer,
+    inputs: &[PathBuf],
+    format: Format,
+    output: Option<&Path>,
+) -> io::Result<Summary> {
+    run_to(morph, inputs, format, output, io::stdout().lock())
+}
+
+/// Разметка файлов с выводом результатов без `output` в `out`.
+fn run_to(
+    morph: &MorphAnalyzer,
+    inputs: &[PathBuf],
+    format: Format,
+    output: Option<&Path>,
+    mut out: impl Write,
+) -> io::Result<Summary> {
+    let files = collect_files(inputs)?;
+    let total = files.len();
+    let done = AtomicUsize::new(0);
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        // Файлы берутся в порядке входа, так что вперед очереди уходят лишь несколько результатов.
+        scope.spawn(|| {
+            files.iter().enumerate().par_bridge().try_for_each_with(
+                sender,
+                |sender, (i, (path, relative))| {
+                    let result = fs::read_to_string(path)
+                        .map_err(|err| err.to_string())
+                        .and_then(|text| {
+                            annotate(morph, &text, format, &relative.to_string_lossy())
+                                .map_err(|err| err.to_string())
+                        })
+                        .and_then(|(annotated, summary)| {
+                            let Some(output) = output else {
+                                return Ok((Some(annotated), summary));
+                            };
+                            let out_path = output.join(relative).with_extension(format.extension());
+                            out_path
+                                .parent()
+                                .map_or(Ok(()), fs::create_dir_all)
+                                .and_then(|_| fs::write(&out_path, annotated))
+                                .map_err(|err| format!("{}: {err}", out_path.display()))?;
+                            Ok((None, summary))
+                        });
+
+                    let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    match &result {
+                        Ok((_, summary)) => info!(
+                            "[{done}/{total}] {}: {} tokens",
+                            path.display(),
+                            summary.tokens
+                        ),
+                        Err(err) => error!("[{done}/{total}] {}: {err}", path.display()),
+                    }
+                    // Получатель пропадает только при ошибке записи вывода: остальные файлы не нужны.
+                    sender.send((i, result))
+                },
+            )
+        });
+
+        let mut summary = Summary::default();
+        // Результаты, готовые раньше предыдущих по порядку входа.
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next) {
+                next += 1;
+                match result {
+                    Ok((annotated, file_summary)) => {
+                        if let Some(annotated) = annotated {
+                            out.write_all(annotated.as_bytes())?;
+                        }
+                        summary = summary.merge(Summary {
+                            files: 1,
+                            ..file_summary
+                        });
+                    }
+                    Err(_) => {
+                        summary.files += 1;
+                        summary.failed += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(summary)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::test_infrastructure::shared_analyzer;
+    use test_case::test_case;
+
+    #[test_case(Format::Tsv => "Стали\tсталь\tNOUN,inan,nomn,femn,plur\nножи\tнож\tNOUN,inan,nomn,masc,plur\n!\t!\tPNCT\n\n"; "tsv")]
+    #[test_case(Format::Conllu => "# sent_id = test:1\n# text = Стали ножи!\n\
+        1\tСтали\tсталь\tNOUN\tNOUN,inan,nomn,femn,plur\tAnimacy=Inan|Case=Nom|Gender=Fem|Number=Plur\t_\t_\t_\t_\n\
+        2\tножи\tнож\tNOUN\tNOUN,inan,nomn,masc,plur\tAnimacy=Inan|Case=Nom|Gender=Masc|Number=Plur\t_\t_\t_\tSpaceAfter=No\n\
+        3\t!\t!\tPUNCT\tPNCT\t_\t_\t_\t_\t_\n\n"; "conllu")]
+    #[test_case(Format::Json => "{\"text\":\"Стали ножи!\",\"tokens\":[\
+        {\"token\":\"Стали\",\"lemma\":\"сталь\",\"tag\":\"NOUN,inan,nomn,femn,plur\",\"method\":\"Dictionary\",\"start\":0,\"end\":5},\
+        {\"token\":\"ножи\",\"lemma\":\"нож\",\"tag\":\"NOUN,inan,nomn,masc,plur\",\"method\":\"Dictionary\",\"start\":6,\"end\":10},\
+        {\"token\":\"!\",\"lemma\":\"!\",\"tag\":\"PNCT\",\"start\":10,\"end\":11}]}\n"; "json")]
+    fn test_annotate(format: Format) -> String {
+        annotate(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            "Стали ножи!\n\n",
+            format,
+            "test",
+        )
+        .unwrap()
+        .0
+    }
+
+    #[test]
+    fn test_process_dir() {
+        let input = tempfile::tempdir().unwrap();
+        let output = tempfile::tempdir().unwrap();
+        fs::create_dir(input.path().join("nested")).unwrap();
+        fs::write(input.path().join("a.txt"), "Стали ножи.\nСуперножи!").unwrap();
+        fs::write(input.path().join("nested/b.txt"), "iPhone 42").unwrap();
+
+        let summary = run(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &[input.path().to_path_buf()],
+            Format::Tsv,
+            Some(output.path()),
+        )
+        .unwrap();
+
+        assert!(output.path().join("a.tsv").exists());
+        assert!(output.path().join("nested/b.tsv").exists());
+        assert_eq!(
+            (
+                summary.files,
+                summary.failed,
+                summary.sentences,
+                summary.tokens
+            ),
+            (2, 0, 3, 7)
+        );
+        assert_eq!(
+            summary.methods,
+            BTreeMap::from([("Dictionary", 2), ("KnownPrefix", 1)])
+        );
+        assert_eq!(
+            summary.pseudo,
+            BTreeMap::from([
+                ("LATN".to_string(), 1),
+                ("NUMB".to_string(), 1),
+                ("PNCT".to_string(), 2)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_process_stdout_order() {
+        let input = tempfile::tempdir().unwrap();
+        let words = [
+            "ножи",
+            "стали",
+            "файлы",
+            "ножи",
+            "стали",
+            "файлы",
+            "ножи",
+            "стали",
+        ];
+        for (i, word) in words.iter().enumerate() {
+            fs::write(input.path().join(format!("{i}.txt")), word).unwrap();
+        }
+
+        let mut out = Vec::new();
+        let summary = run_to(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &[input.path().to_path_buf()],
+            Format::Json,
+            None,
+            &mut out,
+        )
+        .unwrap();
+
+        assert_eq!(summary.files, words.len());
+        let texts = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["text"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, words);
+    }
+
+    /// Вывод, закрытый читателем, как `| head`.
+    struct Closed;
+
+    impl Write for Closed {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_process_stdout_closed() {
+        let input = tempfile::tempdir().unwrap();
+        for i in 0..32 {
+            fs::write(input.path().join(format!("{i}.txt")), "Стали ножи!").unwrap();
+        }
+
+        let err = run_to(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &[input.path().to_path_buf()],
+            Format::Tsv,
+            None,
+            Closed,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,281 @@
+use morph_rs::{
+    errors::MopsResult,
+    json::{Item, Outcome},
+    morph::grammemes::Grammem,
+    InflectWords, MorphAnalyzer, NormalizedWords, ParsedWords, Table,
+};
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Args)]
+/// Выбор разборов слова и формат вывода, общие для разовых запросов.
+pub struct Select {
+    /// Граммемы OpenCorpora через запятую: берутся только разборы со всеми граммемами.
+    #[clap(short, long, value_delimiter = ',')]
+    pub filter: Vec<Grammem>,
+    /// Номер разбора среди отфильтрованных.
+    #[clap(short, long)]
+    pub index: Option<usize>,
+    /// JSON вместо таблицы.
+    #[clap(long, default_value_t = false)]
+    pub json: bool,
+}
+
+impl Select {
+    /// Заданы ли фильтр или номер разбора.
+    fn narrowed(&self) -> bool {
+        !self.filter.is_empty() || self.index.is_some()
+    }
+
+    /// Отбор по фильтру, затем по номеру.
+    fn apply<T>(&self, items: &mut Vec<T>, tag: impl Fn(&T) -> Vec<Grammem>) {
+        items.retain(|item| {
+            let tag = tag(item);
+            self.filter.iter().all(|gram| tag.contains(gram))
+        });
+        if let Some(index) = self.index {
+            *items = std::mem::take(items)
+                .into_iter()
+                .nth(index)
+                .into_iter()
+                .collect();
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Разовый запрос к словарю.
+pub enum Query {
+    Parse,
+    Normalize,
+    /// Формы с граммемами, без граммем - начальная форма.
+    Inflect(Vec<Grammem>),
+    Declension,
+}
+
+#[derive(Debug, Clone)]
+/// Ответ на запрос.
+pub enum Answer {
+    Parsed(ParsedWords),
+    Normalized(NormalizedWords),
+    Inflected(Option<InflectWords>),
+    Declensions(Vec<InflectWords>),
+}
+
+impl Answer {
+    fn outcome(self) -> Outcome {
+        match self {
+            Answer::Parsed(parsed) => Outcome::parsed(parsed),
+            Answer::Normalized(normalized) => Outcome::normalized(normalized),
+            Answer::Inflected(inflect) => Outcome::inflected(inflect),
+            Answer::Declensions(declensions) => Outcome::declensions(declensions),
+        }
+    }
+
+    fn table(&self) -> String {
+        match self {
+            Answer::Parsed(parsed) => Table::from(parsed).to_string(),
+            Answer::Normalized(normalized) => Table::from(normalized).to_string(),
+            Answer::Inflected(Some(inflect)) => Table::from(inflect).to_string(),
+            Answer::Inflected(None) => "Нет форм с такими граммемами\n".to_string(),
+            Answer::Declensions(declensions) => {
+                let mut output = String::new();
+                for (i, forms) in declensions.iter().enumerate() {
+                    let _ = write!(output, "Parse {i}:\n{}", Table::from(forms));
+                }
+                output
+            }
+        }
+    }
+}
+
+/// Разборы слова, отобранные `select`.
+fn selected(morph: &MorphAnalyzer, word: &str, select: &Select) -> MopsResult<ParsedWords> {
+    let mut parsed = morph.parse(word)?;
+    select.apply(&mut parsed.0, |parse| parse.tag().to_vec());
+    Ok(parsed)
+}
+
+/// Выполнение запроса.
+///
+/// Для `parse` и `normalize` фильтр и номер отбирают результат, для `inflect` и `declension` -
+/// разборы, которые изменяются. Без фильтра и номера изменяются все разборы слова.
+pub fn run(
+    morph: &MorphAnalyzer,
+    query: &Query,
+    word: &str,
+    select: &Select,
+) -> MopsResult<Answer> {
+    let answer = match query {
+        Query::Parse => Answer::Parsed(selected(morph, word, select)?),
+        Query::Normalize => {
+            let mut normalized = morph.normalize(word)?;
+            select.apply(&mut normalized.0, |normal| normal.tag().to_vec());
+            Answer::Normalized(normalized)
+        }
+        Query::Inflect(grams) if !select.narrowed() => Answer::Inflected(match grams.is_empty() {
+            true => morph.inflect_inizio(word)?,
+            false => morph.inflect_forms(word, grams.clone())?,
+        }),
+        Query::Inflect(grams) => {
+            let mut forms = InflectWords::default();
+            for parse in selected(morph, word, select)?.0 {
+                let inflect = match grams.is_empty() {
+                    true => morph.inflect_parsed_inizio(parse)?,
+                    false => morph.inflect_parsed(parse, grams.clone())?,
+                };
+                for form in inflect.into_iter().flat_map(|inflect| inflect.0) {
+                    if !forms.0.contains(&form) {
+                        forms.0.push(form);
+                    }
+                }
+            }
+            Answer::Inflected((!forms.0.is_empty()).then_some(forms))
+        }
+        Query::Declension if !select.narrowed() => Answer::Declensions(morph.declension(word)?),
+        Query::Declension => {
+            let mut declensions = Vec::new();
+            for parse in selected(morph, word, select)?.0 {
+                if let Some(forms) = morph.declension_parsed(&parse)? {
+                    if !declensions
+                        .iter()
+                        .any(|known: &InflectWords| known.0 == forms.0)
+                    {
+                        declensions.push(forms);
+                    }
+                }
+            }
+            Answer::Declensions(declensions)
+        }
+    };
+    Ok(answer)
+}
+
+/// Ответ таблицей или JSON-строкой `{"word": .., "parses": [..]}`.
+///
+/// В JSON ошибка анализа становится `{"word": .., "error": ..}`, в таблице - возвращается.
+pub fn render(
+    morph: &MorphAnalyzer,
+    query: &Query,
+    word: &str,
+    select: &Select,
+) -> MopsResult<String> {
+    let answer = run(morph, query, word, select);
+    if select.json {
+        let item = Item::new(Some(word.to_string()), answer.map(Answer::outcome));
+        Ok(format!(
+            "{}\n",
+            serde_json::to_string(&item).unwrap_or_default()
+        ))
+    } else {
+        answer.map(|answer| answer.table())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::test_infrastructure::shared_analyzer;
+    use morph_rs::morph::grammemes::*;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn words(query: Query, word: &str, filter: &[Grammem], index: Option<usize>) -> Vec<String> {
+        let select = Select {
+            filter: filter.to_vec(),
+            index,
+            json: true,
+        };
+        let output = render(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &query,
+            word,
+            &select,
+        )
+        .unwrap();
+        let item: Value = serde_json::from_str(&output).unwrap();
+
+        let forms = match item.get("declensions") {
+            Some(declensions) => declensions
+                .as_array()
+                .unwrap()
+                .iter()
+                .flat_map(|forms| forms.as_array().unwrap().to_owned())
+                .collect(),
+            None => item
+                .get("parses")
+                .or_else(|| item.get("forms"))
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .to_owned(),
+        };
+        forms
+            .iter()
+            .map(|form| format!("{} {}", form["word"], form["tag"]).replace('"', ""))
+            .collect()
+    }
+
+    #[test_case(Query::Parse, &[Grammem::ParteSpeech(ParteSpeech::Verb)], None => vec!["стали VERB,perf,indc,plur,intr,past"]; "parse_filter")]
+    #[test_case(Query::Parse, &[], Some(1) => vec!["стали NOUN,inan,gent,femn,sing"]; "parse_index")]
+    #[test_case(Query::Parse, &[], Some(10) => Vec::<String>::new(); "parse_index_out")]
+    #[test_case(Query::Normalize, &[Grammem::ParteSpeech(ParteSpeech::Noun)], None => vec!["сталь NOUN,inan,nomn,femn,sing"]; "normalize_filter")]
+    #[test_case(Query::Normalize, &[Grammem::ParteSpeech(ParteSpeech::Infinitive)], None => vec!["стать INFN,perf,intr"]; "normalize_infinitive")]
+    #[test_case(Query::Inflect(vec![Grammem::Gender(Gender::Feminine)]), &[Grammem::ParteSpeech(ParteSpeech::Verb)], None => vec!["стала VERB,perf,femn,indc,sing,intr,past"]; "inflect_filter")]
+    #[test_case(Query::Inflect(vec![Grammem::Case(Case::Ablativus), Grammem::Number(Number::Plural)]), &[], None => vec!["сталями NOUN,inan,ablt,femn,plur"]; "inflect_grams")]
+    fn test_query(query: Query, filter: &[Grammem], index: Option<usize>) -> Vec<String> {
+        words(query, "стали", filter, index)
+    }
+
+    #[test]
+    fn test_declension_filter() {
+        let all = words(Query::Declension, "стали", &[], None);
+        let verb = words(
+            Query::Declension,
+            "стали",
+            &[Grammem::ParteSpeech(ParteSpeech::Verb)],
+            None,
+        );
+
+        assert!(!verb.is_empty() && verb.len() < all.len());
+        assert!(verb.iter().all(|form| all.contains(form)));
+        assert!(verb.iter().all(|form| !form.contains("NOUN")));
+    }
+
+    #[test]
+    fn test_render() {
+        let select = Select::default();
+        let table = render(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &Query::Parse,
+            "ножи",
+            &select,
+        )
+        .unwrap();
+        assert!(table.starts_with("+---+"));
+        assert!(table.contains("| ножи | нож         | NOUN,inan,nomn,masc,plur |"));
+
+        let json = Select {
+            json: true,
+            ..Select::default()
+        };
+        let error: Value = serde_json::from_str(
+            &render(
+                shared_analyzer("data/test/tagger_dict.xml"),
+                &Query::Declension,
+                "бутявка",
+                &json,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(error["word"], json!("бутявка"));
+        assert!(error.get("error").is_some());
+        assert!(render(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &Query::Declension,
+            "бутявка",
+            &select
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,210 @@
+use morph_rs::{
+    errors::{MopsErr, ParseErr},
+    morph::grammemes::Grammem,
+    MorphAnalyzer, Table,
+};
+use rustyline::{error::ReadlineError, DefaultEditor};
+use std::{fmt::Write as _, path::Path, str::FromStr};
+use thiserror::Error;
+
+/// Приглашение ко вводу команды.
+const PROMPT: &str = "morph> ";
+
+const HELP: &str = "\
+Команды (слова приводятся к нижнему регистру):
+  parse, p <слово>                       разборы слова
+  normalize, n <слово>                   нормальные формы
+  inflect, i <слово> [граммемы...]       формы с граммемами OpenCorpora (plur gent или plur,gent),
+                                         без граммем - начальная форма
+  declension, d <слово>                  все формы для каждого разбора
+  known, k <слово>                       есть ли слово в словаре
+  lemma, l <слово>                       строки лемм OpenCorpora словарных разборов
+  vanga, v <слово>                       предсказание разбора по приставкам
+  help, h                                эта справка
+  quit, q                                выход (или Ctrl+D)
+";
+
+#[derive(Debug, Error)]
+pub enum ReplErr {
+    #[error("Неизвестная команда `{0}`, список команд: help")]
+    UnknownCommand(String),
+    #[error("Команде `{0}` нужно слово")]
+    MissingWord(&'static str),
+    #[error("Граммема: {0}")]
+    Grammem(#[source] ParseErr),
+    #[error(transparent)]
+    Analyzer(#[from] MopsErr),
+    #[error(transparent)]
+    Parse(#[from] ParseErr),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Команда REPL.
+pub enum Command {
+    Parse(String),
+    Normalize(String),
+    Inflect(String, Vec<Grammem>),
+    Declension(String),
+    Known(String),
+    Lemma(String),
+    Vanga(String),
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = ReplErr;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mut args = line.split_whitespace();
+        let name = args.next().unwrap_or("help");
+        let word = args.next().map(str::to_lowercase);
+        let word = |command: &'static str| word.clone().ok_or(ReplErr::MissingWord(command));
+
+        let command = match name {
+            "parse" | "p" => Command::Parse(word("parse")?),
+            "normalize" | "n" => Command::Normalize(word("normalize")?),
+            "inflect" | "i" => {
+                let word = word("inflect")?;
+                let grammemes = args
+                    .flat_map(|arg| arg.split(','))
+                    .filter(|name| !name.is_empty())
+                    .map(Grammem::from_str)
+                    .collect::<Result<_, _>>()
+                    .map_err(ReplErr::Grammem)?;
+                Command::Inflect(word, grammemes)
+            }
+            "declension" | "d" => Command::Declension(word("declension")?),
+            "known" | "k" => Command::Known(word("known")?),
+            "lemma" | "l" => Command::Lemma(word("lemma")?),
+            "vanga" | "v" => Command::Vanga(word("vanga")?),
+            "help" | "h" | "?" => Command::Help,
+            "quit" | "q" | "exit" => Command::Quit,
+            name => return Err(ReplErr::UnknownCommand(name.to_string())),
+        };
+        Ok(command)
+    }
+}
+
+/// Выполнение команды: таблица результата или сообщение.
+pub fn execute(morph: &MorphAnalyzer, command: &Command) -> Result<String, ReplErr> {
+    let output = match command {
+        Command::Parse(word) => Table::from(&morph.parse(word)?).to_string(),
+        Command::Normalize(word) => Table::from(&morph.normalize(word)?).to_string(),
+        Command::Inflect(word, grammemes) => {
+            let inflect = match grammemes.is_empty() {
+                true => morph.inflect_inizio(word)?,
+                false => morph.inflect_forms(word, grammemes.clone())?,
+            };
+            match inflect {
+                Some(inflect) => Table::from(&inflect).to_string(),
+                None => format!("Нет форм `{word}` с такими граммемами\n"),
+            }
+        }
+        Command::Declension(word) => {
+            let mut output = String::new();
+            for (i, forms) in morph.declension(word)?.iter().enumerate() {
+                let _ = write!(output, "Parse {i}:\n{}", Table::from(forms));
+            }
+            output
+        }
+        Command::Known(word) => match morph.is_known(word) {
+            true => format!("`{word}` есть в словаре\n"),
+            false => format!("`{word}` нет в словаре\n"),
+        },
+        Command::Lemma(word) => Table::from(morph.lemma_rows(word)?.as_slice()).to_string(),
+        Command::Vanga(word) => match morph.vangovanie(word)? {
+            Some(vangas) => Table::from(vangas.as_slice()).to_string(),
+            None => format!("Вангование не нашло разборов `{word}`\n"),
+        },
+        Command::Help => HELP.to_string(),
+        Command::Quit => String::new(),
+    };
+    Ok(output)
+}
+
+/// Интерактивный цикл с историей команд.
+///
+/// История читается из `history` при запуске и сохраняется туда при выходе.
+pub fn run(morph: &MorphAnalyzer, history: Option<&Path>) -> rustyline::Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    if let Some(history) = history {
+        // Истории может еще не быть при первом запуске.
+        let _ = editor.load_history(history);
+    }
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+
+        match line.parse().and_then(|command| match command {
+            Command::Quit => Ok(None),
+            command => execute(morph, &command).map(Some),
+        }) {
+            Ok(Some(output)) => print!("{output}"),
+            Ok(None) => break,
+            Err(error) => eprintln!("{error}"),
+        }
+    }
+
+    if let Some(history) = history {
+        editor.save_history(history)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::test_infrastructure::shared_analyzer;
+    use morph_rs::morph::grammemes::*;
+    use test_case::test_case;
+
+    #[test_case("p стали" => Command::Parse("стали".to_string()))]
+    #[test_case("normalize  СТАЛИ" => Command::Normalize("стали".to_string()); "uppercase")]
+    #[test_case("inflect ножи plur,gent" => Command::Inflect("ножи".to_string(), vec![Grammem::Number(Number::Plural), Grammem::Case(Case::Genetivus)]))]
+    #[test_case("i ножи plur gent" => Command::Inflect("ножи".to_string(), vec![Grammem::Number(Number::Plural), Grammem::Case(Case::Genetivus)]))]
+    #[test_case("i ножи" => Command::Inflect("ножи".to_string(), vec![]))]
+    #[test_case("d ножи" => Command::Declension("ножи".to_string()))]
+    #[test_case("known ножи" => Command::Known("ножи".to_string()))]
+    #[test_case("l ножи" => Command::Lemma("ножи".to_string()))]
+    #[test_case("vanga суперножи" => Command::Vanga("суперножи".to_string()))]
+    #[test_case("?" => Command::Help)]
+    #[test_case("exit" => Command::Quit)]
+    fn test_command(line: &str) -> Command {
+        line.parse().unwrap()
+    }
+
+    #[test_case("find ножи" => "Неизвестная команда `find`, список команд: help"; "unknown")]
+    #[test_case("parse" => "Команде `parse` нужно слово"; "missing_word")]
+    #[test_case("i ножи plur,xxx" => "Граммема: Unknown grammem 'xxx'"; "grammem")]
+    fn test_command_err(line: &str) -> String {
+        line.parse::<Command>().unwrap_err().to_string()
+    }
+
+    #[test_case("i ножи plur,gent" => "\
++---+-------+-------------+--------------------------+------------+
+| # | form  | normal_form | tag                      | method     |
++---+-------+-------------+--------------------------+------------+
+| 0 | ножей | нож         | NOUN,inan,gent,masc,plur | Dictionary |
++---+-------+-------------+--------------------------+------------+
+")]
+    #[test_case("k ножи" => "`ножи` есть в словаре\n")]
+    #[test_case("k бутявка" => "`бутявка` нет в словаре\n")]
+    fn test_execute(line: &str) -> String {
+        execute(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            &line.parse().unwrap(),
+        )
+        .unwrap()
+    }
+}
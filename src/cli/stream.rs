@@ -0,0 +1,174 @@
+use clap::ValueEnum;
+use morph_rs::{
+    errors::MopsResult,
+    json::{Item, Outcome},
+    morph::grammemes::Grammem,
+    MorphAnalyzer,
+};
+use std::io::{self, BufRead, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// Операция над каждой строкой входа.
+pub enum Operation {
+    /// Разбор слова.
+    Parse,
+    /// Нормальные формы слова.
+    Normalize,
+    /// Формы слова с граммемами `--grams`, без граммем - начальная форма.
+    Inflect,
+    /// Все формы слова.
+    Declension,
+    /// Леммы всех слов строки-текста.
+    Lemmatize,
+}
+
+/// Проверка тега на наличие всех граммем фильтра.
+fn has_grams(tag: &[Grammem], grams: &[Grammem]) -> bool {
+    grams.iter().all(|gram| tag.contains(gram))
+}
+
+/// Результат операции для одной строки.
+///
+/// Для `parse`, `normalize` и `declension` граммемы `grams` фильтруют результат,
+/// для `inflect` задают нужную форму, для `lemmatize` не используются.
+pub fn process_line(
+    morph: &MorphAnalyzer,
+    operation: Operation,
+    grams: &[Grammem],
+    line: &str,
+) -> Item {
+    let word = line.trim();
+    let outcome: MopsResult<Outcome> = match operation {
+        Operation::Parse => morph.parse(word).map(|mut parsed| {
+            parsed.0.retain(|parse| has_grams(&parse.tag(), grams));
+            Outcome::parsed(parsed)
+        }),
+        Operation::Normalize => morph.normalize(word).map(|mut normalized| {
+            normalized
+                .0
+                .retain(|normal| has_grams(&normal.tag(), grams));
+            Outcome::normalized(normalized)
+        }),
+        Operation::Inflect if grams.is_empty() => {
+            morph.inflect_inizio(word).map(Outcome::inflected)
+        }
+        Operation::Inflect => morph
+            .inflect_forms(word, grams.to_vec())
+            .map(Outcome::inflected),
+        Operation::Declension => morph.declension(word).map(|mut declensions| {
+            for forms in declensions.iter_mut() {
+                forms.0.retain(|form| has_grams(&form.tag(), grams));
+            }
+            declensions.retain(|forms| !forms.0.is_empty());
+            Outcome::declensions(declensions)
+        }),
+        Operation::Lemmatize => morph.lemmatize_text(line).map(Outcome::Lemmas),
+    };
+
+    // Для `lemmatize` строка - это текст, а не слово.
+    let word = (operation != Operation::Lemmatize).then(|| word.to_string());
+    Item::new(word, outcome)
+}
+
+/// Чтение строк из `input` и запись одной JSON-строки на каждую строку входа.
+///
+/// Вывод сбрасывается после каждой строки, чтобы процесс можно было вести построчно через pipe.
+pub fn run(
+    morph: &MorphAnalyzer,
+    operation: Operation,
+    grams: &[Grammem],
+    input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    for line in input.lines() {
+        let item = process_line(morph, operation, grams, &line?);
+        serde_json::to_writer(&mut output, &item)?;
+        output.write_all(b"\n")?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::test_infrastructure::shared_analyzer;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    fn stream(operation: Operation, grams: &[&str], input: &str) -> Vec<Value> {
+        let grams = grams
+            .iter()
+            .map(|gram| gram.parse().unwrap())
+            .collect::<Vec<_>>();
+        let mut output = Vec::new();
+        run(
+            shared_analyzer("data/test/tagger_dict.xml"),
+            operation,
+            &grams,
+            input.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_stream_lines() {
+        let output = stream(Operation::Parse, &["gent"], "стали\n  ножи \nкваквакря\n");
+        assert_eq!(output.len(), 3);
+        assert_eq!(
+            output[0],
+            json!({ "word": "стали", "parses": [{
+                "word": "стали", "normal_form": "сталь", "tag": "NOUN,inan,gent,femn,sing", "method": "Dictionary"
+            }] })
+        );
+        assert_eq!(output[1], json!({ "word": "ножи", "parses": [] }));
+        assert_eq!(output[2], json!({ "word": "кваквакря", "parses": [] }));
+    }
+
+    #[test_case(Operation::Inflect, &["plur", "ablt"], "стали" => json!(["сталями"]))]
+    #[test_case(Operation::Inflect, &[], "ножами" => json!(["нож"]))]
+    #[test_case(Operation::Normalize, &["INFN"], "стали" => json!(["стать"]))]
+    #[test_case(Operation::Declension, &["ablt"], "ножи" => json!([["ножами", "ножом"]]))]
+    #[test_case(Operation::Lemmatize, &[], "стали ножи." => json!(["сталь", "нож"]))]
+    fn test_stream_operations(operation: Operation, grams: &[&str], line: &str) -> Value {
+        let item = stream(operation, grams, line).remove(0);
+        let words = |forms: &Value| {
+            forms
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|form| form["word"].clone())
+                .collect::<Value>()
+        };
+
+        match operation {
+            Operation::Parse | Operation::Normalize => words(&item["parses"]),
+            Operation::Inflect => words(&item["forms"]),
+            Operation::Declension => item["declensions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(words)
+                .collect(),
+            Operation::Lemmatize => item["lemmas"].clone(),
+        }
+    }
+
+    #[test]
+    fn test_stream_error() {
+        let output = stream(Operation::Inflect, &["plur"], "кваквакря");
+        assert_eq!(
+            output,
+            [
+                json!({ "word": "кваквакря", "error": "Parse err -> Word 'кваквакря' is not in the dictionary" })
+            ]
+        );
+    }
+}
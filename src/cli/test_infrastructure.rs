@@ -0,0 +1,39 @@
+use morph_rs::{Language, MorphAnalyzer};
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+use tempfile::{tempdir, TempDir};
+
+/// Анализатор по словарю из файла, собранный во временной директории.
+pub(crate) fn analyzer<P: AsRef<Path>>(file_path: P) -> (TempDir, MorphAnalyzer) {
+    let file_path = file_path.as_ref();
+    let tmp_dir = tempdir().unwrap();
+    let dict = MorphAnalyzer::create(file_path, tmp_dir.path(), Language::Russian)
+        .unwrap_or_else(|err| panic!("{}: {err}", file_path.display()));
+    let anal = MorphAnalyzer::init(dict, tmp_dir.path()).unwrap();
+    (tmp_dir, anal)
+}
+
+/// Анализатор по тексту словаря `OpenCorpora`.
+pub(crate) fn xml_analyzer(xml: &str) -> (TempDir, MorphAnalyzer) {
+    let xml_dir = tempdir().unwrap();
+    let file_path = xml_dir.path().join("dict.xml");
+    std::fs::write(&file_path, xml).unwrap();
+    analyzer(file_path)
+}
+
+/// Общий для всех тестов анализатор по словарю из файла: собирается один раз на словарь.
+pub(crate) fn shared_analyzer(file_path: &'static str) -> &'static MorphAnalyzer {
+    static ANALYZERS: OnceLock<Mutex<HashMap<&str, &(TempDir, MorphAnalyzer)>>> = OnceLock::new();
+
+    let mut analyzers = ANALYZERS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    &analyzers
+        .entry(file_path)
+        .or_insert_with(|| Box::leak(Box::new(analyzer(file_path))))
+        .1
+}
@@ -1,4 +1,7 @@
-use crate::analyzer::{Parse, Tag};
+use crate::{
+    analyzer::{Parse, Tag},
+    morph::grammemes::{Case, Gender, Grammem},
+};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -29,6 +32,9 @@ pub enum MopsErr {
 
     #[error("Parse err -> {0}")]
     Parse(#[from] ParseErr),
+
+    #[error("User dictionary err -> {0}")]
+    UserDictionary(#[from] UserDictErr),
 }
 
 #[derive(Debug, derive_more::Display)]
@@ -85,7 +91,34 @@ pub enum DictionaryErr {
     Stem(String),
 }
 
-#[derive(Debug, derive_more::Display)]
+#[derive(Debug, Error)]
+/// Ошибки чтения пользовательских словарей.
+pub enum UserDictErr {
+    #[error("{file}:{line}: expected 'lemma<TAB>form<TAB>grammemes' or 'lemma<TAB>like word'")]
+    Format { file: PathBuf, line: usize },
+
+    #[error("{file}:{line}: {error}")]
+    Grammem {
+        file: PathBuf,
+        line: usize,
+        error: ParseErr,
+    },
+
+    #[error("Lemma '{0}' has no form equal to it")]
+    NoNormalForm(String),
+
+    #[error("Model word '{0}' is not a normal form in the dictionary")]
+    UnknownModel(String),
+
+    #[error("Lemma '{lemma}' doesn't end like model word '{model}' ('-{ending}')")]
+    ModelMismatch {
+        lemma: String,
+        model: String,
+        ending: String,
+    },
+}
+
+#[derive(Debug, Clone, derive_more::Display)]
 pub enum Bound {
     #[display(fmt = "word_parses")]
     WordParses,
@@ -99,7 +132,7 @@ pub enum Bound {
     LemmasRow,
 }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 /// Ошибки парсинга слова
 pub enum ParseErr {
     #[error("Index of search {idx} more than {vec} len")]
@@ -117,8 +150,9 @@ pub enum ParseErr {
     #[error("Analyzer lost parse {0:?}")]
     LostParse(Tag),
 
-    #[error("To be continued...")]
-    FutureRelease,
+    /// Слова нет в словаре: изменение форм и склонение работают только со словарными словами.
+    #[error("Word '{0}' is not in the dictionary")]
+    UnknownWord(String),
 
     #[error("No parte of speech in {0}")]
     NoPos(String),
@@ -128,16 +162,100 @@ pub enum ParseErr {
 
     #[error("Binary search not found tag: {0:?}")]
     BinaryTag(Tag),
+
+    #[error("Fio err -> {0}")]
+    Fio(FioErr),
+
+    #[error("Numeral err -> {0}")]
+    Numeral(NumeralErr),
+
+    #[error("Unknown grammem '{0}'")]
+    UnknownGrammem(String),
+
+    #[error("Template err -> {0}")]
+    Template(TemplateErr),
 }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum DeclensionErr {
     #[error("Word is empty")]
     EmptyWord,
 
-    #[error("Binary search not found char {0} in alphabet")]
-    BinaryChar(char),
-
     #[error("Index of search {idx} more than {vec} len")]
     OutOfBound { idx: u64, vec: Bound },
 }
+
+#[derive(Debug, Clone, Error)]
+/// Ошибки разбора и склонения ФИО.
+pub enum FioErr {
+    #[error("Full name is empty")]
+    EmptyFio,
+
+    #[error("Full name '{0}' has more than three parts")]
+    TooManyParts(String),
+
+    #[error("Couldn't recognize surname, name and patronymic in '{0}'")]
+    UnknownStructure(String),
+
+    #[error("Case {0} is not supported for full name declension")]
+    UnsupportedCase(Case),
+
+    #[error("Gender {0} is not supported for patronymic")]
+    UnsupportedGender(Gender),
+
+    #[error("Couldn't make patronymic from '{0}'")]
+    NotAName(String),
+}
+
+#[derive(Debug, Clone, Error)]
+/// Ошибки построения и разбора числительных.
+pub enum NumeralErr {
+    #[error("Number {0} is too large to spell")]
+    TooLarge(u64),
+
+    #[error("Number {0} has no collective numeral")]
+    NoCollective(u64),
+
+    #[error("'{0}' is not a number")]
+    NotANumber(String),
+
+    #[error("'{0}' is not a numeral")]
+    NotANumeral(String),
+
+    #[error("Wrong order of numeral words in '{0}'")]
+    WrongOrder(String),
+
+    #[error("Numeral '{0}' not found in dictionary")]
+    NotInDictionary(String),
+
+    #[error("Numeral '{lemma}' has no form {grammemes:?}")]
+    NoForm {
+        lemma: String,
+        grammemes: Vec<Grammem>,
+    },
+}
+
+#[derive(Debug, Clone, Error)]
+/// Ошибки разбора и заполнения шаблонов сообщений.
+pub enum TemplateErr {
+    #[error("Placeholder at {0} is not closed")]
+    Unclosed(usize),
+
+    #[error("Unexpected '}}' at {0}")]
+    Unopened(usize),
+
+    #[error("Placeholder at {0} is empty")]
+    EmptyPlaceholder(usize),
+
+    #[error("Argument '{0}' is not provided")]
+    UnknownArgument(String),
+
+    #[error("Argument '{0}' has neither gender nor number to agree with")]
+    NoAgreement(String),
+
+    #[error("Word '{word}' has no form {grammemes:?}")]
+    NoForm {
+        word: String,
+        grammemes: Vec<Grammem>,
+    },
+}
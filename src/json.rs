@@ -0,0 +1,124 @@
+//! JSON-представление результатов анализа, общее для CLI и HTTP-сервера.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analyzer::Tag, errors::MopsResult, morph::grammemes::Grammem, InflectWord, InflectWords,
+    Method, NormalizedWord, NormalizedWords, ParsedWord, ParsedWords,
+};
+
+/// Граммемы тега в обозначениях `OpenCorpora` через запятую: "NOUN,inan,femn,sing,gent".
+pub fn tag_string(tag: &Tag) -> String {
+    tag.iter()
+        .map(Grammem::opencorpora)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Форма слова: разбор, нормальная форма или измененная форма.
+pub struct WordForm {
+    pub word: String,
+    pub normal_form: String,
+    /// Граммемы `OpenCorpora` через запятую: "NOUN,inan,femn,sing,gent".
+    pub tag: String,
+    pub method: String,
+}
+
+impl WordForm {
+    fn new(word: String, normal_form: String, tag: &Tag, method: &Method) -> Self {
+        Self {
+            word,
+            normal_form,
+            tag: tag_string(tag),
+            method: method.to_string(),
+        }
+    }
+}
+
+impl From<ParsedWord> for WordForm {
+    fn from(word: ParsedWord) -> Self {
+        Self::new(word.word, word.normal_form, &word.tags, &word.method)
+    }
+}
+
+impl From<NormalizedWord> for WordForm {
+    fn from(word: NormalizedWord) -> Self {
+        Self::new(
+            word.normal_word.to_owned(),
+            word.normal_word,
+            &word.tags,
+            &word.method,
+        )
+    }
+}
+
+impl From<InflectWord> for WordForm {
+    fn from(word: InflectWord) -> Self {
+        Self::new(
+            word.inflect_form,
+            word.normal_form,
+            &word.tags,
+            &word.method,
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+/// Результат для одного слова или текста: данные или текст ошибки.
+pub enum Outcome {
+    Parses(Vec<WordForm>),
+    Forms(Vec<WordForm>),
+    Declensions(Vec<Vec<WordForm>>),
+    Lemmas(Vec<String>),
+    Error(String),
+}
+
+impl Outcome {
+    pub fn parsed(words: ParsedWords) -> Self {
+        Self::Parses(words.0.into_iter().map(WordForm::from).collect())
+    }
+
+    pub fn normalized(words: NormalizedWords) -> Self {
+        Self::Parses(words.0.into_iter().map(WordForm::from).collect())
+    }
+
+    /// Формы слова, отсутствие форм - пустой список.
+    pub fn inflected(words: Option<InflectWords>) -> Self {
+        Self::Forms(
+            words
+                .into_iter()
+                .flat_map(|words| words.0)
+                .map(WordForm::from)
+                .collect(),
+        )
+    }
+
+    pub fn declensions(declensions: Vec<InflectWords>) -> Self {
+        Self::Declensions(
+            declensions
+                .into_iter()
+                .map(|words| words.0.into_iter().map(WordForm::from).collect())
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+/// Результат для одного слова или текста: `{"word": "стали", "parses": [..]}`.
+pub struct Item {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub word: Option<String>,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+impl Item {
+    /// Ошибка анализа становится результатом `{"error": ".."}`.
+    pub fn new(word: Option<String>, result: MopsResult<Outcome>) -> Self {
+        Self {
+            word,
+            outcome: result.unwrap_or_else(|err| Outcome::Error(err.to_string())),
+        }
+    }
+}
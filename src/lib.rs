@@ -4,28 +4,50 @@ pub mod errors;
 /// Грамматические структуры русского языка, используемые анализатором.
 #[macro_use]
 pub mod morph;
+/// JSON-представление результатов для CLI и сервера.
+pub mod json;
 /// Словарь Opencorpora.
 pub(crate) mod opencorpora;
 /// Инфраструктура для юнит-тестов + экспериментальное тестирование.
 pub(crate) mod test_infrastructure;
+/// Разбиение текста на токены со смещениями.
+pub mod tokenizer;
+
+/// C-интерфейс для встраивания в сервисы не на Rust.
+#[cfg(feature = "capi")]
+pub mod capi;
+/// Python-модуль с API `pymorphy2`.
+#[cfg(feature = "python")]
+pub mod python;
+/// HTTP-сервер с JSON API.
+#[cfg(feature = "server")]
+pub mod server;
 
 use allocative::Allocative;
-use analyzer::{InflectWords, Lemmas, LemmasRows, ParseTable, Tag, Tags};
+use analyzer::{
+    AnalyzerCache, LemmaForms, Lemmas, LemmasRows, ParseTable, PrefixAlternations, Tag, Tags,
+};
 use errors::{MopsErr, MopsResult};
 use fst::Map;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 use tracing::info;
 
 use crate::{
     analyzer::{Dictionary, Vanga},
-    morph::grammemes::Grammem,
+    morph::grammemes::{Case, Gender, Grammem},
     opencorpora::DictionaryOpenCorpora,
 };
-pub use analyzer::{NormalizedWords, ParsedWords, SMALLLEMMA, SMALLTAG, SMALLVANGA};
+pub use analyzer::{
+    AnalyzedToken, CacheConfig, CacheReport, CacheStats, Change, DictionaryDiff, DictionaryStats,
+    Fio, FioKind, FioPart, InflectWords, Lemma, LemmaRow, MemoryStats, Meta, NormalizedWords,
+    Numeral, NumeralKind, ParseIter, ParsedWordRef, ParsedWords, PseudoTag, SetDiff, Table, Tagger,
+    Template, TemplateArg, TextAnalysis, TokenAnalysis, VangovanieRes, WordDiff, MAX_NUMERAL,
+    SMALLLEMMA, SMALLTAG, SMALLVANGA, TAGGER_FILE,
+};
 
 #[rustfmt::skip]
-#[derive(Debug, Clone, Default, clap::Parser, clap::ValueEnum, Serialize, Deserialize, Allocative)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Parser, clap::ValueEnum, Serialize, Deserialize, Allocative)]
 /// Имеющиеся словарные языки
 pub enum Language {
     #[default]
@@ -35,6 +57,8 @@ pub enum Language {
 #[derive(Debug, Allocative)]
 /// Морфологический анализатор, образованный из словаря.
 pub struct MorphAnalyzer {
+    /// Версия и ревизия словаря `OpenCorpora`, язык.
+    pub meta: Meta,
     #[allocative(skip)]
     pub fst: Map<Vec<u8>>,
     #[allocative(skip)]
@@ -45,6 +69,13 @@ pub struct MorphAnalyzer {
     pub lemmas: Lemmas,
     pub paradigms: Vec<Vanga>,
     pub lemmas_rows: LemmasRows,
+    pub lemma_forms: LemmaForms,
+    pub alternations: PrefixAlternations,
+    #[allocative(skip)]
+    pub(crate) cache: Option<AnalyzerCache>,
+    /// Пользовательские словари, собранные в один словарь поверх основного.
+    #[allocative(skip)]
+    pub(crate) user: Option<Box<MorphAnalyzer>>,
 }
 
 #[derive(
@@ -220,26 +251,137 @@ impl MorphAnalyzer {
         Self::init(dictionary, path)
     }
 
+    /// Включение кэша результатов `parse`, `normalize` и `inflect_*` для частых слов.
+    ///
+    /// Кэш потокобезопасен и ограничен по размеру: давно не использованные слова вытесняются.
+    /// Кэш от 2048 слов делится на шарды по хешу слова, так что подходит и для `*_batch` методов.
+    /// Ошибки не кэшируются. Прежний кэш со статистикой сбрасывается.
+    pub fn with_cache(mut self, config: CacheConfig) -> Self {
+        self.cache = Some(AnalyzerCache::new(config));
+        self
+    }
+
+    /// Подключение пользовательских словарей поверх основного: названия продуктов, сленг, термины.
+    ///
+    /// Словари с расширением `.xml` - подмножество формата `OpenCorpora`: `<lemmata>` и,
+    /// при необходимости, `<links>`. Остальные - TSV со строками "лемма<TAB>форма<TAB>граммемы"
+    /// или "лемма<TAB>like слово[<TAB>граммемы]": формы леммы образуются по формам слова-образца
+    /// из основного словаря, граммемы выбирают разбор образца.
+    ///
+    /// Слова ищутся сначала в пользовательских словарях: их разборы, нормальные формы и формы
+    /// идут перед результатами основного словаря. Прежние пользовательские словари заменяются,
+    /// кэш очищается.
+    pub fn with_user_dictionaries<P: AsRef<Path>>(mut self, paths: &[P]) -> MopsResult<Self> {
+        let paths = paths
+            .iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+
+        self.user = None;
+        self.user = self.user_analyzer(&paths)?.map(Box::new);
+        self.clear_cache();
+        Ok(self)
+    }
+
+    /// Содержимое словаря: количество словоформ, лемм, тегов, парадигм,
+    /// память по компонентам и распределения разборов по тегам и частям речи.
+    pub fn stats(&self) -> DictionaryStats {
+        self.dictionary_stats()
+    }
+
+    /// Сравнение словаря (старого) с `new`: леммы, теги, связи между леммами
+    /// и слова, у которых изменился вывод `parse` или `normalize`.
+    ///
+    /// Без списка `words` сравниваются все словоформы обоих словарей.
+    pub fn diff(&self, new: &MorphAnalyzer, words: Option<&[String]>) -> DictionaryDiff {
+        self.diff_dictionary(new, words)
+    }
+
+    /// Статистика попаданий в кэш, если он включен.
+    pub fn cache_stats(&self) -> Option<CacheReport> {
+        self.cache.as_ref().map(AnalyzerCache::report)
+    }
+
+    /// Очистка кэша с сохранением его размеров, статистика обнуляется.
+    pub fn clear_cache(&mut self) {
+        if let Some(cache) = &mut self.cache {
+            *cache = AnalyzerCache::new(cache.config());
+        }
+    }
+
     /// Парсинг слова. Получение всех возможных результатов.
     ///
     /// Все варианты парсинга возвращаются в отсортированном порядке,
     /// гарантируя единообразие выдачи между запусками.
+    ///
+    /// Неизвестное слово, разбор которого не удалось предсказать, дает пустой результат,
+    /// см. `ParsedWords::is_unknown`.
     pub fn parse(&self, word: &str) -> MopsResult<ParsedWords> {
-        self.parse_word(word).map_err(MopsErr::Parse)
+        self.parse_cached(word).map_err(MopsErr::Parse)
+    }
+
+    /// Ленивый разбор слова без аллокаций для словарных слов.
+    ///
+    /// Разборы заимствуют теги и нормальные формы из словаря,
+    /// `ParsedWordRef::into_owned` дает `ParsedWord` по требованию.
+    /// В отличие от `parse`, разборы не сортируются.
+    pub fn parse_iter<'a>(&'a self, word: &'a str) -> MopsResult<ParseIter<'a>> {
+        self.parse_view(word).map_err(MopsErr::Parse)
+    }
+
+    /// Разбор слов пачкой, см. `parse`.
+    ///
+    /// Повторяющиеся слова разбираются один раз, уникальные - параллельно.
+    /// Результаты выдаются в порядке входных слов.
+    pub fn parse_batch<I>(&self, words: I) -> Vec<MopsResult<ParsedWords>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.batch(words, Self::parse_cached)
+    }
+
+    /// Нормализация слов пачкой, см. `normalize` и `parse_batch`.
+    pub fn normalize_batch<I>(&self, words: I) -> Vec<MopsResult<NormalizedWords>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.batch(words, Self::normalize_cached)
+    }
+
+    /// Леммы слов пачкой: нормальная форма первого разбора в нижнем регистре.
+    ///
+    /// Слово без разбора остается как есть, см. `parse_batch`.
+    pub fn lemmatize_batch<I>(&self, words: I) -> Vec<MopsResult<Normalized>>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.batch(words, Self::word_lemma)
     }
 
     /// Нормализация слова. Получение всех возможных результатов.
     ///
     /// Все варианты нормализации возвращаются в отсортированном порядке,
     /// гарантируя единообразие выдачи между запусками.
+    ///
+    /// Неизвестное слово дает пустой результат, см. `NormalizedWords::is_unknown`.
     pub fn normalize(&self, word: &str) -> MopsResult<NormalizedWords> {
-        self.normalized_word(word).map_err(MopsErr::Parse)
+        self.normalize_cached(word).map_err(MopsErr::Parse)
     }
 
-    /// Проверка слова на наличие в словаре.
+    /// Проверка слова на наличие в словаре, в том числе в пользовательских.
     pub fn is_known(&self, word: &str) -> bool {
         let map = &self.fst;
-        map.get(word).is_some()
+        map.get(word).is_some() || self.user_for(word).is_some()
+    }
+
+    /// Словарные разборы слова со строками связанных лемм `OpenCorpora`.
+    ///
+    /// Для слова не из словаря возвращается `ParseErr::UnknownWord`.
+    pub fn lemma_rows(&self, word: &str) -> MopsResult<Vec<LemmaRow>> {
+        self.lemma_rows_word(word).map_err(MopsErr::Parse)
     }
 
     /// Парсинг слова и взятие нужного по индексу набора граммем.
@@ -275,8 +417,11 @@ impl MorphAnalyzer {
 
     /// Приведение к начальной форме слова.
     /// Начальная форма может отличаться от нормализованной.
+    ///
+    /// Здесь и в остальных `inflect_*` и `declension*` для несловарного слова
+    /// возвращается `ParseErr::UnknownWord`.
     pub fn inflect_inizio(&self, word: &str) -> MopsResult<Option<InflectWords>> {
-        self.inflect_word(word, None).map_err(MopsErr::Parse)
+        self.inflect_cached(word, None).map_err(MopsErr::Parse)
     }
 
     /// Приведение слова к нужной форме слова с указанными граммемами.
@@ -285,7 +430,7 @@ impl MorphAnalyzer {
         word: &str,
         grammemes: Vec<Grammem>,
     ) -> MopsResult<Option<InflectWords>> {
-        self.inflect_word(word, Some(grammemes))
+        self.inflect_cached(word, Some(grammemes))
             .map_err(MopsErr::Parse)
     }
 
@@ -299,6 +444,12 @@ impl MorphAnalyzer {
             .map_err(MopsErr::Parse)
     }
 
+    /// Приведение разобранного слова к начальной форме.
+    pub fn inflect_parsed_inizio(&self, parse: ParsedWord) -> MopsResult<Option<InflectWords>> {
+        self.inflect_parsed_words(parse, None)
+            .map_err(MopsErr::Parse)
+    }
+
     /// Полное склонение/спряжение слова по всем формам.
     ///
     /// WARN: Не быстрая функция. Если есть необходимый набор слов,
@@ -326,4 +477,118 @@ impl MorphAnalyzer {
     pub fn declension_parsed(&self, parse: &ParsedWord) -> MopsResult<Option<InflectWords>> {
         self.declension_parsed_word(parse).map_err(MopsErr::Parse)
     }
+
+    /// Разбор ФИО: определение фамилии, имени, отчества и пола.
+    ///
+    /// Части ФИО могут идти в порядке "Фамилия Имя Отчество" или "Имя Отчество Фамилия",
+    /// любая из частей, кроме имени, может отсутствовать.
+    pub fn parse_fio(&self, fio: &str) -> MopsResult<Fio> {
+        self.fio_parse(fio).map_err(MopsErr::Parse)
+    }
+
+    /// Склонение ФИО в нужный падеж с согласованием всех его частей.
+    ///
+    /// Фамилии, которых нет в словаре, склоняются по продуктивным окончаниям (-ов, -ин, -ский, -ко, -их).
+    pub fn inflect_fio(&self, fio: &str, case: Case) -> MopsResult<Fio> {
+        let fio = self.fio_parse(fio).map_err(MopsErr::Parse)?;
+        self.inflect_fio_parsed(&fio, case)
+    }
+
+    /// Склонение разобранного ФИО в нужный падеж.
+    pub fn inflect_fio_parsed(&self, fio: &Fio, case: Case) -> MopsResult<Fio> {
+        self.fio_inflect(fio, case).map_err(MopsErr::Parse)
+    }
+
+    /// Образование отчества от имени в нужном роде и падеже: "Илья" -> "Ильич", "Ильинична".
+    ///
+    /// Имя может быть в любом падеже, если оно есть в словаре.
+    /// Отчество склоняется по словарю, если оно там есть, иначе - по продуктивным окончаниям.
+    pub fn patronymic(&self, first_name: &str, gender: Gender, case: Case) -> MopsResult<String> {
+        self.patronymic_make(first_name, gender, case)
+            .map_err(MopsErr::Parse)
+    }
+
+    /// Запись числа словами: количественное, порядковое или собирательное числительное.
+    ///
+    /// Из граммем берутся падеж, род и число (для порядковых), по умолчанию - им.п., м.р., ед.ч.
+    /// Слова берутся из словаря и склоняются по нему, поддерживаются числа до `MAX_NUMERAL`.
+    ///
+    /// ### Example
+    /// 21, `NumeralKind::Cardinal`, [Genetivus] -> "двадцати одного" \
+    /// 21, `NumeralKind::Ordinal`, [Nominativus, Feminine] -> "двадцать первая".
+    pub fn spell_number(
+        &self,
+        number: u64,
+        kind: NumeralKind,
+        grammemes: Vec<Grammem>,
+    ) -> MopsResult<String> {
+        self.numeral_spell(number, kind, &grammemes)
+            .map_err(MopsErr::Parse)
+    }
+
+    /// Запись словами числа из строки: "21" -> "двадцать один", "21-й" -> "двадцать первый".
+    pub fn spell_number_str(&self, number: &str, grammemes: Vec<Grammem>) -> MopsResult<String> {
+        self.numeral_spell_str(number, &grammemes)
+            .map_err(MopsErr::Parse)
+    }
+
+    /// Распознавание числительного, записанного словами, с падежом и числом словосочетания.
+    ///
+    /// ### Example
+    /// "двадцати пяти тысяч" -> 25000, `Cardinal`, Genetivus, Plural \
+    /// "сто первого" -> 101, `Ordinal`, Genetivus, Singular.
+    pub fn parse_numeral(&self, text: &str) -> MopsResult<Numeral> {
+        self.numeral_parse(text).map_err(MopsErr::Parse)
+    }
+
+    /// Заполнение шаблона сообщения аргументами с согласованием слов по роду и числу.
+    ///
+    /// ### Example
+    /// "{user} {сделал@user} {n} {изменение@n}", user = ("Анна", Feminine), n = 5 \
+    /// -> "Анна сделала 5 изменений".
+    pub fn render(&self, template: &str, args: &HashMap<&str, TemplateArg>) -> MopsResult<String> {
+        template
+            .parse::<Template>()
+            .and_then(|template| self.template_render(&template, args))
+            .map_err(MopsErr::Parse)
+    }
+
+    /// Заполнение заранее разобранного шаблона.
+    pub fn render_template(
+        &self,
+        template: &Template,
+        args: &HashMap<&str, TemplateArg>,
+    ) -> MopsResult<String> {
+        self.template_render(template, args).map_err(MopsErr::Parse)
+    }
+
+    /// Ленивый анализ текста: токены с разборами слов или псевдотегами.
+    ///
+    /// Слова разбираются по словарю, остальным токенам назначаются псевдотеги:
+    /// `PNCT`, `NUMB`, `LATN`, `ROMN`, `UNKN`.
+    pub fn analyze_text<'a>(&'a self, text: &'a str) -> TextAnalysis<'a> {
+        TextAnalysis::new(self, text)
+    }
+
+    /// Нормальные формы всех токенов текста, кроме пунктуации.
+    pub fn lemmatize_text(&self, text: &str) -> MopsResult<Vec<Normalized>> {
+        self.analyze_text(text)
+            .filter(|token| {
+                !matches!(token, Ok(token) if token.pseudo_tag() == Some(PseudoTag::Punctuation))
+            })
+            .map(|token| token.map(|token| token.normal_form()))
+            .collect()
+    }
+
+    /// Анализ текста с выбором одного разбора для каждого слова по контексту.
+    ///
+    /// `tagger` обучается заранее на размеченном корпусе `OpenCorpora`
+    /// и хранится рядом со словарем: `Tagger::open(dir)`.
+    pub fn disambiguate_text<'a>(
+        &self,
+        tagger: &Tagger,
+        text: &'a str,
+    ) -> MopsResult<Vec<AnalyzedToken<'a>>> {
+        self.text_disambiguate(tagger, text).map_err(MopsErr::Parse)
+    }
 }
@@ -1,44 +1,98 @@
 use clap::{Parser, Subcommand};
+#[cfg(feature = "repl")]
+use cli::repl;
+use cli::{
+    diff, info,
+    process::{self, Format},
+    query::{self, Query, Select},
+    stream::{self, Operation},
+};
 use mimalloc::MiMalloc;
-use morph_rs::{grams, morph::grammemes::*, Language, MorphAnalyzer};
+use morph_rs::{morph::grammemes::Grammem, Language, MorphAnalyzer, Tagger};
 use std::{ops::Div, path::PathBuf};
 use tracing::debug;
 
+mod cli;
+
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
 
 #[derive(Debug, Subcommand)]
 enum Commands {
+    /// Разборы слова.
     Parse {
         word: String,
+        #[clap(flatten)]
+        select: Select,
     },
-    ParseGet {
+    /// Нормальные формы слова.
+    Normalize {
         word: String,
-        #[clap(short)]
-        index: usize,
+        #[clap(flatten)]
+        select: Select,
     },
-    ParseTag {
+    /// Формы слова с граммемами `--grams`, без граммем - начальная форма.
+    Inflect {
         word: String,
+        /// Граммемы OpenCorpora через запятую: "plur,gent".
+        #[clap(short, long, value_delimiter = ',')]
+        grams: Vec<Grammem>,
+        #[clap(flatten)]
+        select: Select,
     },
-    Normalize {
+    /// Все формы слова для каждого разбора.
+    Declension {
         word: String,
+        #[clap(flatten)]
+        select: Select,
     },
-    NormalizeGet {
-        word: String,
-        #[clap(short)]
-        index: usize,
+    /// Содержимое словаря: версия OpenCorpora, размеры, память и распределения тегов.
+    Info {
+        /// Сколько самых частых тегов показать.
+        #[clap(long, default_value_t = 20)]
+        top: usize,
+        /// JSON вместо таблиц, со всеми тегами.
+        #[clap(long, default_value_t = false)]
+        json: bool,
     },
-    NormalizeTag {
-        word: String,
+    /// Различия словаря `--db` (нового) со старым: леммы, теги, связи и изменившиеся разборы слов.
+    Diff {
+        /// Каталог старого собранного словаря.
+        old: PathBuf,
+        /// Файл со словами для сравнения, по слову в строке. По умолчанию - все словоформы.
+        #[clap(long)]
+        words: Option<PathBuf>,
+        /// JSON вместо текста.
+        #[clap(long, default_value_t = false)]
+        json: bool,
     },
-    Inflect {
-        word: String,
+    /// Обучение теггера на размеченном корпусе и сохранение рядом со словарем.
+    TrainTagger { corpus: PathBuf },
+    /// Построчная обработка stdin: одна JSON-строка результата на каждую строку входа.
+    Stream {
+        #[clap(long = "op", value_enum, default_value = "parse")]
+        operation: Operation,
+        /// Граммемы OpenCorpora через запятую: фильтр результата или нужная форма для `inflect`.
+        #[clap(short, long, value_delimiter = ',')]
+        grams: Vec<Grammem>,
     },
-    Declension {
-        word: String,
+    /// Интерактивный разбор слов: словарь открывается один раз на всю сессию.
+    #[cfg(feature = "repl")]
+    Repl {
+        /// Файл истории команд, по умолчанию - `~/.morph_rs_history`.
+        #[clap(long)]
+        history: Option<PathBuf>,
     },
-    DeclensionGeography {
-        word: String,
+    /// Токенизация и разметка файлов или каталогов с текстами.
+    Process {
+        /// Файлы и каталоги, каталоги обходятся рекурсивно.
+        #[clap(required = true)]
+        inputs: Vec<PathBuf>,
+        #[clap(short, long, value_enum, default_value = "tsv")]
+        format: Format,
+        /// Каталог для результатов, по умолчанию - stdout.
+        #[clap(short, long)]
+        output: Option<PathBuf>,
     },
 }
 
@@ -55,6 +109,10 @@ struct Args {
     #[clap(short, default_value = "russian")]
     language: Language,
 
+    /// Пользовательские словари поверх основного: `.xml` в формате OpenCorpora или TSV.
+    #[clap(long)]
+    user: Vec<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 
@@ -64,30 +122,45 @@ struct Args {
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt::init();
+    // stdout занят результатами, в том числе JSON-строками `stream`.
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
 
     let Args {
         dictionary,
         db,
         language,
+        user,
         command,
         init,
     } = Args::parse();
 
     let start = std::time::Instant::now();
 
+    // Обучению теггера нужен только корпус: словарь не открывается.
+    let command = match command {
+        Commands::TrainTagger { corpus } => {
+            Tagger::train_opencorpora(corpus)?.save(&db)?;
+            debug!("{:?}", start.elapsed());
+            return Ok(());
+        }
+        command => command,
+    };
+
     let anal = match init {
         true => {
             let anal = MorphAnalyzer::create(dictionary, db.clone(), language)?;
             debug!("Инициализация словаря: {:?}", start.elapsed());
-            MorphAnalyzer::init(anal, db)?
+            MorphAnalyzer::init(anal, &db)?
         }
         false => {
-            let anal = MorphAnalyzer::open(db)?;
+            let anal = MorphAnalyzer::open(&db)?;
             debug!("Словарь открывается за: {:?}", start.elapsed());
             anal
         }
     };
+    let anal = anal.with_user_dictionaries(&user)?;
 
     debug!(
         "Весит: {} Мбайт",
@@ -99,39 +172,73 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let new_start = std::time::Instant::now();
 
     match command {
-        Commands::Parse { word } => println!("{}", anal.parse(&word)?),
-        Commands::ParseGet { word, index } => {
-            println!("{:?}", anal.parse_get(&word, index)?)
+        Commands::Parse { word, select } => {
+            print!("{}", query::render(&anal, &Query::Parse, &word, &select)?)
         }
-        Commands::ParseTag { word } => println!("{:?}", anal.parse_get(&word, 0)?.unwrap().tag()),
-        Commands::Normalize { word } => println!("{}", anal.normalize(&word)?),
-        Commands::NormalizeGet { word, index } => {
-            println!("{:?}", anal.normalize_get(&word, index)?)
+        Commands::Normalize { word, select } => {
+            print!(
+                "{}",
+                query::render(&anal, &Query::Normalize, &word, &select)?
+            )
         }
-        Commands::NormalizeTag { word } => {
-            println!("{:?}", anal.normalize_get(&word, 0)?.unwrap().tag())
+        Commands::Inflect {
+            word,
+            grams,
+            select,
+        } => print!(
+            "{}",
+            query::render(&anal, &Query::Inflect(grams), &word, &select)?
+        ),
+        Commands::Declension { word, select } => {
+            print!(
+                "{}",
+                query::render(&anal, &Query::Declension, &word, &select)?
+            );
+            debug!("{:?}", new_start.elapsed());
         }
-        Commands::Inflect { word } => println!("{}", anal.inflect_inizio(&word)?.unwrap()),
-        Commands::Declension { word } => {
-            let parses = anal.declension(&word).unwrap();
-            debug!("Declension variations {}", parses.len());
-
-            for (i, parse) in parses.iter().enumerate() {
-                println!("For {i} parse:\n{parse}");
+        Commands::Info { top, json } => {
+            let stats = anal.stats();
+            match json {
+                true => println!("{}", serde_json::to_string_pretty(&stats)?),
+                false => print!("{}", info::render(&stats, top)),
             }
-
-            println!("{:?}", new_start.elapsed());
         }
-        Commands::DeclensionGeography { word } => {
-            let parses = anal
-                .parse_grammemes(&word, grams!(Other::Geography))
-                .unwrap()
-                .unwrap();
-            let declension = anal.declension_parsed(&parses).unwrap().unwrap();
-
-            println!("{declension}");
-
-            println!("{:?}", new_start.elapsed());
+        Commands::Diff { old, words, json } => {
+            // Пользовательские словари накладываются на оба словаря, чтобы не попасть в различия.
+            let old = MorphAnalyzer::open(&old)?.with_user_dictionaries(&user)?;
+            let words = words.as_deref().map(diff::read_words).transpose()?;
+            let changes = old.diff(&anal, words.as_deref());
+            match json {
+                true => println!("{}", serde_json::to_string_pretty(&changes)?),
+                false => print!("{}", diff::render(&changes)),
+            }
+            debug!("{:?}", new_start.elapsed());
+        }
+        Commands::TrainTagger { .. } => unreachable!("теггер обучается до открытия словаря"),
+        Commands::Process {
+            inputs,
+            format,
+            output,
+        } => {
+            let summary = process::run(&anal, &inputs, format, output.as_deref())?;
+            eprint!("{summary}");
+            debug!("{:?}", new_start.elapsed());
+        }
+        #[cfg(feature = "repl")]
+        Commands::Repl { history } => {
+            let history = history.or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".morph_rs_history"))
+            });
+            repl::run(&anal, history.as_deref())?;
+        }
+        Commands::Stream { operation, grams } => {
+            stream::run(
+                &anal,
+                operation,
+                &grams,
+                std::io::stdin().lock(),
+                std::io::stdout().lock(),
+            )?;
         }
     };
 
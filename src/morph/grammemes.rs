@@ -1,5 +1,8 @@
 use allocative::Allocative;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::errors::ParseErr;
 
 pub trait ToGrammem {
     fn to_grammem(self) -> Grammem;
@@ -49,6 +52,30 @@ impl Grammem {
     }
 }
 
+impl Grammem {
+    /// Краткое название граммемы в `OpenCorpora`: "gent", "plur", "NOUN".
+    pub fn opencorpora(&self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Граммема по ее краткому названию в `OpenCorpora`: "gent", "plur", "NOUN".
+impl FromStr for Grammem {
+    type Err = ParseErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match serde_json::from_value(serde_json::Value::String(s.to_string())) {
+            Ok(Grammem::Other(Other::Other)) | Err(_) => {
+                Err(ParseErr::UnknownGrammem(s.to_string()))
+            }
+            Ok(grammem) => Ok(grammem),
+        }
+    }
+}
+
 #[rustfmt::skip]
 #[derive(Debug, derive_more::Display, Copy, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[display(fmt = "{}", _0.display())]
@@ -12,6 +12,8 @@ use self::grammemes::{Grammem, Other, ParteSpeech};
 /// Содержит типы хранимых граммем слов
 /// в виде `unit enum`-ов для упрощения хранения.
 pub mod grammemes;
+/// Перевод тегов `OpenCorpora` в части речи и признаки Universal Dependencies.
+pub mod ud;
 /// Модуль сборки данных для Вангования
 /// на основе имеющегося словаря.
 pub(crate) mod vanga;
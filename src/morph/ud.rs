@@ -0,0 +1,156 @@
+use super::grammemes::*;
+use crate::analyzer::PseudoTag;
+
+/// Часть речи Universal Dependencies (UPOS) для тега `OpenCorpora`.
+///
+/// Имена собственные (`Name`, `Surn`, `Patr`, `Geox`, `Orgn`, `Trad`) дают `PROPN`,
+/// местоименные прилагательные (`Apro`) - `DET`. Союзы не делятся на сочинительные и
+/// подчинительные и всегда дают `CCONJ`.
+pub fn upos(tag: &[Grammem]) -> &'static str {
+    let has = |other: Other| tag.contains(&Grammem::Other(other));
+
+    match Grammem::pos_in_tag(tag) {
+        Some(ParteSpeech::Noun)
+            if [
+                Other::Name,
+                Other::Surname,
+                Other::Patronymic,
+                Other::Geography,
+                Other::Organization,
+                Other::Trademark,
+            ]
+            .into_iter()
+            .any(has) =>
+        {
+            "PROPN"
+        }
+        Some(ParteSpeech::Noun) => "NOUN",
+        Some(ParteSpeech::AdjectiveFull) if has(Other::Pronominal) => "DET",
+        Some(
+            ParteSpeech::AdjectiveFull | ParteSpeech::AdjectiveShort | ParteSpeech::Comparative,
+        ) => "ADJ",
+        Some(
+            ParteSpeech::Verb
+            | ParteSpeech::Infinitive
+            | ParteSpeech::ParticipleFull
+            | ParteSpeech::ParticipleShort
+            | ParteSpeech::Gerundive,
+        ) => "VERB",
+        Some(ParteSpeech::Number) => "NUM",
+        Some(ParteSpeech::Adverb | ParteSpeech::Predicative) => "ADV",
+        Some(ParteSpeech::NounPronoun) => "PRON",
+        Some(ParteSpeech::Preposition) => "ADP",
+        Some(ParteSpeech::Conjunction) => "CCONJ",
+        Some(ParteSpeech::Particle) => "PART",
+        Some(ParteSpeech::Interjection) => "INTJ",
+        None => "X",
+    }
+}
+
+/// UPOS для токена с псевдотегом.
+pub fn upos_pseudo(tag: PseudoTag) -> &'static str {
+    match tag {
+        PseudoTag::Punctuation => "PUNCT",
+        PseudoTag::Number | PseudoTag::Roman => "NUM",
+        PseudoTag::Latin | PseudoTag::Unknown => "X",
+    }
+}
+
+/// Признак UD для одной граммемы.
+fn feature(grammem: &Grammem) -> Option<(&'static str, &'static str)> {
+    let feature = match grammem {
+        Grammem::ParteSpeech(pos) => match pos {
+            ParteSpeech::Verb => ("VerbForm", "Fin"),
+            ParteSpeech::Infinitive => ("VerbForm", "Inf"),
+            ParteSpeech::ParticipleFull => ("VerbForm", "Part"),
+            ParteSpeech::ParticipleShort => ("Variant", "Short"),
+            ParteSpeech::Gerundive => ("VerbForm", "Conv"),
+            ParteSpeech::AdjectiveShort => ("Variant", "Short"),
+            ParteSpeech::Comparative => ("Degree", "Cmp"),
+            _ => return None,
+        },
+        Grammem::Animacy(Animacy::Animate) => ("Animacy", "Anim"),
+        Grammem::Animacy(Animacy::Inanimate) => ("Animacy", "Inan"),
+        Grammem::Aspect(Aspect::Perfetto) => ("Aspect", "Perf"),
+        Grammem::Aspect(Aspect::Imperfetto) => ("Aspect", "Imp"),
+        Grammem::Case(case) => match case {
+            Case::Nominativus => ("Case", "Nom"),
+            Case::Genetivus | Case::Gen2 => ("Case", "Gen"),
+            Case::Dativus => ("Case", "Dat"),
+            Case::Accusativus | Case::Acc2 => ("Case", "Acc"),
+            Case::Ablativus => ("Case", "Ins"),
+            Case::Locativus | Case::Loc2 => ("Case", "Loc"),
+            Case::Vocativus => ("Case", "Voc"),
+            Case::Fixed => return None,
+        },
+        Grammem::Gender(Gender::Masculine) => ("Gender", "Masc"),
+        Grammem::Gender(Gender::Feminine) => ("Gender", "Fem"),
+        Grammem::Gender(Gender::Neutral) => ("Gender", "Neut"),
+        Grammem::Mood(Mood::Indicativo) => ("Mood", "Ind"),
+        Grammem::Mood(Mood::Imperativo) => ("Mood", "Imp"),
+        Grammem::Number(Number::Singular) => ("Number", "Sing"),
+        Grammem::Number(Number::Plural) => ("Number", "Plur"),
+        Grammem::Person(Person::First) => ("Person", "1"),
+        Grammem::Person(Person::Second) => ("Person", "2"),
+        Grammem::Person(Person::Third) => ("Person", "3"),
+        Grammem::Tense(Tense::Past) => ("Tense", "Past"),
+        Grammem::Tense(Tense::Present) => ("Tense", "Pres"),
+        Grammem::Tense(Tense::Future) => ("Tense", "Fut"),
+        Grammem::Voice(Voice::Active) => ("Voice", "Act"),
+        Grammem::Voice(Voice::Passive) => ("Voice", "Pass"),
+        Grammem::Other(Other::Superior) => ("Degree", "Sup"),
+        Grammem::Other(Other::Reflessivo) => ("Reflex", "Yes"),
+        _ => return None,
+    };
+    Some(feature)
+}
+
+/// Признаки UD (FEATS) в формате CoNLL-U: "Animacy=Inan|Case=Gen|Gender=Fem|Number=Sing".
+///
+/// Признаки отсортированы по имени, у каждого признака берется первое значение тега,
+/// для тега без признаков возвращается "_".
+pub fn feats(tag: &[Grammem]) -> String {
+    let mut features = Vec::<(&str, &str)>::new();
+    for (name, value) in tag.iter().filter_map(feature) {
+        if !features.iter().any(|(known, _)| *known == name) {
+            features.push((name, value));
+        }
+    }
+    // Причастие - глагольная форма, краткость отмечается отдельно.
+    if tag.contains(&Grammem::ParteSpeech(ParteSpeech::ParticipleShort)) {
+        features.push(("VerbForm", "Part"));
+    }
+
+    if features.is_empty() {
+        return "_".to_string();
+    }
+    features.sort_by_key(|(name, _)| name.to_lowercase());
+    features
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn tag(names: &str) -> Vec<Grammem> {
+        names.split(',').map(|name| name.parse().unwrap()).collect()
+    }
+
+    #[test_case("NOUN,inan,femn,sing,gent" => ("NOUN", "Animacy=Inan|Case=Gen|Gender=Fem|Number=Sing".to_string()))]
+    #[test_case("NOUN,anim,masc,Surn,sing,nomn" => ("PROPN", "Animacy=Anim|Case=Nom|Gender=Masc|Number=Sing".to_string()))]
+    #[test_case("VERB,perf,intr,plur,past,indc" => ("VERB", "Aspect=Perf|Mood=Ind|Number=Plur|Tense=Past|VerbForm=Fin".to_string()))]
+    #[test_case("PRTS,perf,past,pssv,femn,sing" => ("VERB", "Aspect=Perf|Gender=Fem|Number=Sing|Tense=Past|Variant=Short|VerbForm=Part|Voice=Pass".to_string()))]
+    #[test_case("ADJF,Apro,masc,sing,nomn" => ("DET", "Case=Nom|Gender=Masc|Number=Sing".to_string()))]
+    #[test_case("COMP,Qual" => ("ADJ", "Degree=Cmp".to_string()))]
+    #[test_case("NOUN,inan,masc,Fixd,Abbr" => ("NOUN", "Animacy=Inan|Gender=Masc".to_string()))]
+    #[test_case("PREP" => ("ADP", "_".to_string()))]
+    fn test_ud(names: &str) -> (&'static str, String) {
+        let tag = tag(names);
+        (upos(&tag), feats(&tag))
+    }
+}
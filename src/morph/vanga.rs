@@ -71,7 +71,7 @@ impl Vanga {
             });
         }
 
-        vangas.sort_by(|a, b| b.popularity.cmp(&a.popularity));
+        vangas.sort_by_key(|vanga| std::cmp::Reverse(vanga.popularity));
         Ok(vangas)
     }
 }
@@ -0,0 +1,98 @@
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::errors::{MopsErr, MopsResult};
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+/// Размеченный корпус `annot.opcorpora.xml`.
+pub struct AnnotationOpenCorpora {
+    #[serde(rename = "text", default)]
+    pub(crate) texts: Vec<Text>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct Text {
+    #[serde(default)]
+    pub(crate) paragraphs: Option<Paragraphs>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct Paragraphs {
+    #[serde(rename = "paragraph", default)]
+    pub(crate) paragraphs: Vec<Paragraph>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct Paragraph {
+    #[serde(rename = "sentence", default)]
+    pub(crate) sentences: Vec<Sentence>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct Sentence {
+    #[serde(default)]
+    pub(crate) tokens: Option<SentenceTokens>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct SentenceTokens {
+    #[serde(rename = "token", default)]
+    pub(crate) tokens: Vec<CorpusToken>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+/// Токен предложения с вариантами разбора.
+/// Снятая неоднозначность - ровно один вариант.
+pub(crate) struct CorpusToken {
+    #[serde(rename = "@text")]
+    pub(crate) text: String,
+    pub(crate) tfr: Tfr,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct Tfr {
+    #[serde(rename = "v", default)]
+    pub(crate) variants: Vec<Variant>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct Variant {
+    pub(crate) l: CorpusLemma,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct CorpusLemma {
+    #[serde(rename = "@t")]
+    pub(crate) text: String,
+    /// Граммемы строками: в корпусе есть псевдотеги `PNCT`, `NUMB`, которых нет в словаре.
+    #[serde(rename = "g", default)]
+    pub(crate) grams: Vec<CorpusGram>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub(crate) struct CorpusGram {
+    #[serde(rename = "@v")]
+    pub(crate) v: String,
+}
+
+impl AnnotationOpenCorpora {
+    /// Чтение корпуса по переданному пути.
+    pub fn init_from_path<P: AsRef<Path>>(path: P) -> MopsResult<Self> {
+        let buf = std::fs::read_to_string(&path).map_err(|error| MopsErr::File {
+            file: path.as_ref().into(),
+            error,
+        })?;
+        quick_xml::de::from_str(&buf).map_err(MopsErr::XMLde)
+    }
+
+    /// Все предложения корпуса токенами.
+    pub(crate) fn sentences(&self) -> impl Iterator<Item = &[CorpusToken]> {
+        self.texts
+            .iter()
+            .filter_map(|text| text.paragraphs.as_ref())
+            .flat_map(|paragraphs| &paragraphs.paragraphs)
+            .flat_map(|paragraph| &paragraph.sentences)
+            .filter_map(|sentence| sentence.tokens.as_ref())
+            .map(|tokens| tokens.tokens.as_slice())
+    }
+}
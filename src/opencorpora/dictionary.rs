@@ -43,9 +43,9 @@ pub(crate) struct Gram {
     pub(crate) v: Grammem,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Clone)]
 pub struct Links {
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", default)]
     pub links: Vec<Link>,
 }
 
@@ -61,7 +61,7 @@ pub struct Link {
 
 impl PartialOrd for Lemma {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.id.cmp(&other.id))
+        Some(self.cmp(other))
     }
 }
 
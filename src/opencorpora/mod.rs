@@ -1,3 +1,5 @@
+/// Содержит структуры для парсинга размеченного корпуса Opencorpora из xml.
+pub(crate) mod corpus;
 /// Содержит структуры для парсинга словаря Opencorpora из xml.
 pub(crate) mod dictionary;
 
@@ -10,13 +12,17 @@ use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+/// Словарь `OpenCorpora`.
+///
+/// В пользовательских словарях версия, ревизия и связи между леммами могут отсутствовать.
 pub struct DictionaryOpenCorpora {
-    #[serde(rename = "@version")]
+    #[serde(rename = "@version", default)]
     pub(crate) version: String,
-    #[serde(rename = "@revision")]
+    #[serde(rename = "@revision", default)]
     pub(crate) revision: u64,
 
     pub(crate) lemmata: Lemmata,
+    #[serde(default)]
     pub(crate) links: Links,
 }
 
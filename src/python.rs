@@ -0,0 +1,592 @@
+//! Python-модуль `morph_rs` с API, повторяющим `pymorphy2`:
+//! для перехода достаточно заменить `import pymorphy2` на `import morph_rs as pymorphy2`.
+//!
+//! Сборка: `maturin build --release` (см. `pyproject.toml`).
+//!
+//! Отличия от `pymorphy2`: `Parse.inflect` возвращает `None` для предсказанных разборов
+//! (не `DictionaryAnalyzer`), а `normalized` и `lexeme` возвращают сам разбор.
+//! Неизвестное слово разбирается, как в `pymorphy2`, одним разбором с тегом `UNKN`.
+// Макросы pyo3 0.20 раскрываются в impl внутри const-блоков.
+#![allow(non_local_definitions)]
+
+use itertools::Itertools;
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyFrozenSet};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use crate::{
+    analyzer::{PseudoTag, Tag},
+    errors::MopsErr,
+    morph::grammemes::{Animacy, Case, Gender, Grammem, Number, Other, ParteSpeech, Person},
+    InflectWord, Method, MorphAnalyzer, ParsedWord, Vangovanie,
+};
+
+/// Переменная окружения с путем до словаря, если путь не передан явно.
+pub const DICT_ENV: &str = "MORPH_RS_DICT";
+
+fn py_err(err: MopsErr) -> PyErr {
+    PyValueError::new_err(err.to_string())
+}
+
+/// Названия граммем из строки или любой коллекции строк (`set`, `frozenset`, `list`).
+fn grammem_names(grammemes: &PyAny) -> PyResult<Vec<String>> {
+    match grammemes.extract::<String>() {
+        Ok(name) => Ok(vec![name]),
+        Err(_) => grammemes
+            .iter()?
+            .map(|name| name.and_then(PyAny::extract::<String>))
+            .collect(),
+    }
+}
+
+#[pyclass(name = "MorphAnalyzer", module = "morph_rs")]
+/// Морфологический анализатор, аналог `pymorphy2.MorphAnalyzer`.
+pub struct PyMorphAnalyzer {
+    morph: Arc<MorphAnalyzer>,
+}
+
+#[pymethods]
+impl PyMorphAnalyzer {
+    #[new]
+    #[pyo3(signature = (path = None, lang = None))]
+    fn new(path: Option<PathBuf>, lang: Option<&str>) -> PyResult<Self> {
+        if !matches!(lang, None | Some("ru")) {
+            return Err(PyValueError::new_err(format!(
+                "Unsupported language {lang:?}, only 'ru' is available"
+            )));
+        }
+
+        let path = path
+            .or_else(|| std::env::var_os(DICT_ENV).map(PathBuf::from))
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Dictionary path is not set, pass it or set {DICT_ENV}"
+                ))
+            })?;
+        let morph = MorphAnalyzer::open(path).map_err(py_err)?;
+
+        Ok(Self {
+            morph: Arc::new(morph),
+        })
+    }
+
+    /// Все разборы слова, слово приводится к нижнему регистру.
+    ///
+    /// Для неизвестного слова возвращается один разбор с тегом `UNKN`.
+    fn parse(&self, word: &str) -> PyResult<Vec<PyParse>> {
+        let word = word.to_lowercase();
+        let parses = self.morph.parse(&word).map_err(py_err)?.0;
+        if parses.is_empty() {
+            return Ok(vec![PyParse::unknown(&self.morph, word)]);
+        }
+        let score = 1.0 / parses.len() as f64;
+
+        Ok(parses
+            .into_iter()
+            .map(|parse| PyParse::new(&self.morph, parse, score))
+            .collect())
+    }
+
+    /// Нормальные формы слова без повторов.
+    fn normal_forms(&self, word: &str) -> PyResult<Vec<String>> {
+        let mut normal_forms = Vec::new();
+        for parse in self.parse(word)? {
+            let normal_form = parse.parse.normal_form();
+            if !normal_forms.contains(&normal_form) {
+                normal_forms.push(normal_form);
+            }
+        }
+        Ok(normal_forms)
+    }
+
+    /// Теги всех разборов слова.
+    fn tag(&self, word: &str) -> PyResult<Vec<PyTag>> {
+        Ok(self
+            .parse(word)?
+            .into_iter()
+            .map(|parse| parse.tag())
+            .collect())
+    }
+
+    /// Есть ли слово в словаре. При `strict=True` слово не приводится к нижнему регистру.
+    #[pyo3(signature = (word, strict = false))]
+    fn word_is_known(&self, word: &str, strict: bool) -> bool {
+        if strict {
+            self.morph.is_known(word)
+        } else {
+            self.morph.is_known(&word.to_lowercase())
+        }
+    }
+
+    /// Синоним `word_is_known`.
+    fn is_known(&self, word: &str) -> bool {
+        self.word_is_known(word, false)
+    }
+}
+
+#[pyclass(name = "Parse", module = "morph_rs")]
+#[derive(Clone)]
+/// Разбор слова, аналог `pymorphy2.analyzer.Parse`.
+pub struct PyParse {
+    morph: Arc<MorphAnalyzer>,
+    parse: ParsedWord,
+    score: f64,
+    /// Слово не найдено и не предсказано: тег `UNKN`, нормальная форма - само слово.
+    unknown: bool,
+}
+
+impl PyParse {
+    fn new(morph: &Arc<MorphAnalyzer>, parse: ParsedWord, score: f64) -> Self {
+        Self {
+            morph: Arc::clone(morph),
+            parse,
+            score,
+            unknown: false,
+        }
+    }
+
+    fn unknown(morph: &Arc<MorphAnalyzer>, word: String) -> Self {
+        let parse = ParsedWord {
+            word: word.clone(),
+            tags: Tag::new(),
+            normal_form: word,
+            method: Method::Dictionary,
+        };
+        Self {
+            unknown: true,
+            ..Self::new(morph, parse, 1.0)
+        }
+    }
+
+    fn with_inflect(&self, inflect: InflectWord) -> Self {
+        let parse = ParsedWord {
+            word: inflect.inflect_form,
+            tags: inflect.tags,
+            normal_form: inflect.normal_form,
+            method: inflect.method,
+        };
+        Self::new(&self.morph, parse, 1.0)
+    }
+}
+
+#[pymethods]
+impl PyParse {
+    #[getter]
+    fn word(&self) -> String {
+        self.parse.word()
+    }
+
+    #[getter]
+    fn tag(&self) -> PyTag {
+        match self.unknown {
+            true => PyTag::pseudo(PseudoTag::Unknown),
+            false => PyTag::new(self.parse.tag()),
+        }
+    }
+
+    #[getter]
+    fn normal_form(&self) -> String {
+        self.parse.normal_form()
+    }
+
+    #[getter]
+    fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Способ разбора именами анализаторов `pymorphy2`: `(("DictionaryAnalyzer", "стали"),)`.
+    #[getter]
+    fn methods_stack(&self) -> ((String, String),) {
+        let analyzer = match &self.parse.method {
+            _ if self.unknown => "UnknAnalyzer",
+            Method::Dictionary => "DictionaryAnalyzer",
+            Method::Vangovanie(Vangovanie::KnownPrefix(_)) => "KnownPrefixAnalyzer",
+            Method::Vangovanie(Vangovanie::UnknownPrefix(_)) => "UnknownPrefixAnalyzer",
+            Method::Vangovanie(Vangovanie::Postfix) => "KnownSuffixAnalyzer",
+        };
+        ((analyzer.to_string(), self.parse.word()),)
+    }
+
+    #[getter]
+    fn is_known(&self) -> bool {
+        !self.unknown && self.parse.method == Method::Dictionary
+    }
+
+    /// Начальная форма разбора, для несловарного слова - сам разбор.
+    #[getter]
+    fn normalized(&self) -> PyResult<Self> {
+        if !self.is_known() {
+            return Ok(self.clone());
+        }
+        let inizio = self
+            .morph
+            .inflect_parsed_words(self.parse.clone(), None)
+            .map_err(|err| py_err(MopsErr::Parse(err)))?;
+        Ok(inizio
+            .and_then(|words| words.0.into_iter().next())
+            .map(|word| self.with_inflect(word))
+            .unwrap_or_else(|| self.clone()))
+    }
+
+    /// Форма слова с граммемами `required_grammemes`, например, `{"plur", "gent"}`.
+    fn inflect(&self, required_grammemes: &PyAny) -> PyResult<Option<Self>> {
+        if !self.is_known() {
+            return Ok(None);
+        }
+        let grammemes = grammem_names(required_grammemes)?
+            .iter()
+            .map(|grammem| grammem.parse::<Grammem>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+
+        let inflected = self
+            .morph
+            .inflect_parsed(self.parse.clone(), grammemes.clone())
+            .map_err(py_err)?;
+        Ok(inflected
+            .and_then(|words| words.find(grammemes))
+            .map(|word| self.with_inflect(word)))
+    }
+
+    /// Все формы слова.
+    #[getter]
+    fn lexeme(&self) -> PyResult<Vec<Self>> {
+        if !self.is_known() {
+            return Ok(vec![self.clone()]);
+        }
+        let forms = self.morph.declension_parsed(&self.parse).map_err(py_err)?;
+        Ok(forms
+            .map(|words| {
+                words
+                    .0
+                    .into_iter()
+                    .map(|word| self.with_inflect(word))
+                    .collect()
+            })
+            .unwrap_or_else(|| vec![self.clone()]))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        (&self.parse, self.unknown) == (&other.parse, other.unknown)
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (&self.parse, self.unknown).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Parse(word='{word}', tag={tag}, normal_form='{normal_form}', score={score}, methods_stack=(('{analyzer}', '{word}'),))",
+            word = self.parse.word(),
+            tag = self.tag().__repr__(),
+            normal_form = self.parse.normal_form(),
+            score = self.score,
+            analyzer = self.methods_stack().0 .0,
+        )
+    }
+}
+
+#[pyclass(name = "OpencorporaTag", module = "morph_rs")]
+#[derive(Clone)]
+/// Тег разбора, аналог `pymorphy2.tagset.OpencorporaTag`.
+///
+/// Строковый вид как в `pymorphy2`: лексемные граммемы через запятую, через пробел - словоформенные.
+/// Деление восстанавливается по категориям граммем: в словаре теги хранятся целиком.
+pub struct PyTag {
+    tag: Tag,
+    /// Псевдотег вместо граммем, например, `UNKN` для неизвестного слова.
+    pseudo: Option<PseudoTag>,
+}
+
+impl PyTag {
+    fn new(tag: Tag) -> Self {
+        Self { tag, pseudo: None }
+    }
+
+    fn pseudo(pseudo: PseudoTag) -> Self {
+        Self {
+            tag: Tag::new(),
+            pseudo: Some(pseudo),
+        }
+    }
+
+    /// Названия граммем тега вместе с псевдотегом.
+    fn names(&self) -> Vec<String> {
+        self.pseudo
+            .iter()
+            .map(PseudoTag::to_string)
+            .chain(self.tag.iter().map(Grammem::opencorpora))
+            .collect()
+    }
+
+    /// Граммема категории, выбранной `category`.
+    fn category(&self, category: impl Fn(&Grammem) -> bool) -> Option<String> {
+        self.tag
+            .iter()
+            .find(|grammem| category(grammem))
+            .map(Grammem::opencorpora)
+    }
+}
+
+#[pymethods]
+impl PyTag {
+    #[getter(POS)]
+    fn pos(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::ParteSpeech(_)))
+    }
+
+    #[getter]
+    fn animacy(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Animacy(_)))
+    }
+
+    #[getter]
+    fn aspect(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Aspect(_)))
+    }
+
+    #[getter]
+    fn case(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Case(_)))
+    }
+
+    #[getter]
+    fn gender(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Gender(_)))
+    }
+
+    #[getter]
+    fn involvement(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Involvement(_)))
+    }
+
+    #[getter]
+    fn mood(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Mood(_)))
+    }
+
+    #[getter]
+    fn number(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Number(_)))
+    }
+
+    #[getter]
+    fn person(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Person(_)))
+    }
+
+    #[getter]
+    fn tense(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Tense(_)))
+    }
+
+    #[getter]
+    fn transitivity(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Trans(_)))
+    }
+
+    #[getter]
+    fn voice(&self) -> Option<String> {
+        self.category(|grammem| matches!(grammem, Grammem::Voice(_)))
+    }
+
+    /// Все граммемы тега.
+    #[getter]
+    fn grammemes<'py>(&self, py: Python<'py>) -> PyResult<&'py PyFrozenSet> {
+        PyFrozenSet::new(py, &self.names())
+    }
+
+    /// `"NOUN" in tag` или `{"NOUN", "gent"} in tag`.
+    fn __contains__(&self, grammemes: &PyAny) -> PyResult<bool> {
+        let names = grammem_names(grammemes)?;
+
+        let own = self.names();
+        Ok(names.iter().all(|name| own.contains(name)))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        (&self.tag, self.pseudo) == (&other.tag, other.pseudo)
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (&self.tag, self.pseudo).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __str__(&self) -> String {
+        if let Some(pseudo) = self.pseudo {
+            return pseudo.to_string();
+        }
+        let pos = Grammem::pos_in_tag(&self.tag);
+        let (lexeme, form): (Vec<_>, Vec<_>) = self
+            .tag
+            .iter()
+            .sorted_by_key(|grammem| category_order(grammem))
+            .partition(|grammem| is_lexeme(pos, grammem));
+
+        let join =
+            |grammemes: Vec<&Grammem>| grammemes.into_iter().map(Grammem::opencorpora).join(",");
+        match form.is_empty() {
+            true => join(lexeme),
+            false => format!("{} {}", join(lexeme), join(form)),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("OpencorporaTag('{}')", self.__str__())
+    }
+}
+
+/// Граммема относится к лексеме, а не к отдельной словоформе, как в `<l>` словаря `OpenCorpora`.
+fn is_lexeme(pos: Option<ParteSpeech>, grammem: &Grammem) -> bool {
+    use ParteSpeech::{NounPronoun, ParticipleFull, ParticipleShort};
+
+    match grammem {
+        Grammem::ParteSpeech(_) | Grammem::Aspect(_) | Grammem::Trans(_) => true,
+        Grammem::Number(number) => {
+            matches!(number, Number::SingulariaTantum | Number::PluraliaTantum)
+        }
+        Grammem::Case(case) => *case == Case::Fixed,
+        // Род и одушевленность прилагательных и глаголов меняются вместе с формой.
+        Grammem::Animacy(animacy) => {
+            matches!(animacy, Animacy::Both) || pos == Some(ParteSpeech::Noun)
+        }
+        Grammem::Gender(gender) => {
+            matches!(gender, Gender::CommonWavering | Gender::GenderNeutral)
+                || matches!(pos, Some(ParteSpeech::Noun | NounPronoun))
+        }
+        Grammem::Person(person) => {
+            matches!(person, Person::Impersonal | Person::PossibleImpersonal)
+                || pos == Some(NounPronoun)
+        }
+        Grammem::Tense(_) | Grammem::Voice(_) => {
+            matches!(pos, Some(ParticipleFull | ParticipleShort))
+        }
+        Grammem::Mood(_) | Grammem::Involvement(_) => false,
+        Grammem::Other(other) => !matches!(
+            other,
+            Other::Comparative
+                | Other::FormEY
+                | Other::FormOY
+                | Other::FormEJ
+                | Other::FormBE
+                | Other::FormENEN
+                | Other::FormIE
+                | Other::FormBI
+                | Other::ParticipleSH
+                | Other::Error
+                | Other::Distortion
+                | Other::Countable
+                | Other::AfterPreposition
+                | Other::PrepositionVariant
+                | Other::Hypothetical
+        ),
+    }
+}
+
+/// Порядок категорий в тегах `OpenCorpora`: "NOUN,inan,femn sing,gent", "VERB,perf,intr plur,past,indc".
+fn category_order(grammem: &Grammem) -> u8 {
+    match grammem {
+        Grammem::ParteSpeech(_) => 0,
+        Grammem::Animacy(_) => 1,
+        Grammem::Aspect(_) => 2,
+        Grammem::Trans(_) => 3,
+        Grammem::Gender(_) => 4,
+        Grammem::Number(_) => 5,
+        Grammem::Person(_) => 6,
+        Grammem::Case(_) => 7,
+        Grammem::Tense(_) => 8,
+        Grammem::Mood(_) => 9,
+        Grammem::Involvement(_) => 10,
+        Grammem::Voice(_) => 11,
+        Grammem::Other(_) => 12,
+    }
+}
+
+#[pymodule]
+/// Модуль `morph_rs`.
+pub fn morph_rs(_py: Python, module: &PyModule) -> PyResult<()> {
+    module.add_class::<PyMorphAnalyzer>()?;
+    module.add_class::<PyParse>()?;
+    module.add_class::<PyTag>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_infrastructure::infrastructure::analyzer;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_pymorphy_api() {
+        let (_tmp_dir, morph) = analyzer("data/test/tagger_dict.xml");
+        let morph = PyMorphAnalyzer {
+            morph: Arc::new(morph),
+        };
+
+        Python::with_gil(|py| {
+            let module = pyo3::wrap_pymodule!(morph_rs)(py);
+            let globals = PyDict::new(py);
+            globals.set_item("morph_rs", module).unwrap();
+            globals
+                .set_item("morph", Py::new(py, morph).unwrap())
+                .unwrap();
+
+            py.run(
+                r#"
+parses = morph.parse("Стали")
+assert all(isinstance(p, morph_rs.Parse) for p in parses)
+assert {p.normal_form for p in parses} == {"сталь", "стать"}
+assert abs(sum(p.score for p in parses) - 1.0) < 1e-9
+
+noun = next(p for p in parses if p.tag.POS == "NOUN" and p.tag.case == "gent")
+assert "NOUN" in noun.tag and {"NOUN", "sing"} in noun.tag
+assert noun.tag.number == "sing" and noun.tag.tense is None
+assert str(noun.tag) == "NOUN,inan,femn sing,gent"
+assert repr(noun.tag) == "OpencorporaTag('NOUN,inan,femn sing,gent')"
+verb = next(p for p in parses if p.tag.POS == "VERB")
+assert str(verb.tag) == "VERB,perf,intr plur,past,indc"
+assert noun.methods_stack == (("DictionaryAnalyzer", "стали"),)
+assert noun.is_known
+
+assert noun.normalized.word == "сталь"
+assert noun.inflect({"plur", "ablt"}).word == "сталями"
+assert noun.inflect({"VERB"}) is None
+assert "сталью" in {p.word for p in noun.lexeme}
+
+assert morph.normal_forms("стали") == ["сталь", "стать"]
+assert any(t.POS == "VERB" for t in morph.tag("стали"))
+assert morph.word_is_known("ножи") and morph.is_known("Ножи")
+assert not morph.is_known("кваквакря")
+unknown = morph.parse("Кваквакря")
+assert len(unknown) == 1
+assert unknown[0].normal_form == "кваквакря" and unknown[0].score == 1.0
+assert str(unknown[0].tag) == "UNKN" and "UNKN" in unknown[0].tag
+assert unknown[0].tag.POS is None and not unknown[0].is_known
+assert unknown[0].methods_stack == (("UnknAnalyzer", "кваквакря"),)
+assert unknown[0].inflect({"plur"}) is None
+assert morph.normal_forms("кваквакря") == ["кваквакря"]
+assert [str(t) for t in morph.tag("кваквакря")] == ["UNKN"]
+
+try:
+    noun.inflect({"nosuchgram"})
+    raise AssertionError("unknown grammeme accepted")
+except ValueError:
+    pass
+"#,
+                Some(globals),
+                None,
+            )
+            .inspect_err(|err| err.print(py))
+            .unwrap();
+        });
+    }
+}
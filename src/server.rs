@@ -0,0 +1,380 @@
+//! HTTP-сервер с JSON API анализатора: словарь загружается один раз и разделяется между запросами.
+//!
+//! | Метод | Путь           | Запрос                                  | Ответ                                   |
+//! |-------|----------------|-----------------------------------------|-----------------------------------------|
+//! | GET   | `/health`      |                                         | `{"status": "ok"}`                      |
+//! | POST  | `/parse`       | `{"words": [..]}`                       | `{"results": [{"word", "parses"}]}`     |
+//! | POST  | `/normalize`   | `{"words": [..]}`                       | `{"results": [{"word", "parses"}]}`     |
+//! | POST  | `/inflect`     | `{"words": [..], "grammemes": [..]}`    | `{"results": [{"word", "forms"}]}`      |
+//! | POST  | `/declension`  | `{"words": [..]}`                       | `{"results": [{"word", "declensions"}]}`|
+//! | POST  | `/lemmatize`   | `{"texts": [..]}`                       | `{"results": [{"lemmas"}]}`             |
+//!
+//! Ошибка разбора отдельного слова не прерывает пачку: в его результате будет поле `error`.
+//! Без `grammemes` `/inflect` приводит слово к начальной форме.
+use std::{future::Future, sync::Arc};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tracing::info;
+
+use crate::{
+    errors::MopsErr,
+    json::{Item, Outcome},
+    morph::grammemes::Grammem,
+    MorphAnalyzer,
+};
+
+type Morph = Arc<MorphAnalyzer>;
+
+#[derive(Debug, Deserialize)]
+/// Пачка слов.
+pub struct WordsRequest {
+    pub words: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Пачка слов и граммемы нужной формы в обозначениях `OpenCorpora`.
+pub struct InflectRequest {
+    pub words: Vec<String>,
+    #[serde(default)]
+    pub grammemes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+/// Пачка текстов.
+pub struct TextsRequest {
+    pub texts: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+/// Ответ на пачку в порядке входных слов.
+pub struct Results {
+    pub results: Vec<Item>,
+}
+
+/// Ошибка всего запроса.
+pub struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<MopsErr> for ApiError {
+    fn from(err: MopsErr) -> Self {
+        Self(StatusCode::BAD_REQUEST, err.to_string())
+    }
+}
+
+/// Анализ вне асинхронного рантайма: разбор пачки занимает процессор.
+async fn blocking<T: Send + 'static>(
+    morph: Morph,
+    call: impl FnOnce(&MorphAnalyzer) -> T + Send + 'static,
+) -> Result<T, ApiError> {
+    tokio::task::spawn_blocking(move || call(&morph))
+        .await
+        .map_err(|err| ApiError(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn parse(
+    State(morph): State<Morph>,
+    Json(request): Json<WordsRequest>,
+) -> Result<Json<Results>, ApiError> {
+    let results = blocking(morph, move |morph| {
+        let parsed = morph.parse_batch(&request.words);
+        request
+            .words
+            .into_iter()
+            .zip(parsed)
+            .map(|(word, parsed)| Item::new(Some(word), parsed.map(Outcome::parsed)))
+            .collect()
+    })
+    .await?;
+
+    Ok(Json(Results { results }))
+}
+
+async fn normalize(
+    State(morph): State<Morph>,
+    Json(request): Json<WordsRequest>,
+) -> Result<Json<Results>, ApiError> {
+    let results = blocking(morph, move |morph| {
+        let normalized = morph.normalize_batch(&request.words);
+        request
+            .words
+            .into_iter()
+            .zip(normalized)
+            .map(|(word, normalized)| Item::new(Some(word), normalized.map(Outcome::normalized)))
+            .collect()
+    })
+    .await?;
+
+    Ok(Json(Results { results }))
+}
+
+async fn inflect(
+    State(morph): State<Morph>,
+    Json(request): Json<InflectRequest>,
+) -> Result<Json<Results>, ApiError> {
+    let grammemes = request
+        .grammemes
+        .map(|grammemes| {
+            grammemes
+                .iter()
+                .map(|grammem| grammem.parse::<Grammem>())
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .map_err(MopsErr::Parse)?;
+
+    let words = request.words;
+    let results = blocking(morph, move |morph| {
+        words
+            .into_par_iter()
+            .map(|word| {
+                let inflect = match &grammemes {
+                    Some(grammemes) => morph.inflect_forms(&word, grammemes.clone()),
+                    None => morph.inflect_inizio(&word),
+                };
+                Item::new(Some(word), inflect.map(Outcome::inflected))
+            })
+            .collect()
+    })
+    .await?;
+
+    Ok(Json(Results { results }))
+}
+
+async fn declension(
+    State(morph): State<Morph>,
+    Json(request): Json<WordsRequest>,
+) -> Result<Json<Results>, ApiError> {
+    let results = blocking(morph, move |morph| {
+        request
+            .words
+            .into_par_iter()
+            .map(|word| {
+                let declensions = morph.declension(&word).map(Outcome::declensions);
+                Item::new(Some(word), declensions)
+            })
+            .collect()
+    })
+    .await?;
+
+    Ok(Json(Results { results }))
+}
+
+async fn lemmatize(
+    State(morph): State<Morph>,
+    Json(request): Json<TextsRequest>,
+) -> Result<Json<Results>, ApiError> {
+    let results = blocking(morph, move |morph| {
+        request
+            .texts
+            .into_par_iter()
+            .map(|text| Item::new(None, morph.lemmatize_text(&text).map(Outcome::Lemmas)))
+            .collect()
+    })
+    .await?;
+
+    Ok(Json(Results { results }))
+}
+
+/// Маршруты API поверх открытого анализатора.
+pub fn router(morph: Morph) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/parse", post(parse))
+        .route("/normalize", post(normalize))
+        .route("/inflect", post(inflect))
+        .route("/declension", post(declension))
+        .route("/lemmatize", post(lemmatize))
+        .with_state(morph)
+}
+
+/// Обслуживание запросов до завершения `shutdown`, начатые запросы дорабатываются.
+pub async fn serve(
+    listener: TcpListener,
+    morph: Morph,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    info!("Listening on {}", listener.local_addr()?);
+    axum::serve(listener, router(morph))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
+
+/// Сигнал остановки: Ctrl+C или SIGTERM.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!("Couldn't listen for Ctrl+C: {err}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!("Couldn't listen for SIGTERM: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Shutting down");
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::json::WordForm;
+    use crate::test_infrastructure::infrastructure::analyzer;
+    use axum::{body::Body, http::Request};
+    use http_body_util::BodyExt;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tower::ServiceExt;
+
+    fn morph() -> Morph {
+        Arc::new(analyzer("data/test/tagger_dict.xml").1)
+    }
+
+    async fn call(morph: Morph, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let request = match body {
+            Some(body) => Request::post(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => Request::get(uri).body(Body::empty()),
+        };
+        let response = router(morph).oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_endpoints() {
+        let morph = morph();
+
+        let (status, health) = call(morph.clone(), "/health", None).await;
+        assert_eq!(
+            (status, health),
+            (StatusCode::OK, json!({ "status": "ok" }))
+        );
+
+        let (status, parsed) = call(
+            morph.clone(),
+            "/parse",
+            Some(json!({ "words": ["стали", "кваквакря"] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let parsed: Results = serde_json::from_value(parsed).unwrap();
+        assert_eq!(parsed.results.len(), 2);
+        let Outcome::Parses(parses) = &parsed.results[0].outcome else {
+            panic!("{parsed:?}")
+        };
+        assert_eq!(parses.len(), 6);
+        assert!(parses.contains(&WordForm {
+            word: "стали".to_string(),
+            normal_form: "сталь".to_string(),
+            tag: "NOUN,inan,gent,femn,sing".to_string(),
+            method: "Dictionary".to_string(),
+        }));
+        assert_eq!(parsed.results[1].outcome, Outcome::Parses(vec![]));
+
+        let (_, normalized) = call(
+            morph.clone(),
+            "/normalize",
+            Some(json!({ "words": ["стали"] })),
+        )
+        .await;
+        let normal_forms = normalized["results"][0]["parses"].as_array().unwrap();
+        assert!(normal_forms.iter().any(|form| form["word"] == "сталь"));
+
+        let (_, declension) = call(
+            morph.clone(),
+            "/declension",
+            Some(json!({ "words": ["ножи"] })),
+        )
+        .await;
+        assert!(declension["results"][0]["declensions"][0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|form| form["word"] == "ножами"));
+
+        let (_, lemmas) = call(
+            morph,
+            "/lemmatize",
+            Some(json!({ "texts": ["Стали ножи.", ""] })),
+        )
+        .await;
+        assert_eq!(
+            lemmas,
+            json!({ "results": [{ "lemmas": ["сталь", "нож"] }, { "lemmas": [] }] })
+        );
+    }
+
+    #[test_case(json!({ "words": ["стали"], "grammemes": ["plur", "ablt"] }) => (StatusCode::OK, json!({ "word": "стали", "forms": [{
+        "word": "сталями", "normal_form": "сталь", "tag": "NOUN,inan,ablt,femn,plur", "method": "Dictionary"
+    }] })))]
+    #[test_case(json!({ "words": ["кваквакря"], "grammemes": ["plur"] }) => (StatusCode::OK, json!({
+        "word": "кваквакря", "error": "Parse err -> Word 'кваквакря' is not in the dictionary"
+    })))]
+    #[test_case(json!({ "words": ["стали"], "grammemes": ["nosuchgram"] }) => (StatusCode::BAD_REQUEST, Value::Null))]
+    #[test_case(json!({ "word": "стали" }) => (StatusCode::UNPROCESSABLE_ENTITY, Value::Null))]
+    #[tokio::test]
+    async fn test_inflect(request: Value) -> (StatusCode, Value) {
+        let (status, response) = call(morph(), "/inflect", Some(request)).await;
+        (status, response["results"][0].clone())
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, morph(), async {
+            stopped.await.ok();
+        }));
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
+}
@@ -1,12 +1,14 @@
 #[cfg(test)]
 pub(crate) mod infrastructure {
     use crate::{
-        analyzer::{Dictionary, Tag},
-        morph::grammemes::{Grammem, ParteSpeech},
-        opencorpora::DictionaryOpenCorpora,
-        Language,
+        analyzer::Dictionary, opencorpora::DictionaryOpenCorpora, Language, MorphAnalyzer,
     };
-    use std::{collections::HashMap, path::PathBuf};
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::{Mutex, OnceLock, PoisonError},
+    };
+    use tempfile::{tempdir, TempDir};
 
     /// Создание тестового словаря + fst для проверки функций.
     /// Имя файла принимается без формата `.xml`.
@@ -18,196 +20,38 @@ pub(crate) mod infrastructure {
             .unwrap()
     }
 
-    pub(crate) fn is_diff(
-        chars_diff: &mut HashMap<String, Vec<String>>,
-        chars: usize,
-        inizio_chars: &str,
-        normal_form: &str,
-        word: &str,
-        grammemes: Tag,
-    ) {
-        let inizio_chars = inizio_chars.replace('ё', "е");
-        let normal_form = normal_form.replace('ё', "е");
-        let word = word.replace('ё', "е");
-        if !word.starts_with(&inizio_chars)
-            && Grammem::pos_in_tag(&grammemes).unwrap() != ParteSpeech::Comparative
-            && Grammem::pos_in_tag(&grammemes).unwrap() != ParteSpeech::AdjectiveFull
-            && Grammem::pos_in_tag(&grammemes).unwrap() != ParteSpeech::AdjectiveShort
-            && Grammem::pos_in_tag(&grammemes).unwrap() != ParteSpeech::Adverb
-        {
-            let another_chars = word.chars().take(chars).collect::<String>();
-
-            let diff = chars_diff.entry(normal_form).or_default();
-            if !diff.contains(&another_chars) {
-                diff.push(another_chars);
-            }
-        };
+    /// Анализатор по словарю `OpenCorpora` из файла, собранный во временной папке.
+    /// Папка удаляется вместе с `TempDir`.
+    pub(crate) fn analyzer<P: AsRef<Path>>(file_path: P) -> (TempDir, MorphAnalyzer) {
+        let file_path = file_path.as_ref();
+        let tmp_dir = tempdir().unwrap();
+        let dict = MorphAnalyzer::create(file_path, tmp_dir.path(), Language::Russian)
+            .unwrap_or_else(|err| panic!("{}: {err}", file_path.display()));
+        let anal = MorphAnalyzer::init(dict, tmp_dir.path()).unwrap();
+        (tmp_dir, anal)
     }
-}
-
-#[cfg(test)]
-mod compile_const {
-    use crate::{analyzer::dictionary::LemmaDict, opencorpora::DictionaryOpenCorpora};
-    use std::{collections::HashMap, io::Write};
-    use test_case::test_case;
-
-    #[ignore = "Too large dictionary. Manual test for each new lemma's links"]
-    #[test_case(1; "1_prefix_chars")]
-    #[test_case(2; "2_prefix_chars")]
-    #[test_case(3; "3_prefix_chars")]
-    /// Для некоторых нормализованных форм характерно чередование букв в префиксе.
-    /// Для того, чтобы найти все слова для спряжений/склонений, нам необходимо
-    /// проверить также префиксы с чередованием.
-    ///
-    /// В данной функции мы собираем пары "нормальная форма - префикс с чередованием".
-    fn test_form_first_chars(chars: usize) {
-        let mut chars_diff: HashMap<String, Vec<String>> = HashMap::new();
-
-        let dict = DictionaryOpenCorpora::init_from_path("dict.opcorpora.xml").unwrap();
-
-        let link_connotation: HashMap<u64, Vec<u64>> = dict.links.clone().collect_lemmas();
-
-        let mut all_lemmas = std::collections::BTreeSet::new();
-
-        let mut lemmata_map = HashMap::new();
-        for lemma in dict.lemmata.lemmas {
-            all_lemmas.insert(lemma.id);
-            lemmata_map.insert(
-                lemma.id,
-                LemmaDict {
-                    normal_form: lemma.normal_form,
-                    variants: lemma.forms,
-                },
-            );
-        }
-
-        for (lemma_id, variants) in link_connotation {
-            let normal_lemma = &lemmata_map.get(&lemma_id).unwrap();
-
-            let normal_form = normal_lemma.normal_form.text.clone();
-            let inizio_chars = normal_form.chars().take(chars).collect::<String>();
-
-            let inizio_grammemes = normal_lemma.first_tags().unwrap();
-
-            match normal_lemma.variants.as_ref().filter(|v| !v.is_empty()) {
-                None => {}
-                Some(forms) => {
-                    let mut iter = LemmaDict::forms(forms.to_owned().to_owned());
-
-                    let (first_word, mut first_grammemes) = iter.next().unwrap();
-                    first_grammemes.extend(inizio_grammemes.clone());
-                    crate::test_infrastructure::infrastructure::is_diff(
-                        &mut chars_diff,
-                        chars,
-                        &inizio_chars,
-                        &normal_form,
-                        &first_word,
-                        first_grammemes,
-                    );
-
-                    for (word, mut grammemes) in iter {
-                        grammemes.extend(inizio_grammemes.clone());
-                        crate::test_infrastructure::infrastructure::is_diff(
-                            &mut chars_diff,
-                            chars,
-                            &inizio_chars,
-                            &normal_form,
-                            &word,
-                            grammemes,
-                        )
-                    }
-                }
-            }
-
-            all_lemmas.remove(&lemma_id);
-
-            for variant_id in variants {
-                let lemma = lemmata_map.get(&variant_id).unwrap();
-
-                match lemma.variants.as_ref().filter(|v| !v.is_empty()) {
-                    None => {}
-                    Some(forms) => {
-                        let mut iter = LemmaDict::forms(forms.to_owned().to_owned());
-
-                        let (first_word, mut first_grammemes) = iter.next().unwrap();
-
-                        first_grammemes.extend(inizio_grammemes.clone());
-                        crate::test_infrastructure::infrastructure::is_diff(
-                            &mut chars_diff,
-                            chars,
-                            &inizio_chars,
-                            &normal_form,
-                            &first_word,
-                            first_grammemes,
-                        );
-
-                        for (word, mut grammemes) in iter {
-                            grammemes.extend(inizio_grammemes.clone());
-                            crate::test_infrastructure::infrastructure::is_diff(
-                                &mut chars_diff,
-                                chars,
-                                &inizio_chars,
-                                &normal_form,
-                                &word,
-                                grammemes,
-                            )
-                        }
-                    }
-                }
-                all_lemmas.remove(&variant_id);
-            }
-        }
 
-        // Осташиеся не участвующие в link_connotations леммы также нужно обработать.
-        for lost_id in all_lemmas {
-            let lemma = lemmata_map.get(&lost_id).unwrap();
-            let normal_form = lemma.normal_form.text.clone();
-
-            let inizio_chars = normal_form.chars().take(chars).collect::<String>();
-
-            let inizio_grammemes = lemma.first_tags().unwrap();
-
-            match lemma.variants.as_ref().filter(|v| !v.is_empty()) {
-                None => {}
-                Some(forms) => {
-                    let mut iter = LemmaDict::forms(forms.to_owned().to_owned());
-
-                    let (first_word, mut first_grammemes) = iter.next().unwrap();
-                    first_grammemes.extend(inizio_grammemes.clone());
-                    crate::test_infrastructure::infrastructure::is_diff(
-                        &mut chars_diff,
-                        chars,
-                        &inizio_chars,
-                        &normal_form,
-                        &first_word,
-                        first_grammemes,
-                    );
-
-                    for (word, mut grammemes) in iter {
-                        grammemes.extend(inizio_grammemes.clone());
-                        crate::test_infrastructure::infrastructure::is_diff(
-                            &mut chars_diff,
-                            chars,
-                            &inizio_chars,
-                            &normal_form,
-                            &word,
-                            grammemes,
-                        )
-                    }
-                }
-            }
-        }
-
-        eprintln!("For {chars} chars diff_len is {}", chars_diff.len());
+    /// Анализатор по тексту словаря `OpenCorpora`.
+    pub(crate) fn xml_analyzer(xml: &str) -> (TempDir, MorphAnalyzer) {
+        let xml_dir = tempdir().unwrap();
+        let file_path = xml_dir.path().join("dict.xml");
+        std::fs::write(&file_path, xml).unwrap();
+        analyzer(file_path)
+    }
 
-        let file = &format!("different_inizio_{chars}");
-        let mut file = std::fs::File::create(file).unwrap();
-        for (k, v) in chars_diff {
-            for val in v {
-                file.write_all(format!("(\"{k}\", \"{val}\"),\n").as_bytes())
-                    .unwrap();
-            }
-        }
+    /// Общий для всех тестов анализатор по словарю из файла: собирается один раз на словарь.
+    pub(crate) fn shared_analyzer(file_path: &'static str) -> &'static MorphAnalyzer {
+        static ANALYZERS: OnceLock<Mutex<HashMap<&str, &(TempDir, MorphAnalyzer)>>> =
+            OnceLock::new();
+
+        let mut analyzers = ANALYZERS
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        &analyzers
+            .entry(file_path)
+            .or_insert_with(|| Box::leak(Box::new(analyzer(file_path))))
+            .1
     }
 }
 
@@ -396,7 +240,7 @@ mod experiments {
         let dict = DictionaryOpenCorpora::init_from_path("dict.opcorpora.xml").unwrap();
 
         let mut lemmata = dict.lemmata.lemmas;
-        lemmata.sort_by(|l, k| l.id.cmp(&k.id));
+        lemmata.sort_by_key(|l| l.id);
 
         for (i, Lemma { id, .. }) in lemmata.iter().enumerate() {
             assert_eq!(i as u64, *id - 1);
@@ -473,3 +317,98 @@ mod experiments {
         assert_eq!(SMALLVANGA, *ninety);
     }
 }
+
+#[cfg(test)]
+/// Публичные методы анализатора не паникуют ни на каком входе.
+mod totality {
+    use super::infrastructure::shared_analyzer;
+    use crate::{
+        analyzer::declension::alphabet_stream,
+        morph::grammemes::{Case, Gender, Grammem, Number, ParteSpeech},
+        MorphAnalyzer,
+    };
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    /// Смесь кириллицы, латиницы, цифр, знаков и комбинируемых символов.
+    const RUSSIAN_LIKE: &str = "[а-яёА-ЯЁa-zA-Z0-9 .,!?:;{}@#'ʼ\\-\u{0301}\u{0308}]{0,16}";
+
+    /// Вызов всех методов, принимающих строку. Результаты не важны, важно отсутствие паники.
+    fn call_all(anal: &MorphAnalyzer, text: &str) {
+        let plural = vec![Grammem::Number(Number::Plural)];
+
+        if let Ok(parses) = anal.parse(text) {
+            for parse in parses.0 {
+                let _ = anal.inflect_parsed(parse.clone(), plural.clone());
+                let _ = anal.declension_parsed(&parse);
+            }
+        }
+        if let Ok(parses) = anal.parse_iter(text) {
+            parses.for_each(drop);
+        }
+        let _ = anal.normalize(text);
+        let _ = anal.is_known(text);
+        let _ = anal.parse_get(text, 0);
+        let _ = anal.parse_grammemes(text, plural.clone());
+        let _ = anal.normalize_get(text, 0);
+        let _ = anal.normalize_grammemes(text, plural.clone());
+        let _ = anal.inflect_inizio(text);
+        let _ = anal.inflect_forms(text, plural.clone());
+        let _ = anal.declension(text);
+        let _ = anal.declension_get(text, 0);
+
+        if let Ok(fio) = anal.parse_fio(text) {
+            let _ = anal.inflect_fio_parsed(&fio, Case::Dativus);
+        }
+        let _ = anal.inflect_fio(text, Case::Genetivus);
+        let _ = anal.patronymic(text, Gender::Feminine, Case::Nominativus);
+
+        let _ = anal.spell_number_str(text, vec![]);
+        let _ = anal.parse_numeral(text);
+        let _ = anal.render(text, &HashMap::new());
+
+        anal.analyze_text(text).for_each(drop);
+        let _ = anal.lemmatize_text(text);
+        let words = text.split(' ').collect::<Vec<_>>();
+        let _ = anal.parse_batch(&words);
+        let _ = anal.normalize_batch(&words);
+        let _ = anal.lemmatize_batch(&words);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(512))]
+
+        #[test]
+        fn test_total_any_string(text in any::<String>()) {
+            call_all(shared_analyzer("data/test/template_dict.xml"), &text);
+        }
+
+        #[test]
+        fn test_total_russian_like(text in RUSSIAN_LIKE) {
+            call_all(shared_analyzer("data/test/template_dict.xml"), &text);
+            call_all(shared_analyzer("data/test/template_dict.xml"), &text.to_lowercase());
+        }
+
+        #[test]
+        fn test_total_numeral_dict(text in RUSSIAN_LIKE) {
+            call_all(shared_analyzer("data/test/numeral_dict.xml"), &text.to_lowercase());
+        }
+
+        #[test]
+        fn test_total_long_words(text in "[а-яё]{60,300}") {
+            call_all(shared_analyzer("data/test/template_dict.xml"), &text);
+        }
+
+        #[test]
+        /// Префиксный поиск строится для любого непустого слова, в т.ч. без части речи в теге.
+        fn test_alphabet_stream(word in any::<String>(), adjective in any::<bool>()) {
+            let tag = if adjective {
+                [Grammem::ParteSpeech(ParteSpeech::AdjectiveFull)].into_iter().collect()
+            } else {
+                Default::default()
+            };
+            let prefixes = alphabet_stream(&word, &word, tag, &Default::default());
+            prop_assert_eq!(prefixes.is_ok(), !word.is_empty());
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Знаки препинания вне ASCII, встречающиеся в русских текстах.
+const PUNCTUATION: &str = "«»„“”‘’‚‹›…–—―‐‑‒·¡¿";
+/// Знаки, повторы которых образуют один токен: "...", "?!".
+const SENTENCE_END: &str = ".!?…";
+/// Символы, которые не могут завершать URL.
+const URL_TRAILING: &str = ".,;:!?)]}\"'»…";
+/// Символы локальной части и домена e-mail, кроме букв и цифр.
+const EMAIL_CHARS: &str = "._%+-@";
+
+#[derive(Debug, Clone, Copy, derive_more::Display, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Тип токена.
+pub enum TokenKind {
+    /// Слово кириллицей, в том числе через дефис ("кто-то") и сокращения ("т.е.").
+    Word,
+    /// Слово латиницей.
+    Latin,
+    /// Число: "42", "3,14", "21-й".
+    Number,
+    /// Знак препинания или их повтор: ",", "...", "?!".
+    Punctuation,
+    Url,
+    Email,
+    /// "#тег"
+    Hashtag,
+    /// "@пользователь"
+    Mention,
+    Emoji,
+    /// Слова других алфавитов и прочие символы.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+/// Токен текста со смещениями в байтах и символах исходной строки.
+pub struct Token<'a> {
+    text: &'a str,
+    kind: TokenKind,
+    bytes: Range<usize>,
+    chars: Range<usize>,
+}
+
+impl<'a> Token<'a> {
+    /// Текст токена - срез исходной строки.
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    /// Смещение в байтах: `&text[token.bytes()] == token.text()`.
+    pub fn bytes(&self) -> Range<usize> {
+        self.bytes.to_owned()
+    }
+
+    /// Смещение в символах (`char`).
+    pub fn chars(&self) -> Range<usize> {
+        self.chars.to_owned()
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Ленивый итератор по токенам текста. Пробельные символы пропускаются.
+pub struct Tokens<'a> {
+    text: &'a str,
+    byte: usize,
+    char: usize,
+}
+
+/// Разбиение текста на токены.
+///
+/// ### Example
+/// "Т.е. кто-то написал 3,5 строки" -> "Т.е." `Word`, "кто-то" `Word`, "написал" `Word`,
+/// "3,5" `Number`, "строки" `Word`.
+pub fn tokenize(text: &str) -> Tokens<'_> {
+    Tokens {
+        text,
+        byte: 0,
+        char: 0,
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.byte..];
+        let trimmed = rest.trim_start();
+        self.char += rest[..rest.len() - trimmed.len()].chars().count();
+        self.byte += rest.len() - trimmed.len();
+
+        let first = trimmed.chars().next()?;
+        let (len, kind) = scan_url(trimmed)
+            .or_else(|| scan_email(trimmed))
+            .or_else(|| scan_tag(trimmed, first))
+            .or_else(|| scan_emoji(trimmed, first))
+            .or_else(|| scan_number(trimmed, first))
+            .or_else(|| scan_word(trimmed, first))
+            .or_else(|| scan_punctuation(trimmed, first))
+            .unwrap_or((first.len_utf8(), TokenKind::Other));
+
+        let text = &trimmed[..len];
+        let chars = text.chars().count();
+        let token = Token {
+            text,
+            kind,
+            bytes: self.byte..self.byte + len,
+            chars: self.char..self.char + chars,
+        };
+
+        self.byte += len;
+        self.char += chars;
+        Some(token)
+    }
+}
+
+/// "https://...", "http://...", "www...." до пробела без завершающей пунктуации.
+fn scan_url(text: &str) -> Option<(usize, TokenKind)> {
+    let prefix = ["https://", "http://", "www."].into_iter().find(|prefix| {
+        text.get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })?;
+
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    let url = text[..end].trim_end_matches(|c| URL_TRAILING.contains(c));
+    (url.len() > prefix.len()).then_some((url.len(), TokenKind::Url))
+}
+
+/// "name@domain.ru": одна "@", домен с точкой и буквенной зоной.
+fn scan_email(text: &str) -> Option<(usize, TokenKind)> {
+    let end = text
+        .find(|c: char| !c.is_alphanumeric() && !EMAIL_CHARS.contains(c))
+        .unwrap_or(text.len());
+    let email = text[..end].trim_end_matches(['.', '-']);
+
+    let (local, domain) = email.split_once('@')?;
+    let (host, zone) = domain.rsplit_once('.')?;
+    let valid = !local.is_empty()
+        && !domain.contains('@')
+        && !host.is_empty()
+        && !host.starts_with('.')
+        && zone.chars().count() >= 2
+        && zone.chars().all(char::is_alphabetic);
+
+    valid.then_some((email.len(), TokenKind::Email))
+}
+
+/// "#хештег", "@упоминание".
+fn scan_tag(text: &str, first: char) -> Option<(usize, TokenKind)> {
+    let kind = match first {
+        '#' => TokenKind::Hashtag,
+        '@' => TokenKind::Mention,
+        _ => return None,
+    };
+
+    let tail = &text[1..];
+    let end = tail
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(tail.len());
+    (end > 0).then_some((end + 1, kind))
+}
+
+/// Эмодзи с модификаторами, последовательностями через ZWJ и флагами.
+fn scan_emoji(text: &str, first: char) -> Option<(usize, TokenKind)> {
+    if !is_emoji(first) {
+        return None;
+    }
+
+    let mut chars = text.char_indices().skip(1).peekable();
+    let mut end = first.len_utf8();
+
+    if is_regional_indicator(first) {
+        if let Some((idx, c)) = chars.next_if(|(_, c)| is_regional_indicator(*c)) {
+            end = idx + c.len_utf8();
+        }
+        return Some((end, TokenKind::Emoji));
+    }
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\u{FE0F}' | '\u{20E3}' | '\u{1F3FB}'..='\u{1F3FF}' => end = idx + c.len_utf8(),
+            '\u{200D}' => match chars.next_if(|(_, c)| is_emoji(*c)) {
+                Some((idx, c)) => end = idx + c.len_utf8(),
+                None => break,
+            },
+            _ => break,
+        }
+    }
+
+    Some((end, TokenKind::Emoji))
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c, '\u{1F000}'..='\u{1FAFF}' | '\u{2600}'..='\u{27BF}' | '\u{2B00}'..='\u{2BFF}')
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// Число с дробной частью или разрядами через точку/запятую
+/// и необязательным окончанием после дефиса: "3,14", "1.000.000", "21-й".
+fn scan_number(text: &str, first: char) -> Option<(usize, TokenKind)> {
+    if !first.is_ascii_digit() {
+        return None;
+    }
+
+    let digits = |text: &str| {
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len())
+    };
+    let mut end = digits(text);
+
+    while let Some(tail) = text[end..].strip_prefix(['.', ',']) {
+        match digits(tail) {
+            0 => break,
+            len => end += 1 + len,
+        }
+    }
+
+    if let Some(tail) = text[end..].strip_prefix('-') {
+        let suffix = tail.find(|c: char| !is_cyrillic(c)).unwrap_or(tail.len());
+        let chars = tail[..suffix].chars().count();
+        if (1..=3).contains(&chars) && !tail[suffix..].starts_with(char::is_alphanumeric) {
+            end += 1 + suffix;
+        }
+    }
+
+    Some((end, TokenKind::Number))
+}
+
+/// Слово с дефисами и апострофами внутри ("кто-то", "don't")
+/// или сокращение из строчных букв с точками ("т.е.", "т.д.").
+fn scan_word(text: &str, first: char) -> Option<(usize, TokenKind)> {
+    if !first.is_alphabetic() {
+        return None;
+    }
+
+    let mut chars = text.char_indices().peekable();
+    let mut end = 0;
+    while let Some((idx, c)) = chars.next() {
+        let joined = matches!(c, '-' | '\'' | '’')
+            && chars.peek().is_some_and(|(_, next)| next.is_alphanumeric());
+        if !(is_word_char(c) || joined) {
+            break;
+        }
+        end = idx + c.len_utf8();
+    }
+    end += abbreviation(&text[..end], &text[end..]);
+
+    let word = &text[..end];
+    let kind = if word.chars().any(is_cyrillic) {
+        TokenKind::Word
+    } else if word
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .all(|c| matches!(c, 'a'..='z' | 'A'..='Z' | '\u{00C0}'..='\u{024F}'))
+    {
+        TokenKind::Latin
+    } else {
+        TokenKind::Other
+    };
+
+    Some((end, kind))
+}
+
+/// Длина продолжения сокращения после первой части: "т" + ".е." -> 3.
+///
+/// Сокращение - не меньше двух частей до трех строчных букв, каждая с точкой.
+/// Одиночные "г.", "ул." не отличить от конца предложения, они не склеиваются.
+fn abbreviation(word: &str, tail: &str) -> usize {
+    let part = |part: &str| {
+        (1..=3).contains(&part.chars().count()) && part.chars().all(char::is_lowercase)
+    };
+    if !part(word) {
+        return 0;
+    }
+
+    let mut len = 0;
+    while let Some(next) = tail[len..].strip_prefix('.') {
+        let letters = next
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(next.len());
+        if !part(&next[..letters]) || !next[letters..].starts_with('.') {
+            break;
+        }
+        len += letters + 2;
+    }
+    len
+}
+
+fn is_word_char(c: char) -> bool {
+    // Комбинируемые диакритические знаки: ударение внутри слова.
+    c.is_alphanumeric() || matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+fn is_cyrillic(c: char) -> bool {
+    matches!(c, '\u{0400}'..='\u{04FF}')
+}
+
+/// Знак препинания, повторы ".!?…" склеиваются.
+fn scan_punctuation(text: &str, first: char) -> Option<(usize, TokenKind)> {
+    if !first.is_ascii_punctuation() && !PUNCTUATION.contains(first) {
+        return None;
+    }
+
+    let end = match SENTENCE_END.contains(first) {
+        true => text
+            .find(|c: char| !SENTENCE_END.contains(c))
+            .unwrap_or(text.len()),
+        false => first.len_utf8(),
+    };
+    Some((end, TokenKind::Punctuation))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_case::test_case;
+
+    fn kinds(text: &str) -> Vec<(&str, TokenKind)> {
+        tokenize(text)
+            .map(|token| (token.text(), token.kind()))
+            .collect()
+    }
+
+    #[test_case("Привет, мир!" => vec![
+        ("Привет", TokenKind::Word), (",", TokenKind::Punctuation),
+        ("мир", TokenKind::Word), ("!", TokenKind::Punctuation),
+    ]; "sentence")]
+    #[test_case("Кто-то, т.е. Петя, и т.д." => vec![
+        ("Кто-то", TokenKind::Word), (",", TokenKind::Punctuation),
+        ("т.е.", TokenKind::Word), ("Петя", TokenKind::Word), (",", TokenKind::Punctuation),
+        ("и", TokenKind::Word), ("т.д.", TokenKind::Word),
+    ]; "hyphen_abbreviation")]
+    #[test_case("Ушёл. Да" => vec![
+        ("Ушёл", TokenKind::Word), (".", TokenKind::Punctuation), ("Да", TokenKind::Word),
+    ]; "sentence_end")]
+    #[test_case("3,14 и 1.000.000 на 21-й день" => vec![
+        ("3,14", TokenKind::Number), ("и", TokenKind::Word), ("1.000.000", TokenKind::Number),
+        ("на", TokenKind::Word), ("21-й", TokenKind::Number), ("день", TokenKind::Word),
+    ]; "numbers")]
+    #[test_case("don't use COVID-19 лол" => vec![
+        ("don't", TokenKind::Latin), ("use", TokenKind::Latin),
+        ("COVID-19", TokenKind::Latin), ("лол", TokenKind::Word),
+    ]; "latin")]
+    #[test_case("См. https://example.com/a?b=1, или www.ya.ru." => vec![
+        ("См", TokenKind::Word), (".", TokenKind::Punctuation),
+        ("https://example.com/a?b=1", TokenKind::Url), (",", TokenKind::Punctuation),
+        ("или", TokenKind::Word), ("www.ya.ru", TokenKind::Url), (".", TokenKind::Punctuation),
+    ]; "urls")]
+    #[test_case("пиши на ivan.petrov@mail.ru или @ivan #вопрос" => vec![
+        ("пиши", TokenKind::Word), ("на", TokenKind::Word),
+        ("ivan.petrov@mail.ru", TokenKind::Email), ("или", TokenKind::Word),
+        ("@ivan", TokenKind::Mention), ("#вопрос", TokenKind::Hashtag),
+    ]; "email_tags")]
+    #[test_case("Ура👍🏻!!! 👨‍👩‍👧 🇷🇺" => vec![
+        ("Ура", TokenKind::Word), ("👍🏻", TokenKind::Emoji), ("!!!", TokenKind::Punctuation),
+        ("👨‍👩‍👧", TokenKind::Emoji), ("🇷🇺", TokenKind::Emoji),
+    ]; "emoji")]
+    #[test_case("«Да» — αβγ ©" => vec![
+        ("«", TokenKind::Punctuation), ("Да", TokenKind::Word), ("»", TokenKind::Punctuation),
+        ("—", TokenKind::Punctuation), ("αβγ", TokenKind::Other), ("©", TokenKind::Other),
+    ]; "other")]
+    #[test_case(" \n\t" => Vec::<(&str, TokenKind)>::new(); "whitespace")]
+    fn test_tokenize(text: &str) -> Vec<(&str, TokenKind)> {
+        kinds(text)
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = "Ёж  ест\u{301} 5 яблок 🍎.";
+        let tokens = tokenize(text).collect::<Vec<_>>();
+
+        for token in &tokens {
+            assert_eq!(&text[token.bytes()], token.text());
+            let chars = text.chars().collect::<Vec<_>>();
+            assert_eq!(
+                chars[token.chars()].iter().collect::<String>(),
+                token.text()
+            );
+        }
+
+        let offsets = tokens
+            .iter()
+            .map(|token| (token.chars(), token.bytes()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            vec![
+                (0..2, 0..4),
+                (4..8, 6..14),
+                (9..10, 15..16),
+                (11..16, 17..27),
+                (17..18, 28..32),
+                (18..19, 32..33),
+            ]
+        );
+    }
+}
@@ -0,0 +1,73 @@
+/*
+ * Проверка C-интерфейса morph-rs на тестовом словаре.
+ *
+ * Запуск: make -C tests/c
+ */
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "morph_rs.h"
+
+static int contains(const MopsWords *words, const char *word, const char *tag) {
+    for (size_t i = 0; i < mops_words_len(words); i++) {
+        const MopsWord *item = mops_words_get(words, i);
+        if (strcmp(item->word, word) == 0 && (tag == NULL || strcmp(item->tag, tag) == 0)) {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <dictionary dir>\n", argv[0]);
+        return 2;
+    }
+
+    MopsAnalyzer *morph = NULL;
+    MopsStatus status = mops_open(argv[1], &morph);
+    if (status != MOPS_STATUS_OK) {
+        fprintf(stderr, "mops_open: %d %s\n", status, mops_last_error());
+        return 1;
+    }
+
+    MopsWords *words = NULL;
+
+    assert(mops_parse(morph, "стали", &words) == MOPS_STATUS_OK);
+    assert(mops_words_len(words) == 6);
+    assert(contains(words, "стали", "NOUN,inan,gent,femn,sing"));
+    assert(mops_words_get(words, mops_words_len(words)) == NULL);
+    for (size_t i = 0; i < mops_words_len(words); i++) {
+        const MopsWord *word = mops_words_get(words, i);
+        printf("%s\t%s\t%s\t%s\n", word->word, word->normal_form, word->tag, word->method);
+    }
+    mops_words_free(words);
+
+    assert(mops_normalize(morph, "стали", &words) == MOPS_STATUS_OK);
+    assert(contains(words, "сталь", NULL));
+    mops_words_free(words);
+
+    assert(mops_inflect(morph, "стали", "NOUN,plur,ablt", &words) == MOPS_STATUS_OK);
+    assert(contains(words, "сталями", NULL));
+    mops_words_free(words);
+
+    assert(mops_inflect(morph, "стали", NULL, &words) == MOPS_STATUS_OK);
+    assert(contains(words, "сталь", NULL));
+    mops_words_free(words);
+
+    assert(mops_parse(morph, "кваквакря", &words) == MOPS_STATUS_OK);
+    assert(mops_words_len(words) == 0);
+    mops_words_free(words);
+
+    assert(mops_inflect(morph, "кваквакря", "plur", &words) == MOPS_STATUS_UNKNOWN_WORD);
+    assert(words == NULL);
+    assert(strstr(mops_last_error(), "кваквакря") != NULL);
+
+    assert(mops_inflect(morph, "стали", "plur,nosuchgram", &words) == MOPS_STATUS_UNKNOWN_GRAMMEM);
+    assert(mops_parse(morph, NULL, &words) == MOPS_STATUS_NULL_POINTER);
+
+    mops_free(morph);
+    puts("ok");
+    return 0;
+}